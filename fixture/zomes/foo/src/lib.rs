@@ -13,6 +13,11 @@ fn bar(_: ()) -> ExternResult<TestString> {
     Ok(TestString("bar".to_string()))
 }
 
+#[hdk_extern]
+fn echo(input: TestString) -> ExternResult<TestString> {
+    Ok(input)
+}
+
 #[hdk_extern]
 fn emitter(_: ()) -> ExternResult<TestString> {
     match emit_signal(&TestString("i am a signal".to_string())) {
@@ -20,3 +25,47 @@ fn emitter(_: ()) -> ExternResult<TestString> {
         Err(e) => Err(e),
     }
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PageRequest<I, C> {
+    pub input: I,
+    pub cursor: Option<C>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Page<T, C> {
+    pub items: Vec<T>,
+    pub next: Option<C>,
+}
+
+/// Returns the numbers from 0 up to the input, three per page.
+#[hdk_extern]
+fn numbers(request: PageRequest<u32, u32>) -> ExternResult<Page<u32, u32>> {
+    let start = request.cursor.unwrap_or(0);
+    let end = (start + 3).min(request.input);
+    Ok(Page {
+        items: (start..end).collect(),
+        next: (end < request.input).then_some(end),
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListSince {
+    pub since: Option<Timestamp>,
+}
+
+#[hdk_extern]
+fn sync_create(record: TestString) -> ExternResult<TestString> {
+    Ok(record)
+}
+
+#[hdk_extern]
+fn sync_update(record: TestString) -> ExternResult<TestString> {
+    Ok(record)
+}
+
+/// Lists the same remote record on every call, whatever the time asked for.
+#[hdk_extern]
+fn sync_list_since(_: ListSince) -> ExternResult<Vec<TestString>> {
+    Ok(vec![TestString("remote".to_string())])
+}
@@ -0,0 +1,77 @@
+use std::collections::BTreeSet;
+
+/// The trust level of an [AdminWebsocket](crate::AdminWebsocket), which limits the operations it
+/// sends.
+///
+/// A node may expose several admin interfaces, such as a full one on localhost and a limited one
+/// behind a proxy. Tagging each client with the role of its interface makes the client refuse
+/// operations outside that role with [ConductorApiError::OperationNotAllowed](crate::ConductorApiError::OperationNotAllowed),
+/// before they reach the conductor. This guards against mistakes in the application, it is not a
+/// replacement for restricting the interface itself.
+///
+/// Operations are named by their request type on the wire, such as `list_apps` or
+/// `install_app`, which are also the operation names in
+/// [ConnectionStats](crate::ConnectionStats).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AdminRole {
+    pub name: String,
+    /// The allowed operations, or `None` if all operations are allowed.
+    allowed_operations: Option<BTreeSet<String>>,
+}
+
+/// The operations of [AdminRole::read_only], which query the conductor without changing it.
+pub const READ_ONLY_ADMIN_OPERATIONS: &[&str] = &[
+    "agent_info",
+    "dump_conductor_state",
+    "dump_full_state",
+    "dump_network_metrics",
+    "dump_network_stats",
+    "dump_state",
+    "get_compatible_cells",
+    "get_dna_definition",
+    "list_app_interfaces",
+    "list_apps",
+    "list_cell_ids",
+    "list_dnas",
+    "storage_info",
+];
+
+impl AdminRole {
+    /// A role which allows all operations. This is the role of a client unless another is set
+    /// with [AdminWebsocket::with_role](crate::AdminWebsocket::with_role).
+    pub fn full(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            allowed_operations: None,
+        }
+    }
+
+    /// A role which only allows the given operations.
+    pub fn restricted(
+        name: impl Into<String>,
+        allowed_operations: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            allowed_operations: Some(allowed_operations.into_iter().map(Into::into).collect()),
+        }
+    }
+
+    /// A role which only allows the [READ_ONLY_ADMIN_OPERATIONS].
+    pub fn read_only(name: impl Into<String>) -> Self {
+        Self::restricted(name, READ_ONLY_ADMIN_OPERATIONS.iter().copied())
+    }
+
+    pub fn allows(&self, operation: &str) -> bool {
+        match &self.allowed_operations {
+            Some(allowed_operations) => allowed_operations.contains(operation),
+            None => true,
+        }
+    }
+}
+
+impl Default for AdminRole {
+    fn default() -> Self {
+        Self::full("admin")
+    }
+}
@@ -1,29 +1,71 @@
+use crate::admin_role::{AdminRole, READ_ONLY_ADMIN_OPERATIONS};
+use crate::clone_ref::CloneRef;
+use crate::compat::{unsupported_by_conductor, Interface};
+use crate::connect::{connect_websocket, websocket_url_target, ConnectOptions, ConnectionInfo};
 use crate::error::{ConductorApiError, ConductorApiResult};
+use crate::grants::{CapGrantInfo, GrantRecord, GrantsExport, ZOME_CALL_SIGNING_KEY_TAG};
+use crate::history::{RequestHistory, RequestRecord};
+use crate::install_validation::validate_install_app_payload;
+#[cfg(feature = "simulated_latency")]
+use crate::latency::{LatencyInjector, SimulatedLatency};
+use crate::network_stats::NetworkStats;
+use crate::reconnect::{AbortOnDropHandle, Connection, ConnectionState};
+use crate::stats::{ConnectionStats, StatsRecorder};
+use crate::wire::{operation_name, PayloadSize};
 use anyhow::Result;
-use holo_hash::DnaHash;
+use futures::{
+    future::{self, BoxFuture, Either},
+    stream, Stream,
+};
+use holo_hash::{ActionHash, DnaHash};
 use holochain_conductor_api::{
-    AdminRequest, AdminResponse, AppAuthenticationTokenIssued, AppInfo, AppInterfaceInfo,
-    AppStatusFilter, CompatibleCells, IssueAppAuthenticationTokenPayload, RevokeAgentKeyPayload,
-    StorageInfo,
+    AdminRequest, AdminResponse, AppAuthenticationToken, AppAuthenticationTokenIssued, AppInfo,
+    AppInfoStatus, AppInterfaceInfo, AppStatusFilter, CompatibleCells, FullStateDump,
+    IssueAppAuthenticationTokenPayload, RevokeAgentKeyPayload, StorageInfo,
 };
 use holochain_types::websocket::AllowedOrigins;
 use holochain_types::{
-    dna::AgentPubKey,
-    prelude::{CellId, DeleteCloneCellPayload, InstallAppPayload, UpdateCoordinatorsPayload},
+    dna::{AgentPubKey, DnaBundle},
+    prelude::{
+        CellId, DeleteCloneCellPayload, DnaModifiersOpt, DnaSource, InstallAppPayload,
+        InstalledAppId, RegisterDnaPayload, SerializedBytesError, UpdateCoordinatorsPayload,
+        YamlProperties,
+    },
 };
-use holochain_websocket::{connect, WebsocketConfig, WebsocketSender};
+use holochain_websocket::WebsocketConfig;
 use holochain_zome_types::{
     capability::GrantedFunctions,
-    prelude::{DnaDef, GrantZomeCallCapabilityPayload, Record},
+    prelude::{Action, DnaDef, Entry, GrantZomeCallCapabilityPayload, Record},
 };
 use kitsune_p2p_types::agent_info::AgentInfoSigned;
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
-use std::{net::ToSocketAddrs, sync::Arc};
-use tokio::task::JoinHandle;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    net::ToSocketAddrs,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
+/// A client for the admin interface of a conductor.
+///
+/// All requests take `&self`, so several requests can be in flight at once from shared
+/// references. Responses are matched to their requests, whatever order they arrive in.
+///
+/// Cloning is cheap: clones share the connection, its statistics and debug history, and the
+/// record of granted capabilities, so each task of a server can hold its own clone. The
+/// [AdminRole] is set per clone. The connection is closed when the last clone is dropped.
+#[derive(Clone)]
 pub struct AdminWebsocket {
-    tx: WebsocketSender,
-    poll_handle: JoinHandle<()>,
+    connection: Arc<Connection>,
+    stats: Arc<StatsRecorder>,
+    history: Arc<RequestHistory>,
+    grants: Arc<Mutex<Vec<GrantRecord>>>,
+    role: AdminRole,
+    #[cfg(feature = "simulated_latency")]
+    latency: Arc<LatencyInjector>,
+    _abort_handle: Arc<AbortOnDropHandle>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -32,6 +74,49 @@ pub struct EnableAppResponse {
     pub errors: Vec<(CellId, String)>,
 }
 
+/// An agent key used by installed apps, see [AdminWebsocket::list_agents].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExistingAgent {
+    pub agent_pub_key: AgentPubKey,
+    pub installed_app_ids: Vec<InstalledAppId>,
+}
+
+/// Progress reported by [AdminWebsocket::enable_app_with_progress].
+#[derive(Clone, Debug)]
+pub enum EnableAppProgress {
+    /// The app is still being enabled.
+    Pending {
+        /// The status of the app, or `None` if it wasn't found.
+        status: Option<AppInfoStatus>,
+        elapsed: Duration,
+    },
+    /// The app has been enabled.
+    Enabled(EnableAppResponse),
+}
+
+/// Which changes to revert when changing whether several apps are enabled, see
+/// [AdminWebsocket::set_apps_enabled].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum RevertPolicy {
+    /// Keep the changes that succeeded.
+    #[default]
+    Never,
+    /// Revert the changes that succeeded if changing any app failed.
+    IfAnyFails,
+    /// Revert the changes that succeeded if changing any of the given apps failed.
+    IfCriticalFails(BTreeSet<InstalledAppId>),
+}
+
+/// The outcome of [AdminWebsocket::set_apps_enabled].
+#[derive(Debug)]
+pub struct SetAppsEnabledReport {
+    /// The result of changing each app. Apps which were already enabled or disabled as requested
+    /// are reported as succeeded.
+    pub results: BTreeMap<InstalledAppId, ConductorApiResult<()>>,
+    /// The apps whose change was reverted, with the result of reverting it.
+    pub reverted: BTreeMap<InstalledAppId, ConductorApiResult<()>>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AuthorizeSigningCredentialsPayload {
     pub cell_id: CellId,
@@ -65,20 +150,78 @@ impl AdminWebsocket {
     pub async fn connect_with_config(
         socket_addr: impl ToSocketAddrs,
         websocket_config: Arc<WebsocketConfig>,
+    ) -> Result<Self> {
+        Self::connect_with_options(
+            socket_addr,
+            ConnectOptions::default().with_websocket_config(websocket_config),
+        )
+        .await
+    }
+
+    /// Connect to a Conductor API AdminWebsocket at a `ws://` or `wss://` URL, such as
+    /// `wss://conductor.example.org`.
+    ///
+    /// `wss://` URLs are for conductors behind a TLS-terminating reverse proxy and need the `tls`
+    /// feature. The host of the URL is the name the certificate of the proxy must be valid for,
+    /// unless the options configure TLS already. URLs with a path are not supported, since the
+    /// client always connects to the root path.
+    pub async fn connect_url(url: &str, options: ConnectOptions) -> Result<Self> {
+        let (target, options) = websocket_url_target(url, options)?;
+        Self::connect_with_options(target, options).await
+    }
+
+    /// Connect to a Conductor API AdminWebsocket with custom [ConnectOptions].
+    ///
+    /// Use this to configure how connecting is retried, for example to fail immediately when the
+    /// conductor isn't reachable:
+    ///
+    /// ```rust,no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// use holochain_client::{AdminWebsocket, ConnectOptions, ConnectRetry};
+    /// use std::net::Ipv4Addr;
+    /// let options = ConnectOptions::default().with_retry(ConnectRetry::Disabled);
+    /// let admin_ws = AdminWebsocket::connect_with_options((Ipv4Addr::LOCALHOST, 30_000), options).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn connect_with_options(
+        socket_addr: impl ToSocketAddrs,
+        options: ConnectOptions,
     ) -> Result<Self> {
         let addr = socket_addr
             .to_socket_addrs()?
             .next()
             .expect("invalid websocket address");
 
-        let (tx, mut rx) = again::retry(|| connect(websocket_config.clone(), addr)).await?;
+        let (tx, mut rx) = connect_websocket(addr, &options).await?;
+        let connection = Arc::new(Connection::new(addr, options, tx));
 
         // WebsocketReceiver needs to be polled in order to receive responses
         // from remote to sender requests.
-        let poll_handle =
-            tokio::task::spawn(async move { while rx.recv::<AdminResponse>().await.is_ok() {} });
+        let poll_handle = tokio::task::spawn({
+            let connection = connection.clone();
+            async move {
+                loop {
+                    while rx.recv::<AdminResponse>().await.is_ok() {}
+                    match connection.reconnect(|_| async { Ok(()) }).await {
+                        Some(new_rx) => rx = new_rx,
+                        None => break,
+                    }
+                }
+            }
+        });
 
-        Ok(Self { tx, poll_handle })
+        Ok(Self {
+            connection,
+            stats: Arc::default(),
+            history: Arc::default(),
+            grants: Arc::default(),
+            role: AdminRole::default(),
+            #[cfg(feature = "simulated_latency")]
+            latency: Arc::default(),
+            _abort_handle: Arc::new(AbortOnDropHandle(poll_handle.abort_handle())),
+        })
     }
 
     /// Issue an app authentication token for the specified app.
@@ -97,6 +240,23 @@ impl AdminWebsocket {
         }
     }
 
+    /// Revoke an app authentication token, so it can no longer be used to authenticate new app
+    /// connections.
+    ///
+    /// Connections that were already authenticated with the token are not closed.
+    pub async fn revoke_app_authentication_token(
+        &self,
+        token: AppAuthenticationToken,
+    ) -> ConductorApiResult<()> {
+        let response = self
+            .send(AdminRequest::RevokeAppAuthenticationToken(token))
+            .await?;
+        match response {
+            AdminResponse::AppAuthenticationTokenRevoked => Ok(()),
+            _ => unreachable!("Unexpected response {:?}", response),
+        }
+    }
+
     pub async fn generate_agent_pub_key(&self) -> ConductorApiResult<AgentPubKey> {
         // Create agent key in Lair and save it in file
         let response = self.send(AdminRequest::GenerateAgentPubKey).await?;
@@ -106,6 +266,35 @@ impl AdminWebsocket {
         }
     }
 
+    /// Export an agent's private key for backup.
+    ///
+    /// The conductor's admin API does not expose key export, so this always fails with
+    /// [ConductorApiError::UnsupportedOperation]. Keys are held in Lair, from which seeds can be
+    /// exported directly. The method exists so that backup features can detect the missing support
+    /// and pick up support once the conductor offers it.
+    pub async fn export_agent_key(&self, _agent_key: AgentPubKey) -> ConductorApiResult<Vec<u8>> {
+        Err(ConductorApiError::UnsupportedOperation {
+            operation: "export_agent_key",
+            reason: "the conductor admin API does not expose key export, export the seed from Lair instead",
+        })
+    }
+
+    /// Delete an agent key from the conductor's keystore.
+    ///
+    /// The conductor's admin API does not expose key deletion, so this always fails with
+    /// [ConductorApiError::UnsupportedOperation]. To retire a key created for a throwaway agent,
+    /// revoke it with [AdminWebsocket::revoke_agent_key] and uninstall its apps.
+    pub async fn delete_agent_key(&self, _agent_key: AgentPubKey) -> ConductorApiResult<()> {
+        Err(ConductorApiError::UnsupportedOperation {
+            operation: "delete_agent_key",
+            reason: "the conductor admin API does not expose key deletion, revoke the key instead",
+        })
+    }
+
+    /// Revoke an agent key for an app, which makes the source chains of the app's cells read-only.
+    ///
+    /// With DPKI, the key is also revoked in the deep key service. Returns the cells for which
+    /// revoking failed, with the error.
     pub async fn revoke_agent_key(
         &self,
         app_id: String,
@@ -124,6 +313,10 @@ impl AdminWebsocket {
 
     /// List all app interfaces attached to the conductor.
     ///
+    /// Each [AppInterfaceInfo] contains the interface's port, the origins it accepts connections
+    /// from and the app it is bound to, if any. This is enough to audit which apps are exposed to
+    /// which origins.
+    ///
     /// See the documentation for [AdminWebsocket::attach_app_interface] to understand the content
     /// of `AppInterfaceInfo` and help you to select an appropriate interface to connect to.
     pub async fn list_app_interfaces(&self) -> ConductorApiResult<Vec<AppInterfaceInfo>> {
@@ -179,6 +372,17 @@ impl AdminWebsocket {
         }
     }
 
+    /// Install an app from a bundle.
+    ///
+    /// The payload supports all install options of the conductor:
+    ///
+    /// - `agent_key`: the agent to install the app for. If `None`, the conductor generates a new
+    ///   agent key, which is returned in the [AppInfo].
+    /// - `roles_settings`: per-role settings, such as DNA modifiers and membrane proofs for
+    ///   provisioned roles, or the existing cell to use for roles which depend on another app.
+    /// - `network_seed`: a network seed for all roles which don't override it in their settings.
+    /// - `ignore_genesis_failure`: install the app even if genesis fails for some of its cells,
+    ///   for example because a membrane proof is provided later.
     pub async fn install_app(&self, payload: InstallAppPayload) -> ConductorApiResult<AppInfo> {
         let msg = AdminRequest::InstallApp(Box::new(payload));
         let response = self.send(msg).await?;
@@ -189,6 +393,67 @@ impl AdminWebsocket {
         }
     }
 
+    /// Validate the payload with [validate_install_app_payload] and install the app if it is
+    /// valid, failing with [ConductorApiError::InvalidInstallAppPayload] otherwise.
+    ///
+    /// This catches mistakes with a precise error instead of a generic conductor error.
+    pub async fn install_app_checked(
+        &self,
+        payload: InstallAppPayload,
+    ) -> ConductorApiResult<AppInfo> {
+        validate_install_app_payload(&payload)
+            .await
+            .map_err(ConductorApiError::InvalidInstallAppPayload)?;
+        self.install_app(payload).await
+    }
+
+    /// List the agent keys used by installed apps, with the apps that use each key.
+    ///
+    /// Agents are listed in the order the conductor lists their first app.
+    pub async fn list_agents(&self) -> ConductorApiResult<Vec<ExistingAgent>> {
+        let mut agents: Vec<ExistingAgent> = Vec::new();
+        for app in self.list_apps(None).await? {
+            match agents
+                .iter_mut()
+                .find(|agent| agent.agent_pub_key == app.agent_pub_key)
+            {
+                Some(agent) => agent.installed_app_ids.push(app.installed_app_id),
+                None => agents.push(ExistingAgent {
+                    agent_pub_key: app.agent_pub_key,
+                    installed_app_ids: vec![app.installed_app_id],
+                }),
+            }
+        }
+
+        Ok(agents)
+    }
+
+    /// Install an app for the first existing agent that matches `select`, the way a launcher
+    /// installs several apps for one identity.
+    ///
+    /// The agent key of the payload is replaced with the selected agent's key. Fails with
+    /// [ConductorApiError::NoMatchingAgent] without installing the app if no agent matches.
+    pub async fn install_app_for_existing_agent(
+        &self,
+        mut payload: InstallAppPayload,
+        select: impl Fn(&ExistingAgent) -> bool,
+    ) -> ConductorApiResult<AppInfo> {
+        let agent = self
+            .list_agents()
+            .await?
+            .into_iter()
+            .find(|agent| select(agent))
+            .ok_or(ConductorApiError::NoMatchingAgent)?;
+        payload.agent_key = Some(agent.agent_pub_key);
+
+        self.install_app(payload).await
+    }
+
+    /// Uninstall an app.
+    ///
+    /// The conductor refuses to uninstall an app whose cells other apps depend on, such as apps
+    /// installed with a role that uses an existing cell of this app. Set `force` to uninstall the
+    /// app anyway, which breaks the dependent apps.
     pub async fn uninstall_app(
         &self,
         installed_app_id: String,
@@ -219,6 +484,49 @@ impl AdminWebsocket {
         }
     }
 
+    /// Enable an app and report its status while it is being enabled.
+    ///
+    /// Enabling an app with slow genesis, such as membrane proof validation or joining the network,
+    /// can take a while. While the request is in flight, the status of the app is polled every
+    /// `poll_interval` and reported as [EnableAppProgress::Pending]. The stream ends with
+    /// [EnableAppProgress::Enabled], or with the error returned when enabling the app. Errors while
+    /// polling the status are reported without ending the stream.
+    pub fn enable_app_with_progress(
+        &self,
+        installed_app_id: String,
+        poll_interval: Duration,
+    ) -> impl Stream<Item = ConductorApiResult<EnableAppProgress>> + '_ {
+        let started_at = Instant::now();
+        let enable: BoxFuture<'_, ConductorApiResult<EnableAppResponse>> =
+            Box::pin(self.enable_app(installed_app_id.clone()));
+
+        stream::unfold(Some(enable), move |enable| {
+            let installed_app_id = installed_app_id.clone();
+            async move {
+                let mut enable = enable?;
+                let tick = Box::pin(tokio::time::sleep(poll_interval));
+                match future::select(&mut enable, tick).await {
+                    Either::Left((result, _)) => {
+                        Some((result.map(EnableAppProgress::Enabled), None))
+                    }
+                    Either::Right(_) => {
+                        let status = self.list_apps(None).await.map(|apps| {
+                            let status = apps
+                                .into_iter()
+                                .find(|app| app.installed_app_id == installed_app_id)
+                                .map(|app| app.status);
+                            EnableAppProgress::Pending {
+                                status,
+                                elapsed: started_at.elapsed(),
+                            }
+                        });
+                        Some((status, Some(enable)))
+                    }
+                }
+            }
+        })
+    }
+
     pub async fn disable_app(&self, installed_app_id: String) -> ConductorApiResult<()> {
         let msg = AdminRequest::DisableApp { installed_app_id };
         let response = self.send(msg).await?;
@@ -229,6 +537,85 @@ impl AdminWebsocket {
         }
     }
 
+    /// Enable or disable several apps concurrently, for example during a maintenance window.
+    ///
+    /// Apps which are already enabled or disabled as requested are left alone. If the `revert`
+    /// policy applies to the failures, the apps that were changed successfully are changed back,
+    /// so the apps end up as they were as far as possible. This is not atomic: other clients can
+    /// observe the intermediate states.
+    pub async fn set_apps_enabled(
+        &self,
+        apps: BTreeMap<InstalledAppId, bool>,
+        revert: RevertPolicy,
+    ) -> ConductorApiResult<SetAppsEnabledReport> {
+        let enabled_before: BTreeMap<InstalledAppId, bool> = self
+            .list_apps(None)
+            .await?
+            .into_iter()
+            .map(|app| (app.installed_app_id, is_enabled(&app.status)))
+            .collect();
+
+        let outcomes = future::join_all(apps.iter().map(|(installed_app_id, enabled)| {
+            let unchanged = enabled_before.get(installed_app_id) == Some(enabled);
+            async move {
+                if unchanged {
+                    return Ok(false);
+                }
+                self.set_app_enabled(installed_app_id.clone(), *enabled)
+                    .await
+                    .map(|()| true)
+            }
+        }))
+        .await;
+
+        let mut results = BTreeMap::new();
+        let mut changed = Vec::new();
+        for ((installed_app_id, enabled), outcome) in apps.into_iter().zip(outcomes) {
+            if let Ok(true) = outcome {
+                changed.push((installed_app_id.clone(), enabled));
+            }
+            results.insert(installed_app_id, outcome.map(|_| ()));
+        }
+
+        let should_revert = match &revert {
+            RevertPolicy::Never => false,
+            RevertPolicy::IfAnyFails => results.values().any(Result::is_err),
+            RevertPolicy::IfCriticalFails(critical) => critical
+                .iter()
+                .any(|installed_app_id| matches!(results.get(installed_app_id), Some(Err(_)))),
+        };
+        let mut reverted = BTreeMap::new();
+        if should_revert {
+            let outcomes = future::join_all(changed.iter().map(|(installed_app_id, enabled)| {
+                self.set_app_enabled(installed_app_id.clone(), !enabled)
+            }))
+            .await;
+            reverted = changed
+                .into_iter()
+                .map(|(installed_app_id, _)| installed_app_id)
+                .zip(outcomes)
+                .collect();
+        }
+
+        Ok(SetAppsEnabledReport { results, reverted })
+    }
+
+    async fn set_app_enabled(
+        &self,
+        installed_app_id: InstalledAppId,
+        enabled: bool,
+    ) -> ConductorApiResult<()> {
+        if enabled {
+            self.enable_app(installed_app_id).await.map(|_| ())
+        } else {
+            self.disable_app(installed_app_id).await
+        }
+    }
+
+    /// List the ids of all running cells of the conductor.
+    ///
+    /// Cells of disabled apps are not included, while cells that are not part of any app, such as
+    /// the DPKI service cell, are.
     pub async fn list_cell_ids(&self) -> ConductorApiResult<Vec<CellId>> {
         let response = self.send(AdminRequest::ListCellIds).await?;
         match response {
@@ -237,6 +624,45 @@ impl AdminWebsocket {
         }
     }
 
+    /// List the hashes of all DNAs registered with the conductor.
+    pub async fn list_dnas(&self) -> ConductorApiResult<Vec<DnaHash>> {
+        let response = self.send(AdminRequest::ListDnas).await?;
+        match response {
+            AdminResponse::DnasListed(dnas) => Ok(dnas),
+            _ => unreachable!("Unexpected response {:?}", response),
+        }
+    }
+
+    /// Register a DNA with the conductor, so that apps can refer to it by its hash.
+    pub async fn register_dna(&self, payload: RegisterDnaPayload) -> ConductorApiResult<DnaHash> {
+        let msg = AdminRequest::RegisterDna(Box::new(payload));
+        let response = self.send(msg).await?;
+        match response {
+            AdminResponse::DnaRegistered(hash) => Ok(hash),
+            _ => unreachable!("Unexpected response {:?}", response),
+        }
+    }
+
+    /// Read a `.dna` bundle from a local file and register it with the given modifier overrides.
+    ///
+    /// The bundle is read by the client and sent to the conductor, so the file does not need to be
+    /// accessible to the conductor. Returns the hash of the DNA with the modifiers applied.
+    pub async fn register_dna_from_file(
+        &self,
+        path: impl AsRef<Path>,
+        modifiers: DnaModifiersOpt<YamlProperties>,
+    ) -> Result<DnaHash> {
+        let bundle = DnaBundle::read_from_file(path.as_ref()).await?;
+        let hash = self
+            .register_dna(RegisterDnaPayload {
+                modifiers,
+                source: DnaSource::Bundle(Box::new(bundle)),
+            })
+            .await?;
+
+        Ok(hash)
+    }
+
     pub async fn get_dna_definition(&self, hash: DnaHash) -> ConductorApiResult<DnaDef> {
         let msg = AdminRequest::GetDnaDefinition(Box::new(hash));
         let response = self.send(msg).await?;
@@ -258,19 +684,101 @@ impl AdminWebsocket {
         }
     }
 
+    /// Grant a zome call capability.
+    ///
+    /// Granted capabilities are recorded, so they can be exported with
+    /// [AdminWebsocket::export_grants].
     pub async fn grant_zome_call_capability(
         &self,
         payload: GrantZomeCallCapabilityPayload,
     ) -> ConductorApiResult<()> {
-        let msg = AdminRequest::GrantZomeCallCapability(Box::new(payload));
+        let msg = AdminRequest::GrantZomeCallCapability(Box::new(payload.clone()));
         let response = self.send(msg).await?;
 
         match response {
-            AdminResponse::ZomeCallCapabilityGranted => Ok(()),
+            AdminResponse::ZomeCallCapabilityGranted => {
+                self.grants.lock().push(payload.into());
+                Ok(())
+            }
             _ => unreachable!("Unexpected response {:?}", response),
         }
     }
 
+    /// Export the capability grants created through this connection, including the grants for
+    /// signing credentials created by [AdminWebsocket::authorize_signing_credentials].
+    pub fn export_grants(&self) -> GrantsExport {
+        GrantsExport {
+            grants: self.grants.lock().clone(),
+        }
+    }
+
+    /// Grant the capabilities of an export, for example to restore signing access after the
+    /// conductor has been rebuilt.
+    pub async fn import_grants(&self, export: GrantsExport) -> ConductorApiResult<()> {
+        for grant in export.grants {
+            self.grant_zome_call_capability(grant.into()).await?;
+        }
+
+        Ok(())
+    }
+
+    /// List the capability grants on the source chain of a cell which have not been deleted or
+    /// updated, including the grants created for signing credentials.
+    ///
+    /// The conductor has no request for listing grants, so they are read from a full state dump of
+    /// the cell. The dump includes every record of the source chain, which makes this expensive
+    /// for cells with long chains. The integrated DHT ops of the dump are skipped with a cursor
+    /// past the last op.
+    pub async fn list_capability_grants(
+        &self,
+        cell_id: CellId,
+    ) -> ConductorApiResult<Vec<CapGrantInfo>> {
+        let records = self
+            .dump_full_state(cell_id, Some(i64::MAX as u64))
+            .await?
+            .source_chain_dump
+            .records;
+
+        let superseded: HashSet<&ActionHash> = records
+            .iter()
+            .filter_map(|record| match &record.action {
+                Action::Delete(delete) => Some(&delete.deletes_address),
+                Action::Update(update) => Some(&update.original_action_address),
+                _ => None,
+            })
+            .collect();
+
+        Ok(records
+            .iter()
+            .filter(|record| !superseded.contains(&record.action_address))
+            .filter_map(|record| match &record.entry {
+                Some(Entry::CapGrant(cap_grant)) => Some(CapGrantInfo {
+                    action_hash: record.action_address.clone(),
+                    created_at: record.action.timestamp(),
+                    cap_grant: cap_grant.clone(),
+                }),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// The capability grants for signing credentials on the source chain of a cell, such as those
+    /// created by [AdminWebsocket::authorize_signing_credentials].
+    ///
+    /// Grants are recognised by their [ZOME_CALL_SIGNING_KEY_TAG], see
+    /// [CapGrantInfo::is_signing_key_grant].
+    pub async fn list_signing_key_grants(
+        &self,
+        cell_id: CellId,
+    ) -> ConductorApiResult<Vec<CapGrantInfo>> {
+        Ok(self
+            .list_capability_grants(cell_id)
+            .await?
+            .into_iter()
+            .filter(CapGrantInfo::is_signing_key_grant)
+            .collect())
+    }
+
     pub async fn delete_clone_cell(
         &self,
         payload: DeleteCloneCellPayload,
@@ -283,6 +791,20 @@ impl AdminWebsocket {
         }
     }
 
+    /// Delete a disabled clone cell of an app, referenced by any of the types a [CloneRef]
+    /// converts from.
+    pub async fn delete_clone(
+        &self,
+        app_id: InstalledAppId,
+        clone: impl Into<CloneRef>,
+    ) -> ConductorApiResult<()> {
+        self.delete_clone_cell(DeleteCloneCellPayload {
+            app_id,
+            clone_cell_id: clone.into().into(),
+        })
+        .await
+    }
+
     pub async fn storage_info(&self) -> ConductorApiResult<StorageInfo> {
         let msg = AdminRequest::StorageInfo;
         let response = self.send(msg).await?;
@@ -292,6 +814,47 @@ impl AdminWebsocket {
         }
     }
 
+    /// Dump the state of a cell, including its source chain and integration state, as JSON.
+    pub async fn dump_state(&self, cell_id: CellId) -> ConductorApiResult<String> {
+        let msg = AdminRequest::DumpState {
+            cell_id: Box::new(cell_id),
+        };
+        let response = self.send(msg).await?;
+        match response {
+            AdminResponse::StateDumped(state) => Ok(state),
+            _ => unreachable!("Unexpected response {:?}", response),
+        }
+    }
+
+    /// Dump the conductor's configuration and state, such as installed apps and interfaces, as JSON.
+    pub async fn dump_conductor_state(&self) -> ConductorApiResult<String> {
+        let response = self.send(AdminRequest::DumpConductorState).await?;
+        match response {
+            AdminResponse::ConductorStateDumped(state) => Ok(state),
+            _ => unreachable!("Unexpected response {:?}", response),
+        }
+    }
+
+    /// Dump the full state of a cell, including its DHT ops.
+    ///
+    /// Large dumps can be pulled incrementally: pass the `dht_ops_cursor` of the previous dump's
+    /// integration state to only include the DHT ops that were integrated after it.
+    pub async fn dump_full_state(
+        &self,
+        cell_id: CellId,
+        dht_ops_cursor: Option<u64>,
+    ) -> ConductorApiResult<FullStateDump> {
+        let msg = AdminRequest::DumpFullState {
+            cell_id: Box::new(cell_id),
+            dht_ops_cursor,
+        };
+        let response = self.send(msg).await?;
+        match response {
+            AdminResponse::FullStateDumped(state) => Ok(state),
+            _ => unreachable!("Unexpected response {:?}", response),
+        }
+    }
+
     pub async fn dump_network_stats(&self) -> ConductorApiResult<String> {
         let msg = AdminRequest::DumpNetworkStats;
         let response = self.send(msg).await?;
@@ -301,6 +864,32 @@ impl AdminWebsocket {
         }
     }
 
+    /// Get the statistics of the network transport, parsed from [AdminWebsocket::dump_network_stats].
+    ///
+    /// The conductor does not report gossip statistics in this dump.
+    pub async fn network_stats(&self) -> ConductorApiResult<NetworkStats> {
+        let stats = self.dump_network_stats().await?;
+        serde_json::from_str(&stats).map_err(|err| {
+            ConductorApiError::SerializationError(SerializedBytesError::Deserialize(
+                err.to_string(),
+            ))
+        })
+    }
+
+    /// Dump the gossip and fetch metrics of the network as JSON, for all DNAs or only for the
+    /// given DNA.
+    pub async fn dump_network_metrics(
+        &self,
+        dna_hash: Option<DnaHash>,
+    ) -> ConductorApiResult<String> {
+        let msg = AdminRequest::DumpNetworkMetrics { dna_hash };
+        let response = self.send(msg).await?;
+        match response {
+            AdminResponse::NetworkMetricsDumped(metrics) => Ok(metrics),
+            _ => unreachable!("Unexpected response {:?}", response),
+        }
+    }
+
     pub async fn update_coordinators(
         &self,
         update_coordinators_payload: UpdateCoordinatorsPayload,
@@ -331,6 +920,10 @@ impl AdminWebsocket {
         }
     }
 
+    /// Get the agent info records the conductor knows about, for the given cell or for all cells.
+    ///
+    /// This includes the agents on other conductors that were discovered through bootstrapping,
+    /// gossip or [AdminWebsocket::add_agent_info], so it can be used to check peer discovery.
     pub async fn agent_info(
         &self,
         cell_id: Option<CellId>,
@@ -343,6 +936,23 @@ impl AdminWebsocket {
         }
     }
 
+    /// Add agent info records to the conductor's peer store, for example to connect conductors
+    /// directly without a bootstrap service.
+    ///
+    /// To seed the peer tables of two conductors with each other's agents:
+    ///
+    /// ```rust,no_run
+    /// # async fn example(
+    /// #     admin_ws_1: holochain_client::AdminWebsocket,
+    /// #     admin_ws_2: holochain_client::AdminWebsocket,
+    /// # ) -> anyhow::Result<()> {
+    /// let agent_infos_1 = admin_ws_1.agent_info(None).await?;
+    /// let agent_infos_2 = admin_ws_2.agent_info(None).await?;
+    /// admin_ws_1.add_agent_info(agent_infos_2).await?;
+    /// admin_ws_2.add_agent_info(agent_infos_1).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
     pub async fn add_agent_info(
         &self,
         agent_infos: Vec<AgentInfoSigned>,
@@ -361,7 +971,6 @@ impl AdminWebsocket {
     ) -> Result<crate::signing::client_signing::SigningCredentials> {
         use holochain_zome_types::capability::{ZomeCallCapGrant, CAP_SECRET_BYTES};
         use rand::{rngs::OsRng, RngCore};
-        use std::collections::BTreeSet;
 
         let mut csprng = OsRng;
         let keypair = ed25519_dalek::SigningKey::generate(&mut csprng);
@@ -374,7 +983,7 @@ impl AdminWebsocket {
         self.grant_zome_call_capability(GrantZomeCallCapabilityPayload {
             cell_id: request.cell_id,
             cap_grant: ZomeCallCapGrant {
-                tag: "zome-call-signing-key".to_string(),
+                tag: ZOME_CALL_SIGNING_KEY_TAG.to_string(),
                 access: holochain_zome_types::capability::CapAccess::Assigned {
                     secret: cap_secret.into(),
                     assignees: BTreeSet::from([signing_agent_key.clone()]),
@@ -392,21 +1001,138 @@ impl AdminWebsocket {
         })
     }
 
+    /// Get the request statistics of this connection.
+    pub fn stats(&self) -> ConnectionStats {
+        self.stats.snapshot()
+    }
+
+    /// Keep a history of the last `capacity` requests of this connection, for dumping recent
+    /// conductor interactions after a failure. A capacity of 0 disables the history.
+    ///
+    /// Requests and responses are recorded in a truncated form. Capability grants with a secret,
+    /// revoked app authentication tokens, issued app authentication tokens and state dumps are
+    /// recorded as `<redacted>`. Other requests and responses are recorded as they are, including
+    /// agent keys, DNA hashes and the payloads of installed apps.
+    pub fn enable_debug_history(&self, capacity: usize) {
+        self.history.set_capacity(capacity);
+    }
+
+    /// Delay every request of this connection as configured, or stop delaying requests with
+    /// `None`. For development against a local conductor only.
+    #[cfg(feature = "simulated_latency")]
+    pub fn set_simulated_latency(&self, latency: Option<SimulatedLatency>) {
+        self.latency.set(latency);
+    }
+
+    /// The latency added to every request of this connection, if any.
+    #[cfg(feature = "simulated_latency")]
+    pub fn simulated_latency(&self) -> Option<SimulatedLatency> {
+        self.latency.get()
+    }
+
+    /// The recent requests of this connection, oldest first. Empty unless enabled with
+    /// [AdminWebsocket::enable_debug_history].
+    pub fn debug_history(&self) -> Vec<RequestRecord> {
+        self.history.records()
+    }
+
+    /// Tag this client with the role of the admin interface it is connected to. Requests for
+    /// operations outside the role then fail with [ConductorApiError::OperationNotAllowed]
+    /// without being sent.
+    ///
+    /// ```rust,no_run
+    /// # async fn example() -> anyhow::Result<()> {
+    /// use holochain_client::{AdminRole, AdminWebsocket};
+    /// use std::net::Ipv4Addr;
+    ///
+    /// let monitoring_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, 30_001))
+    ///     .await?
+    ///     .with_role(AdminRole::read_only("monitoring"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_role(mut self, role: AdminRole) -> Self {
+        self.role = role;
+        self
+    }
+
+    pub fn role(&self) -> &AdminRole {
+        &self.role
+    }
+
+    /// The endpoint this client is connected to.
+    pub fn connection_info(&self) -> ConnectionInfo {
+        ConnectionInfo {
+            addr: self.connection.addr(),
+            installed_app_id: None,
+        }
+    }
+
+    /// Whether this client is connected, or restoring its connection according to the
+    /// [ReconnectPolicy](crate::ReconnectPolicy) it was connected with.
+    pub fn connection_state(&self) -> ConnectionState {
+        self.connection.state()
+    }
+
     async fn send(&self, msg: AdminRequest) -> ConductorApiResult<AdminResponse> {
-        let response: AdminResponse = self
-            .tx
-            .request(msg)
+        let operation = operation_name(&msg);
+        if !self.role.allows(&operation) {
+            return Err(ConductorApiError::OperationNotAllowed {
+                operation,
+                role: self.role.name.clone(),
+            });
+        }
+        let pending = self.stats.start(operation, msg.payload_size());
+        let pending_record = self.history.start(&msg);
+        #[cfg(feature = "simulated_latency")]
+        self.latency.delay().await;
+        let idempotent = READ_ONLY_ADMIN_OPERATIONS.contains(&pending.operation());
+        let response: AdminResponse = match self
+            .connection
+            .request(msg, pending.operation(), idempotent, None)
             .await
-            .map_err(ConductorApiError::WebsocketError)?;
+        {
+            Ok(response) => response,
+            Err(err) => {
+                self.history.finish::<AdminResponse>(
+                    pending_record,
+                    pending.operation(),
+                    None,
+                    true,
+                );
+                self.stats.finish(pending, 0, true);
+                return Err(err);
+            }
+        };
+        let is_error = matches!(response, AdminResponse::Error(_));
+        let unsupported = match &response {
+            AdminResponse::Error(error) => {
+                unsupported_by_conductor(Interface::Admin, pending.operation(), error)
+            }
+            _ => None,
+        };
+        self.history.finish(
+            pending_record,
+            pending.operation(),
+            Some(&response),
+            is_error,
+        );
+        self.stats
+            .finish(pending, response.payload_size(), is_error);
+
         match response {
-            AdminResponse::Error(error) => Err(ConductorApiError::ExternalApiWireError(error)),
+            AdminResponse::Error(error) => {
+                Err(unsupported.unwrap_or(ConductorApiError::ExternalApiWireError(error)))
+            }
             _ => Ok(response),
         }
     }
 }
 
-impl Drop for AdminWebsocket {
-    fn drop(&mut self) {
-        self.poll_handle.abort();
-    }
+/// Whether an app is enabled, including enabled apps which are paused.
+pub(crate) fn is_enabled(status: &AppInfoStatus) -> bool {
+    matches!(
+        status,
+        AppInfoStatus::Running | AppInfoStatus::Paused { .. }
+    )
 }
@@ -0,0 +1,179 @@
+use crate::{AdminWebsocket, ConductorApiResult};
+use futures::{stream, Stream};
+use holochain_conductor_api::{AppInfo, AppInfoStatus, CellInfo};
+use holochain_types::app::InstalledAppId;
+use holochain_zome_types::prelude::{CellId, RoleName};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    time::Duration,
+};
+
+/// A change to the installed apps, see [AdminWebsocket::watch_all_apps].
+#[derive(Clone, Debug)]
+pub enum AppChange {
+    /// An app was installed, or was already installed when watching started.
+    AppAdded(AppInfo),
+    AppRemoved(InstalledAppId),
+    StatusChanged {
+        installed_app_id: InstalledAppId,
+        status: AppInfoStatus,
+    },
+    /// A cell was added to an app, such as a clone cell.
+    CellAdded {
+        installed_app_id: InstalledAppId,
+        role_name: RoleName,
+        cell_info: CellInfo,
+    },
+    CellRemoved {
+        installed_app_id: InstalledAppId,
+        role_name: RoleName,
+        cell_id: CellId,
+    },
+    /// A clone cell was enabled or disabled.
+    CellEnabledChanged {
+        installed_app_id: InstalledAppId,
+        cell_id: CellId,
+        enabled: bool,
+    },
+}
+
+impl AdminWebsocket {
+    /// Watch the installed apps for changes, for UIs such as launchers which show many apps.
+    ///
+    /// The apps are listed every `poll_interval` and compared with the previous list, so that
+    /// only the changes are reported as [AppChange]s. The first poll reports every installed app
+    /// as [AppChange::AppAdded]. Stem cells are not reported. Errors while polling are reported
+    /// without ending the stream.
+    pub fn watch_all_apps(
+        &self,
+        poll_interval: Duration,
+    ) -> impl Stream<Item = ConductorApiResult<AppChange>> + '_ {
+        stream::unfold(
+            (AppTracker::default(), true),
+            move |(mut tracker, mut first)| async move {
+                loop {
+                    if let Some(change) = tracker.pending.pop_front() {
+                        return Some((Ok(change), (tracker, first)));
+                    }
+                    if !first {
+                        tokio::time::sleep(poll_interval).await;
+                    }
+                    first = false;
+                    match self.list_apps(None).await {
+                        Ok(apps) => tracker.update(apps),
+                        Err(err) => return Some((Err(err), (tracker, first))),
+                    }
+                }
+            },
+        )
+    }
+}
+
+#[derive(Default)]
+struct AppTracker {
+    apps: BTreeMap<InstalledAppId, TrackedApp>,
+    pending: VecDeque<AppChange>,
+}
+
+struct TrackedApp {
+    status: AppInfoStatus,
+    cells: Vec<TrackedCell>,
+}
+
+struct TrackedCell {
+    role_name: RoleName,
+    cell_id: CellId,
+    /// Whether a clone cell is enabled. Always `None` for provisioned cells.
+    enabled: Option<bool>,
+}
+
+impl AppTracker {
+    fn update(&mut self, apps: Vec<AppInfo>) {
+        let mut previous_apps = std::mem::take(&mut self.apps);
+        for app in apps {
+            let installed_app_id = app.installed_app_id.clone();
+            let tracked = TrackedApp {
+                status: app.status.clone(),
+                cells: tracked_cells(&app),
+            };
+            match previous_apps.remove(&installed_app_id) {
+                Some(previous) => self.diff(&app, &previous, &tracked),
+                None => self.pending.push_back(AppChange::AppAdded(app)),
+            }
+            self.apps.insert(installed_app_id, tracked);
+        }
+        for installed_app_id in previous_apps.into_keys() {
+            self.pending
+                .push_back(AppChange::AppRemoved(installed_app_id));
+        }
+    }
+
+    fn diff(&mut self, app: &AppInfo, previous: &TrackedApp, current: &TrackedApp) {
+        let installed_app_id = &app.installed_app_id;
+        if previous.status != current.status {
+            self.pending.push_back(AppChange::StatusChanged {
+                installed_app_id: installed_app_id.clone(),
+                status: current.status.clone(),
+            });
+        }
+
+        for (role_name, cells) in &app.cell_info {
+            for cell_info in cells {
+                let Some((cell_id, enabled)) = cell_state(cell_info) else {
+                    continue;
+                };
+                match previous.cells.iter().find(|cell| cell.cell_id == *cell_id) {
+                    None => self.pending.push_back(AppChange::CellAdded {
+                        installed_app_id: installed_app_id.clone(),
+                        role_name: role_name.clone(),
+                        cell_info: cell_info.clone(),
+                    }),
+                    Some(cell) => {
+                        if let (Some(was_enabled), Some(enabled)) = (cell.enabled, enabled) {
+                            if was_enabled != enabled {
+                                self.pending.push_back(AppChange::CellEnabledChanged {
+                                    installed_app_id: installed_app_id.clone(),
+                                    cell_id: cell_id.clone(),
+                                    enabled,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        for cell in &previous.cells {
+            if !current.cells.iter().any(|c| c.cell_id == cell.cell_id) {
+                self.pending.push_back(AppChange::CellRemoved {
+                    installed_app_id: installed_app_id.clone(),
+                    role_name: cell.role_name.clone(),
+                    cell_id: cell.cell_id.clone(),
+                });
+            }
+        }
+    }
+}
+
+fn tracked_cells(app: &AppInfo) -> Vec<TrackedCell> {
+    app.cell_info
+        .iter()
+        .flat_map(|(role_name, cells)| {
+            cells.iter().filter_map(move |cell_info| {
+                cell_state(cell_info).map(|(cell_id, enabled)| TrackedCell {
+                    role_name: role_name.clone(),
+                    cell_id: cell_id.clone(),
+                    enabled,
+                })
+            })
+        })
+        .collect()
+}
+
+fn cell_state(cell_info: &CellInfo) -> Option<(&CellId, Option<bool>)> {
+    match cell_info {
+        CellInfo::Provisioned(cell) => Some((&cell.cell_id, None)),
+        CellInfo::Cloned(cell) => Some((&cell.cell_id, Some(cell.enabled))),
+        _ => None,
+    }
+}
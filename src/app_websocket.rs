@@ -1,33 +1,113 @@
 use crate::app_websocket_inner::AppWebsocketInner;
+use crate::connect::websocket_url_target;
 use crate::{
     signing::{sign_zome_call, AgentSigner},
-    ConductorApiError, ConductorApiResult,
+    AppAuthTokenSource, CloneRef, ConductorApiError, ConductorApiResult, ConductorError,
+    ConductorErrorKind, ConnectOptions, ConnectionInfo, ConnectionState, ConnectionStats,
+    InputValidators, RequestRecord, WebsocketConfig,
 };
 use anyhow::{anyhow, Result};
-use holo_hash::AgentPubKey;
+use futures::{Stream, StreamExt};
+use holo_hash::{AgentPubKey, DnaHash};
 use holochain_conductor_api::{
-    AppAuthenticationToken, AppInfo, AppRequest, AppResponse, CellInfo, NetworkInfo,
+    AppAuthenticationToken, AppInfo, AppInfoStatus, AppRequest, AppResponse, CellInfo, NetworkInfo,
     ProvisionedCell, ZomeCallParamsSigned,
 };
 use holochain_nonce::fresh_nonce;
 use holochain_types::app::{
-    CreateCloneCellPayload, DisableCloneCellPayload, EnableCloneCellPayload, MemproofMap,
-    NetworkInfoRequestPayload,
+    CreateCloneCellPayload, DisableCloneCellPayload, EnableCloneCellPayload, InstalledAppId,
+    MemproofMap, NetworkInfoRequestPayload,
 };
+#[cfg(feature = "unstable-countersigning")]
+use holochain_types::prelude::CountersigningSessionState;
 use holochain_types::prelude::{CloneId, Signal};
 use holochain_zome_types::{
     clone::ClonedCell,
     prelude::{CellId, ExternIO, FunctionName, RoleName, Timestamp, ZomeCallParams, ZomeName},
 };
+use parking_lot::RwLock;
 use std::net::ToSocketAddrs;
 use std::sync::Arc;
+use std::time::Duration;
 
+/// A client for an app interface of a conductor, authenticated for one app.
+///
+/// All requests take `&self`, so several requests can be in flight at once from shared
+/// references. Clones share the connection, see [AdminWebsocket](crate::AdminWebsocket).
 #[derive(Clone)]
 pub struct AppWebsocket {
     pub my_pub_key: AgentPubKey,
     inner: AppWebsocketInner,
-    app_info: AppInfo,
+    app_info: Arc<RwLock<CachedAppInfo>>,
+    app_info_refresh: Arc<AppInfoRefresh>,
+    /// Fed by a signal handler registered on the first [AppWebsocket::subscribe_signals].
+    pub(crate) signal_broadcast: Arc<tokio::sync::OnceCell<tokio::sync::broadcast::Sender<Signal>>>,
     signer: Arc<dyn AgentSigner + Send + Sync>,
+    disabled_app_guard: DisabledAppGuard,
+    input_validators: Option<Arc<InputValidators>>,
+}
+
+/// The window over which [AppWebsocket::refresh_app_info_debounced] coalesces refreshes, unless
+/// changed with [AppWebsocket::set_app_info_refresh_window].
+pub const DEFAULT_APP_INFO_REFRESH_WINDOW: Duration = Duration::from_millis(200);
+
+struct AppInfoRefresh {
+    window: RwLock<Duration>,
+    /// Held while a debounced refresh is in flight, so that concurrent refreshes share it.
+    in_flight: tokio::sync::Mutex<()>,
+}
+
+/// How an [AppWebsocket] avoids making zome calls to an app which is disabled.
+///
+/// Signing and sending a zome call to a disabled app only to have it rejected is wasted work, so
+/// the cached [AppInfo] is checked before signing a call.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DisabledAppGuard {
+    /// Always make the call.
+    Off,
+    /// Fail with [ConductorApiError::AppDisabled] if the cached app info shows that the app is
+    /// disabled. Use [AppWebsocket::refresh_app_info] after enabling the app.
+    Cached,
+    /// Like [DisabledAppGuard::Cached], but if the cached app info shows that the app is
+    /// disabled, fetch it once more to confirm before failing. An app that has been enabled since
+    /// the app info was cached can be called without refreshing it first.
+    #[default]
+    Confirm,
+}
+
+/// The copy of the [AppInfo] cached by an [AppWebsocket], and when it was fetched.
+///
+/// The cache is shared between clones of an [AppWebsocket].
+#[derive(Clone, Debug)]
+pub struct CachedAppInfo {
+    pub app_info: AppInfo,
+    pub fetched_at: Timestamp,
+}
+
+impl CachedAppInfo {
+    /// How long ago the app info was fetched.
+    pub fn age(&self) -> Duration {
+        let micros = Timestamp::now().as_micros() - self.fetched_at.as_micros();
+        Duration::from_micros(micros.max(0) as u64)
+    }
+}
+
+/// [AppInfo] freshly fetched from the conductor, see [AppWebsocket::fetch_app_info].
+#[derive(Clone, Debug)]
+pub struct FetchedAppInfo {
+    /// The app info, or `None` if the app no longer exists.
+    pub app_info: Option<AppInfo>,
+    pub fetched_at: Timestamp,
+    /// The copy that was cached by the client before this fetch.
+    pub previously_cached: CachedAppInfo,
+}
+
+/// The output of a zome call made with [AppWebsocket::call_zome_with_deadline].
+#[derive(Clone, Debug)]
+pub struct ZomeCallOutcome {
+    pub output: ExternIO,
+    /// When the zome call expired. The conductor rejects the call if it arrives after this time.
+    pub expires_at: Timestamp,
 }
 
 impl AppWebsocket {
@@ -59,27 +139,183 @@ impl AppWebsocket {
         token: AppAuthenticationToken,
         signer: Arc<dyn AgentSigner + Send + Sync>,
     ) -> Result<Self> {
-        let app_ws = AppWebsocketInner::connect(socket_addr).await?;
+        Self::connect_with_options(socket_addr, token, signer, ConnectOptions::default()).await
+    }
+
+    /// Connect to a Conductor API AppWebsocket with a custom WebsocketConfig, for example to
+    /// allow larger messages for zome calls with large payloads.
+    pub async fn connect_with_config(
+        socket_addr: impl ToSocketAddrs,
+        token: AppAuthenticationToken,
+        signer: Arc<dyn AgentSigner + Send + Sync>,
+        websocket_config: Arc<WebsocketConfig>,
+    ) -> Result<Self> {
+        Self::connect_with_options(
+            socket_addr,
+            token,
+            signer,
+            ConnectOptions::default().with_websocket_config(websocket_config),
+        )
+        .await
+    }
+
+    /// Connect to a Conductor API AppWebsocket with custom [ConnectOptions].
+    ///
+    /// The client can't authenticate again with a single token, so it does not reconnect even
+    /// if the options have a [ReconnectPolicy](crate::ReconnectPolicy). Use
+    /// [AppWebsocket::connect_with_token_source] for that.
+    pub async fn connect_with_options(
+        socket_addr: impl ToSocketAddrs,
+        token: AppAuthenticationToken,
+        signer: Arc<dyn AgentSigner + Send + Sync>,
+        options: ConnectOptions,
+    ) -> Result<Self> {
+        let app_ws = AppWebsocketInner::connect(socket_addr, options, None).await?;
+
+        Self::authenticate(app_ws, token, signer).await
+    }
 
+    /// Connect to a Conductor API AppWebsocket at a `ws://` or `wss://` URL, see
+    /// [AdminWebsocket::connect_url](crate::AdminWebsocket::connect_url).
+    pub async fn connect_url(
+        url: &str,
+        token: AppAuthenticationToken,
+        signer: Arc<dyn AgentSigner + Send + Sync>,
+        options: ConnectOptions,
+    ) -> Result<Self> {
+        let (target, options) = websocket_url_target(url, options)?;
+        Self::connect_with_options(target, token, signer, options).await
+    }
+
+    /// Connect to a Conductor API AppWebsocket, authenticating with tokens from a
+    /// [AppAuthTokenSource].
+    ///
+    /// A token is issued for the first connection, and for every later connection if the options
+    /// have a [ReconnectPolicy](crate::ReconnectPolicy). Signal handlers and streams of the
+    /// client keep working after it reconnected.
+    ///
+    /// ```rust,no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// use holochain_client::{
+    ///     AdminWebsocket, AppWebsocket, ClientAgentSigner, ConnectOptions, ReconnectPolicy,
+    /// };
+    /// use std::{net::Ipv4Addr, sync::Arc};
+    /// let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, 30_000)).await?;
+    /// let token_source = move || {
+    ///     let admin_ws = admin_ws.clone();
+    ///     async move {
+    ///         let issued = admin_ws.issue_app_auth_token("test-app".to_string().into()).await?;
+    ///         anyhow::Ok(issued.token)
+    ///     }
+    /// };
+    /// let app_ws = AppWebsocket::connect_with_token_source(
+    ///     (Ipv4Addr::LOCALHOST, 30_001),
+    ///     Arc::new(token_source),
+    ///     ClientAgentSigner::default().into(),
+    ///     ConnectOptions::default().with_reconnect(ReconnectPolicy::default()),
+    /// )
+    /// .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn connect_with_token_source(
+        socket_addr: impl ToSocketAddrs,
+        token_source: Arc<dyn AppAuthTokenSource>,
+        signer: Arc<dyn AgentSigner + Send + Sync>,
+        options: ConnectOptions,
+    ) -> Result<Self> {
+        let token = token_source
+            .issue_token()
+            .await
+            .map_err(|err| err.context("Failed to issue an app authentication token"))?;
+        let app_ws = AppWebsocketInner::connect(socket_addr, options, Some(token_source)).await?;
+
+        Self::authenticate(app_ws, token, signer).await
+    }
+
+    async fn authenticate(
+        app_ws: AppWebsocketInner,
+        token: AppAuthenticationToken,
+        signer: Arc<dyn AgentSigner + Send + Sync>,
+    ) -> Result<Self> {
         app_ws
             .authenticate(token)
             .await
-            .map_err(|err| anyhow!("Failed to send authentication: {err:?}"))?;
+            .map_err(|err| anyhow::Error::new(err).context("Failed to send authentication"))?;
 
+        let fetched_at = Timestamp::now();
         let app_info = app_ws
             .app_info()
             .await
-            .map_err(|err| anyhow!("Error fetching app_info {err:?}"))?
+            .map_err(|err| anyhow::Error::new(err).context("Error fetching app_info"))?
             .ok_or(anyhow!("App doesn't exist"))?;
 
         Ok(AppWebsocket {
             my_pub_key: app_info.agent_pub_key.clone(),
             inner: app_ws,
-            app_info,
+            app_info: Arc::new(RwLock::new(CachedAppInfo {
+                app_info,
+                fetched_at,
+            })),
+            app_info_refresh: Arc::new(AppInfoRefresh {
+                window: RwLock::new(DEFAULT_APP_INFO_REFRESH_WINDOW),
+                in_flight: tokio::sync::Mutex::new(()),
+            }),
+            signal_broadcast: Arc::new(tokio::sync::OnceCell::new()),
             signer,
+            disabled_app_guard: DisabledAppGuard::default(),
+            input_validators: None,
         })
     }
 
+    /// Get a copy of this [AppWebsocket] that signs zome calls with another signer.
+    ///
+    /// The copy shares the connection and cached app info with this one. This can be used to
+    /// choose the signer per call, for example a profile of a
+    /// [ClientAgentSigner](crate::ClientAgentSigner):
+    ///
+    /// ```rust,no_run
+    /// # async fn example(app_ws: holochain_client::AppWebsocket, signer: holochain_client::ClientAgentSigner) -> anyhow::Result<()> {
+    /// use holochain_zome_types::prelude::ExternIO;
+    /// app_ws
+    ///     .with_signer(signer.profile("background-jobs").into())
+    ///     .call_zome("foo".to_string().into(), "foo".into(), "foo".into(), ExternIO::encode(())?)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_signer(&self, signer: Arc<dyn AgentSigner + Send + Sync>) -> Self {
+        Self {
+            signer,
+            ..self.clone()
+        }
+    }
+
+    /// Get a copy of this [AppWebsocket] that checks the inputs of zome calls with `validators`
+    /// before signing them.
+    pub fn with_input_validators(&self, validators: InputValidators) -> Self {
+        Self {
+            input_validators: Some(Arc::new(validators)),
+            ..self.clone()
+        }
+    }
+
+    /// Get a copy of this [AppWebsocket] that guards zome calls against a disabled app as
+    /// configured. The default is [DisabledAppGuard::Confirm].
+    pub fn with_disabled_app_guard(&self, disabled_app_guard: DisabledAppGuard) -> Self {
+        Self {
+            disabled_app_guard,
+            ..self.clone()
+        }
+    }
+
+    /// Register a handler which is called for every signal received on this connection.
+    ///
+    /// The handler receives the app signals emitted by the cells of this app and all system
+    /// signals. It is called from the task that receives messages from the conductor, so it
+    /// should return quickly and hand longer work off to another task. Returns an id to remove
+    /// the handler with [AppWebsocket::off_signal].
     pub async fn on_signal<F: Fn(Signal) + 'static + Sync + Send>(
         &self,
         handler: F,
@@ -92,7 +328,7 @@ impl AppWebsocket {
                     zome_name: _,
                     signal: _,
                 } => {
-                    if app_info.cell_info.values().any(|cells| {
+                    if app_info.read().app_info.cell_info.values().any(|cells| {
                         cells.iter().any(|cell_info| match cell_info {
                             CellInfo::Provisioned(cell) => cell.cell_id.eq(&cell_id),
                             CellInfo::Cloned(cell) => cell.cell_id.eq(&cell_id),
@@ -107,10 +343,159 @@ impl AppWebsocket {
             .await
     }
 
+    /// Remove a signal handler registered with [AppWebsocket::on_signal]. Returns whether a
+    /// handler with the id was registered.
+    pub async fn off_signal(&self, id: &str) -> bool {
+        self.inner.off_signal(id).await
+    }
+
+    /// Register a handler that is called when the connection is lost, which is removed with
+    /// [AppWebsocket::off_signal].
+    pub(crate) async fn on_disconnected<F: Fn(()) + 'static + Sync + Send>(
+        &self,
+        handler: F,
+    ) -> Result<String> {
+        self.inner.on_disconnected(handler).await
+    }
+
+    /// Register a handler that is called when the connection was restored after it was lost,
+    /// which is removed with [AppWebsocket::off_signal].
+    pub(crate) async fn on_reconnected<F: Fn(()) + 'static + Sync + Send>(
+        &self,
+        handler: F,
+    ) -> Result<String> {
+        self.inner.on_reconnected(handler).await
+    }
+
+    /// Stop delivering signals to the handlers registered with [AppWebsocket::on_signal].
+    ///
+    /// Signals received while paused are dropped without being decoded, which saves work for apps
+    /// that are in the background. Requests are not affected. Pausing applies to all clones of this
+    /// [AppWebsocket].
+    pub fn pause_signals(&self) {
+        self.inner.set_signals_paused(true);
+    }
+
+    /// Resume delivering signals after [AppWebsocket::pause_signals].
+    ///
+    /// Signals that were received while paused are not delivered.
+    pub fn resume_signals(&self) {
+        self.inner.set_signals_paused(false);
+    }
+
+    pub fn signals_paused(&self) -> bool {
+        self.inner.signals_paused()
+    }
+
+    /// Get the request statistics of this connection, which are shared between clones.
+    pub fn stats(&self) -> ConnectionStats {
+        self.inner.stats()
+    }
+
+    /// Keep a history of the last `capacity` requests of this connection, for dumping recent
+    /// conductor interactions after a failure. A capacity of 0 disables the history. The history
+    /// is shared between clones.
+    ///
+    /// Requests and responses are recorded in a truncated form. Zome calls made with a capability
+    /// secret, such as those signed with signing credentials, are recorded as `<redacted>`. Zome
+    /// calls without a secret and all responses are recorded as they are, including zome call
+    /// outputs.
+    pub fn enable_debug_history(&self, capacity: usize) {
+        self.inner.enable_debug_history(capacity);
+    }
+
+    /// Delay every request of this connection as configured, or stop delaying requests with
+    /// `None`. For development against a local conductor only. The latency is shared between
+    /// clones.
+    #[cfg(feature = "simulated_latency")]
+    pub fn set_simulated_latency(&self, latency: Option<crate::SimulatedLatency>) {
+        self.inner.set_simulated_latency(latency);
+    }
+
+    /// The latency added to every request of this connection, if any.
+    #[cfg(feature = "simulated_latency")]
+    pub fn simulated_latency(&self) -> Option<crate::SimulatedLatency> {
+        self.inner.simulated_latency()
+    }
+
+    /// The recent requests of this connection, oldest first. Empty unless enabled with
+    /// [AppWebsocket::enable_debug_history].
+    pub fn debug_history(&self) -> Vec<RequestRecord> {
+        self.inner.debug_history()
+    }
+
+    /// The id of the app this connection is authenticated for.
+    ///
+    /// The app interface serves a single app per connection, which is determined by the token
+    /// used to connect. So a client that was only given a port and a token can find out which app
+    /// it is connected to without further configuration.
+    pub fn installed_app_id(&self) -> InstalledAppId {
+        self.app_info.read().app_info.installed_app_id.clone()
+    }
+
+    /// Whether this client is connected, or restoring its connection according to the
+    /// [ReconnectPolicy](crate::ReconnectPolicy) it was connected with. The state is shared
+    /// between clones.
+    pub fn connection_state(&self) -> ConnectionState {
+        self.inner.connection_state()
+    }
+
+    /// The endpoint this client is connected to and the app it is authenticated for.
+    pub fn connection_info(&self) -> ConnectionInfo {
+        ConnectionInfo {
+            addr: self.inner.addr(),
+            installed_app_id: Some(self.installed_app_id()),
+        }
+    }
+
+    /// Fetch the [AppInfo] of the app this connection is authenticated for.
+    ///
+    /// An app interface connection is bound to a single app by the token it authenticated with,
+    /// so no app id is needed. Returns `None` if the app has been uninstalled. Unlike
+    /// [AppWebsocket::fetch_app_info], this does not update the cached copy.
     pub async fn app_info(&self) -> ConductorApiResult<Option<AppInfo>> {
         self.inner.app_info().await
     }
 
+    /// Whether the app is waiting for its membrane proofs, according to the cached [AppInfo].
+    pub fn awaiting_memproofs(&self) -> bool {
+        self.app_info.read().app_info.status == AppInfoStatus::AwaitingMemproofs
+    }
+
+    /// Get the copy of the [AppInfo] cached by this client, without making a request.
+    ///
+    /// Use [CachedAppInfo::age] to decide whether it is stale.
+    pub fn cached_app_info(&self) -> CachedAppInfo {
+        self.app_info.read().clone()
+    }
+
+    /// Fetch the [AppInfo] from the conductor, along with when it was fetched and the copy that was
+    /// cached before.
+    ///
+    /// If the app still exists, the cached copy is updated.
+    pub async fn fetch_app_info(&self) -> ConductorApiResult<FetchedAppInfo> {
+        let fetched_at = Timestamp::now();
+        let app_info = self.inner.app_info().await?;
+
+        let previously_cached = {
+            let mut cached = self.app_info.write();
+            let previously_cached = cached.clone();
+            if let Some(app_info) = &app_info {
+                *cached = CachedAppInfo {
+                    app_info: app_info.clone(),
+                    fetched_at,
+                };
+            }
+            previously_cached
+        };
+
+        Ok(FetchedAppInfo {
+            app_info,
+            fetched_at,
+            previously_cached,
+        })
+    }
+
     pub async fn call_zome(
         &self,
         target: ZomeCallTarget,
@@ -118,15 +503,167 @@ impl AppWebsocket {
         fn_name: FunctionName,
         payload: ExternIO,
     ) -> ConductorApiResult<ExternIO> {
-        let cell_id = match target {
-            ZomeCallTarget::CellId(cell_id) => cell_id,
-            ZomeCallTarget::RoleName(role_name) => self.get_cell_id_from_role_name(&role_name)?,
-            ZomeCallTarget::CloneId(clone_id) => self.get_cell_id_from_role_name(&clone_id.0)?,
-        };
+        let (signed_zome_call, _) = self
+            .sign_call(target, zome_name, fn_name, payload, None)
+            .await?;
 
+        self.signed_call_zome(signed_zome_call).await
+    }
+
+    /// Call a zome function with an input from an untyped source, such as a config file or an
+    /// HTTP request.
+    ///
+    /// The input is encoded as it is, so JSON objects become maps. Register validators with
+    /// [AppWebsocket::with_input_validators] to reject malformed inputs before they are sent.
+    pub async fn call_zome_json(
+        &self,
+        target: ZomeCallTarget,
+        zome_name: ZomeName,
+        fn_name: FunctionName,
+        input: serde_json::Value,
+    ) -> ConductorApiResult<ExternIO> {
+        let payload = ExternIO::encode(input).map_err(ConductorApiError::SerializationError)?;
+        self.call_zome(target, zome_name, fn_name, payload).await
+    }
+
+    /// Call a zome function, failing with [ConductorApiError::RequestTimedOut] if no response
+    /// arrives within `timeout`.
+    ///
+    /// This overrides the request timeout of the [ConnectOptions] for one call. The conductor may
+    /// still complete a call that timed out, see [AppWebsocket::call_zome_with_deadline] for calls
+    /// which must not be applied after the client gave up on them.
+    pub async fn call_zome_with_timeout(
+        &self,
+        target: ZomeCallTarget,
+        zome_name: ZomeName,
+        fn_name: FunctionName,
+        payload: ExternIO,
+        timeout: Duration,
+    ) -> ConductorApiResult<ExternIO> {
+        let (signed_zome_call, _) = self
+            .sign_call(target, zome_name, fn_name, payload, None)
+            .await?;
+        let app_request = AppRequest::CallZome(Box::new(signed_zome_call));
+        let response = self
+            .inner
+            .send_with_timeout(app_request, Some(timeout))
+            .await?;
+
+        match response {
+            AppResponse::ZomeCalled(result) => Ok(*result),
+            _ => unreachable!("Unexpected response {:?}", response),
+        }
+    }
+
+    /// Call a zome function with a deadline which the conductor enforces too.
+    ///
+    /// The zome call expires when the deadline passes, so a call which times out locally is
+    /// rejected by the conductor if it arrives afterwards. A call that the conductor received
+    /// before the deadline may still complete. The deadline is capped at the expiry the client
+    /// uses for zome calls by default.
+    ///
+    /// If no response is received before the deadline, the call fails with
+    /// [ConductorApiError::ZomeCallDeadlineExceeded]. The expiry of the call is returned together
+    /// with the output.
+    pub async fn call_zome_with_deadline(
+        &self,
+        target: ZomeCallTarget,
+        zome_name: ZomeName,
+        fn_name: FunctionName,
+        payload: ExternIO,
+        timeout: Duration,
+    ) -> ConductorApiResult<ZomeCallOutcome> {
+        let deadline = Timestamp::from_micros(
+            Timestamp::now()
+                .as_micros()
+                .saturating_add(timeout.as_micros().try_into().unwrap_or(i64::MAX)),
+        );
+        let (signed_zome_call, expires_at) = self
+            .sign_call(target, zome_name, fn_name, payload, Some(deadline))
+            .await?;
+
+        let remaining = Duration::from_micros(
+            (expires_at.as_micros() - Timestamp::now().as_micros()).max(0) as u64,
+        );
+        match tokio::time::timeout(remaining, self.signed_call_zome(signed_zome_call)).await {
+            Ok(output) => Ok(ZomeCallOutcome {
+                output: output?,
+                expires_at,
+            }),
+            Err(_) => Err(ConductorApiError::ZomeCallDeadlineExceeded { expires_at }),
+        }
+    }
+
+    /// Build an unsigned zome call, for signing it with a custom signing pipeline.
+    ///
+    /// The call is made by [AppWebsocket::my_pub_key] without a capability secret, and expires
+    /// after the default expiry of zome calls. Change the fields as needed before signing. A
+    /// signed call can be checked with [verify_signed_zome_call](crate::verify_signed_zome_call)
+    /// and sent with [AppWebsocket::signed_call_zome]. [SerializedZomeCall](crate::SerializedZomeCall)
+    /// gives the bytes to sign.
+    pub fn build_zome_call(
+        &self,
+        target: ZomeCallTarget,
+        zome_name: ZomeName,
+        fn_name: FunctionName,
+        payload: ExternIO,
+    ) -> ConductorApiResult<ZomeCallParams> {
+        self.validate_input(&zome_name, &fn_name, &payload)?;
+        let cell_id = self.target_cell_id(target)?;
         let (nonce, expires_at) =
             fresh_nonce(Timestamp::now()).map_err(ConductorApiError::FreshNonceError)?;
 
+        Ok(ZomeCallParams {
+            provenance: self.my_pub_key.clone(),
+            cap_secret: None,
+            cell_id,
+            zome_name,
+            fn_name,
+            payload,
+            expires_at,
+            nonce,
+        })
+    }
+
+    fn validate_input(
+        &self,
+        zome_name: &ZomeName,
+        fn_name: &FunctionName,
+        payload: &ExternIO,
+    ) -> ConductorApiResult<()> {
+        match &self.input_validators {
+            Some(validators) => validators.validate(zome_name, fn_name, payload),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn target_cell_id(&self, target: ZomeCallTarget) -> ConductorApiResult<CellId> {
+        match target {
+            ZomeCallTarget::CellId(cell_id) => Ok(cell_id),
+            ZomeCallTarget::RoleName(role_name) => self.get_cell_id_from_role_name(&role_name),
+            ZomeCallTarget::CloneId(clone_id) => self.get_cell_id_from_role_name(&clone_id.0),
+        }
+    }
+
+    /// Build and sign a zome call, which expires at the earlier of `deadline` and the default
+    /// expiry. Returns the signed call and its expiry.
+    async fn sign_call(
+        &self,
+        target: ZomeCallTarget,
+        zome_name: ZomeName,
+        fn_name: FunctionName,
+        payload: ExternIO,
+        deadline: Option<Timestamp>,
+    ) -> ConductorApiResult<(ZomeCallParamsSigned, Timestamp)> {
+        self.validate_input(&zome_name, &fn_name, &payload)?;
+        self.check_app_not_disabled().await?;
+
+        let cell_id = self.target_cell_id(target)?;
+
+        let (nonce, expires_at) =
+            fresh_nonce(Timestamp::now()).map_err(ConductorApiError::FreshNonceError)?;
+        let expires_at = deadline.map_or(expires_at, |deadline| deadline.min(expires_at));
+
         let params = ZomeCallParams {
             provenance: self.signer.get_provenance(&cell_id).ok_or(
                 ConductorApiError::SignZomeCallError("Provenance not found".to_string()),
@@ -143,9 +680,33 @@ impl AppWebsocket {
             .await
             .map_err(|e| ConductorApiError::SignZomeCallError(e.to_string()))?;
 
-        self.signed_call_zome(signed_zome_call).await
+        Ok((signed_zome_call, expires_at))
     }
 
+    /// Call a zome function once for every payload of a stream.
+    ///
+    /// Up to `concurrency` calls are in flight at a time. Results are yielded in the order of the
+    /// payloads, whatever order the calls complete in. Use [futures::stream::iter] to call with the
+    /// payloads of an iterator.
+    pub fn call_zome_stream<'a>(
+        &'a self,
+        target: ZomeCallTarget,
+        zome_name: ZomeName,
+        fn_name: FunctionName,
+        payloads: impl Stream<Item = ExternIO> + 'a,
+        concurrency: usize,
+    ) -> impl Stream<Item = ConductorApiResult<ExternIO>> + 'a {
+        payloads
+            .map(move |payload| {
+                self.call_zome(target.clone(), zome_name.clone(), fn_name.clone(), payload)
+            })
+            .buffered(concurrency.max(1))
+    }
+
+    /// Send a zome call which has already been signed.
+    ///
+    /// This is the lower level path behind [AppWebsocket::call_zome], for calls signed outside of
+    /// an [AgentSigner]. See [AppWebsocket::build_zome_call] for building the call to sign.
     pub async fn signed_call_zome(
         &self,
         signed_params: ZomeCallParamsSigned,
@@ -159,6 +720,28 @@ impl AppWebsocket {
         }
     }
 
+    /// Send several zome calls which have already been signed, such as the calls of a
+    /// [DetachedSigningBatch](crate::DetachedSigningBatch).
+    ///
+    /// The calls are sent concurrently and the results are returned in the order of the calls.
+    pub async fn signed_call_zomes(
+        &self,
+        signed_calls: Vec<ZomeCallParamsSigned>,
+    ) -> Vec<ConductorApiResult<ExternIO>> {
+        futures::future::join_all(
+            signed_calls
+                .into_iter()
+                .map(|signed_call| self.signed_call_zome(signed_call)),
+        )
+        .await
+    }
+
+    /// Provide the membrane proofs of an app that was installed with deferred membrane proofs.
+    ///
+    /// Such an app has the status [AppInfoStatus::AwaitingMemproofs] until the proofs are
+    /// provided, see [AppWebsocket::awaiting_memproofs]. Afterwards it is disabled, and is started
+    /// with [AppWebsocket::enable_app], which completes the join flow. The proofs are keyed by the
+    /// role names of the app.
     pub async fn provide_memproofs(&self, memproofs: MemproofMap) -> ConductorApiResult<()> {
         let app_request = AppRequest::ProvideMemproofs(memproofs);
         let response = self.inner.send(app_request).await?;
@@ -168,27 +751,148 @@ impl AppWebsocket {
         }
     }
 
+    /// Enable the app after its membrane proofs were provided with
+    /// [AppWebsocket::provide_memproofs].
+    ///
+    /// This lets a client bound to a single app finish activating it without admin access. The
+    /// conductor only allows it for apps which were disabled because they were waiting for their
+    /// membrane proofs. The cached [AppInfo] is refreshed afterwards, so that the app's cells can
+    /// be called right away.
     pub async fn enable_app(&self) -> ConductorApiResult<()> {
         let app_request = AppRequest::EnableApp;
         let response = self.inner.send(app_request).await?;
         match response {
-            AppResponse::Ok => Ok(()),
+            AppResponse::Ok => {}
             _ => unreachable!("Unexpected response {:?}", response),
         }
+        self.fetch_app_info().await?;
+        Ok(())
     }
 
+    /// Get the state of the countersigning session of a cell, or `None` if the cell has no
+    /// session in progress.
+    ///
+    /// The state tells whether the session has been accepted, whether all signatures have been
+    /// collected, or whether its outcome could not be resolved yet, so that a UI can show
+    /// sessions that are stuck.
+    #[cfg(feature = "unstable-countersigning")]
+    pub async fn get_countersigning_session_state(
+        &self,
+        cell_id: CellId,
+    ) -> ConductorApiResult<Option<CountersigningSessionState>> {
+        let msg = AppRequest::GetCountersigningSessionState(Box::new(cell_id));
+        let response = self.inner.send(msg).await?;
+        match response {
+            AppResponse::CountersigningSessionState(state) => Ok(*state),
+            _ => unreachable!("Unexpected response {:?}", response),
+        }
+    }
+
+    /// Abandon the unresolved countersigning session of a cell.
+    ///
+    /// Only use this for a session whose outcome the conductor could not resolve, as reported by
+    /// [AppWebsocket::get_countersigning_session_state]. The session entry is dropped and the
+    /// source chain of the cell is unlocked, so the agent must be sure that the other parties
+    /// don't complete the session.
+    #[cfg(feature = "unstable-countersigning")]
+    pub async fn abandon_countersigning_session(&self, cell_id: CellId) -> ConductorApiResult<()> {
+        let msg = AppRequest::AbandonCountersigningSession(Box::new(cell_id));
+        let response = self.inner.send(msg).await?;
+        match response {
+            AppResponse::CountersigningSessionAbandoned => Ok(()),
+            _ => unreachable!("Unexpected response {:?}", response),
+        }
+    }
+
+    /// Publish the unresolved countersigning session of a cell with the signatures collected so
+    /// far.
+    ///
+    /// Like [AppWebsocket::abandon_countersigning_session], this recovers from a session whose
+    /// outcome the conductor could not resolve, when the agent knows that the session completed.
+    /// Publishing is triggered in the background.
+    #[cfg(feature = "unstable-countersigning")]
+    pub async fn publish_countersigning_session(&self, cell_id: CellId) -> ConductorApiResult<()> {
+        let msg = AppRequest::PublishCountersigningSession(Box::new(cell_id));
+        let response = self.inner.send(msg).await?;
+        match response {
+            AppResponse::PublishCountersigningSessionTriggered => Ok(()),
+            _ => unreachable!("Unexpected response {:?}", response),
+        }
+    }
+
+    /// Create a clone cell.
+    ///
+    /// If the cached [AppInfo] shows that the role has reached its clone limit, this fails with
+    /// [ConductorApiError::CloneLimitReached] without making a request. The conductor rejecting the
+    /// request because of the clone limit is reported with the same error.
     pub async fn create_clone_cell(
         &self,
         msg: CreateCloneCellPayload,
     ) -> ConductorApiResult<ClonedCell> {
+        let clone_limit = {
+            let cached = self.app_info.read();
+            match clone_limit_and_count(&cached.app_info, &msg.role_name) {
+                Some((limit, count)) if count >= limit => {
+                    return Err(ConductorApiError::CloneLimitReached { limit })
+                }
+                Some((limit, _)) => Some(limit),
+                None => None,
+            }
+        };
+
         let app_request = AppRequest::CreateCloneCell(Box::new(msg));
-        let response = self.inner.send(app_request).await?;
+        let response = match self.inner.send(app_request).await {
+            Ok(response) => response,
+            // Another client may have created clones since the app info was cached.
+            Err(err) => match (clone_limit, err.conductor_error()) {
+                (Some(limit), Some(conductor_error))
+                    if is_clone_limit_exceeded(&conductor_error, limit) =>
+                {
+                    return Err(ConductorApiError::CloneLimitReached { limit })
+                }
+                _ => return Err(err),
+            },
+        };
         match response {
             AppResponse::CloneCellCreated(clone_cell) => Ok(clone_cell),
             _ => unreachable!("Unexpected response {:?}", response),
         }
     }
 
+    /// List the clone cells of a role, including disabled ones.
+    ///
+    /// The [AppInfo] is fetched first, so the list includes clone cells created since it was
+    /// cached, and the cache is updated. Whether a clone cell is enabled is reported in
+    /// [ClonedCell::enabled]. Returns an empty list if the role has no clone cells or doesn't
+    /// exist.
+    pub async fn list_clone_cells(
+        &self,
+        role_name: &RoleName,
+    ) -> ConductorApiResult<Vec<ClonedCell>> {
+        let app_info = self.fetch_app_info().await?.app_info;
+        Ok(app_info
+            .as_ref()
+            .and_then(|app_info| app_info.cell_info.get(role_name))
+            .into_iter()
+            .flatten()
+            .filter_map(|cell_info| match cell_info {
+                CellInfo::Cloned(cloned_cell) => Some(cloned_cell.clone()),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// How many more clone cells can be created for a role, according to the cached [AppInfo].
+    /// Disabled clone cells do not count towards the clone limit.
+    ///
+    /// Call [AppWebsocket::refresh_app_info] after creating clone cells to keep the count current.
+    /// Returns `None` if the role is not part of the app.
+    pub fn clones_remaining(&self, role_name: &RoleName) -> Option<u32> {
+        let cached = self.app_info.read();
+        clone_limit_and_count(&cached.app_info, role_name)
+            .map(|(limit, count)| limit.saturating_sub(count))
+    }
+
     pub async fn disable_clone_cell(
         &self,
         payload: DisableCloneCellPayload,
@@ -213,6 +917,40 @@ impl AppWebsocket {
         }
     }
 
+    /// Disable a clone cell of this app, referenced by any of the types a [CloneRef] converts from.
+    pub async fn disable_clone(&self, clone: impl Into<CloneRef>) -> ConductorApiResult<()> {
+        self.disable_clone_cell(DisableCloneCellPayload {
+            clone_cell_id: clone.into().into(),
+        })
+        .await
+    }
+
+    /// Enable a clone cell of this app, referenced by any of the types a [CloneRef] converts from.
+    pub async fn enable_clone(&self, clone: impl Into<CloneRef>) -> ConductorApiResult<ClonedCell> {
+        self.enable_clone_cell(EnableCloneCellPayload {
+            clone_cell_id: clone.into().into(),
+        })
+        .await
+    }
+
+    /// The cell id of a clone cell of this app, according to the cached [AppInfo].
+    ///
+    /// Call [AppWebsocket::refresh_app_info] first if the clone cell was created after the app
+    /// info was fetched.
+    pub fn clone_cell_id(&self, clone: impl Into<CloneRef>) -> ConductorApiResult<CellId> {
+        let cached = self.app_info.read();
+        clone
+            .into()
+            .cell_id(&cached.app_info)
+            .map_err(|_| ConductorApiError::CellNotFound)
+    }
+
+    /// Get the network state of the given DNAs, as seen by an agent of this app.
+    ///
+    /// For every DNA, in the order of the request, this returns the agent's storage arc size, the
+    /// number of peers it knows of and the ops it still has to fetch. Pass the time of the
+    /// previous query as `last_time_queried` to also get the gossip progress since then. This is
+    /// what a UI needs to show that the app is still syncing.
     pub async fn network_info(
         &self,
         payload: NetworkInfoRequestPayload,
@@ -225,6 +963,40 @@ impl AppWebsocket {
         }
     }
 
+    /// Get the network state of all enabled cells of this app for [AppWebsocket::my_pub_key].
+    ///
+    /// The DNAs are taken from the cached [AppInfo], see [AppWebsocket::network_info] for the
+    /// information returned.
+    pub async fn app_network_info(
+        &self,
+        last_time_queried: Option<Timestamp>,
+    ) -> ConductorApiResult<Vec<(DnaHash, NetworkInfo)>> {
+        let dnas = {
+            let cached = self.app_info.read();
+            let mut dnas: Vec<DnaHash> = Vec::new();
+            for cell_info in cached.app_info.cell_info.values().flatten() {
+                let dna_hash = match cell_info {
+                    CellInfo::Provisioned(cell) => cell.cell_id.dna_hash(),
+                    CellInfo::Cloned(cell) if cell.enabled => cell.cell_id.dna_hash(),
+                    _ => continue,
+                };
+                if !dnas.contains(dna_hash) {
+                    dnas.push(dna_hash.clone());
+                }
+            }
+            dnas
+        };
+
+        let infos = self
+            .network_info(NetworkInfoRequestPayload {
+                agent_pub_key: self.my_pub_key.clone(),
+                dnas: dnas.clone(),
+                last_time_queried,
+            })
+            .await?;
+        Ok(dnas.into_iter().zip(infos).collect())
+    }
+
     pub async fn list_wasm_host_functions(&self) -> ConductorApiResult<Vec<String>> {
         let msg = AppRequest::ListWasmHostFunctions;
         let response = self.inner.send(msg).await?;
@@ -237,21 +1009,76 @@ impl AppWebsocket {
     /// Gets a new copy of the [AppInfo] for the app this agent is connected to.
     ///
     /// This is useful if you have made changes to the app, such as creating new clone cells, and need to refresh the app info.
-    pub async fn refresh_app_info(&mut self) -> Result<()> {
-        self.app_info = self
-            .app_info()
+    pub async fn refresh_app_info(&self) -> Result<()> {
+        self.fetch_app_info()
             .await
-            .map_err(|err| anyhow!("Error fetching app_info {err:?}"))?
+            .map_err(|err| anyhow::Error::new(err).context("Error fetching app_info"))?
+            .app_info
             .ok_or(anyhow!("App doesn't exist"))?;
 
         Ok(())
     }
 
+    /// Refresh the cached [AppInfo], coalescing refreshes requested in quick succession.
+    ///
+    /// Use this instead of [AppWebsocket::refresh_app_info] where refreshes can be requested in
+    /// bursts, such as after creating each of many clone cells. The refresh waits for the refresh
+    /// window and then fetches the app info, unless a fetch which started after this refresh was
+    /// requested has completed in the meantime. So there is at most one request per window, and
+    /// the returned app info always reflects changes made before calling this.
+    /// [AppWebsocket::refresh_app_info] fetches the app info right away.
+    pub async fn refresh_app_info_debounced(&self) -> ConductorApiResult<CachedAppInfo> {
+        let requested_at = Timestamp::now();
+        let window = *self.app_info_refresh.window.read();
+        tokio::time::sleep(window).await;
+
+        let _in_flight = self.app_info_refresh.in_flight.lock().await;
+        {
+            let cached = self.app_info.read();
+            if cached.fetched_at >= requested_at {
+                return Ok(cached.clone());
+            }
+        }
+        self.fetch_app_info().await?;
+        Ok(self.cached_app_info())
+    }
+
+    /// Set the window over which [AppWebsocket::refresh_app_info_debounced] coalesces refreshes.
+    /// The window is shared between clones. The default is [DEFAULT_APP_INFO_REFRESH_WINDOW].
+    pub fn set_app_info_refresh_window(&self, window: Duration) {
+        *self.app_info_refresh.window.write() = window;
+    }
+
+    /// Check the app status according to the [DisabledAppGuard] of this client.
+    async fn check_app_not_disabled(&self) -> ConductorApiResult<()> {
+        if self.disabled_app_guard == DisabledAppGuard::Off {
+            return Ok(());
+        }
+        let status = self.app_info.read().app_info.status.clone();
+        let AppInfoStatus::Disabled { mut reason } = status else {
+            return Ok(());
+        };
+        if self.disabled_app_guard == DisabledAppGuard::Confirm {
+            match self
+                .fetch_app_info()
+                .await?
+                .app_info
+                .map(|app_info| app_info.status)
+            {
+                Some(AppInfoStatus::Disabled { reason: confirmed }) => reason = confirmed,
+                _ => return Ok(()),
+            }
+        }
+        Err(ConductorApiError::AppDisabled { reason })
+    }
+
     fn get_cell_id_from_role_name(&self, role_name: &RoleName) -> ConductorApiResult<CellId> {
+        let cached = self.app_info.read();
+        let app_info = &cached.app_info;
         if is_clone_id(role_name) {
             let base_role_name = get_base_role_name_from_clone_id(role_name);
 
-            let Some(role_cells) = self.app_info.cell_info.get(&base_role_name) else {
+            let Some(role_cells) = app_info.cell_info.get(&base_role_name) else {
                 return Err(ConductorApiError::CellNotFound);
             };
 
@@ -270,7 +1097,7 @@ impl AppWebsocket {
             let clone_cell = maybe_clone_cell.ok_or(ConductorApiError::CellNotFound)?;
             Ok(clone_cell.cell_id)
         } else {
-            let Some(role_cells) = self.app_info.cell_info.get(role_name) else {
+            let Some(role_cells) = app_info.cell_info.get(role_name) else {
                 return Err(ConductorApiError::CellNotFound);
             };
 
@@ -286,6 +1113,7 @@ impl AppWebsocket {
     }
 }
 
+#[derive(Clone, Debug)]
 pub enum ZomeCallTarget {
     CellId(CellId),
     /// Call a cell by its role name.
@@ -318,6 +1146,37 @@ impl From<CloneId> for ZomeCallTarget {
     }
 }
 
+/// The clone limit of a role and the number of its enabled clone cells.
+///
+/// Like the conductor, only enabled clone cells count towards the limit. Disabling a clone cell
+/// makes room for a new one.
+fn clone_limit_and_count(app_info: &AppInfo, role_name: &RoleName) -> Option<(u32, u32)> {
+    let role = app_info
+        .manifest
+        .app_roles()
+        .into_iter()
+        .find(|role| &role.name == role_name)?;
+    let count = app_info.cell_info.get(role_name).map_or(0, |cells| {
+        cells
+            .iter()
+            .filter(|cell| matches!(cell, CellInfo::Cloned(cell) if cell.enabled))
+            .count() as u32
+    });
+
+    Some((role.dna.clone_limit, count))
+}
+
+/// Whether the conductor rejected creating a clone cell because the role reached its clone limit.
+///
+/// The conductor reports this as an internal error with the message of
+/// `AppError::CloneLimitExceeded`, which starts with "Clone limit of {limit} exceeded".
+fn is_clone_limit_exceeded(error: &ConductorError, limit: u32) -> bool {
+    error.kind == ConductorErrorKind::Internal
+        && error
+            .message
+            .contains(&format!("Clone limit of {limit} exceeded"))
+}
+
 fn is_clone_id(role_name: &RoleName) -> bool {
     role_name.as_str().contains('.')
 }
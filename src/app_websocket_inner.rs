@@ -1,58 +1,130 @@
+use crate::compat::{unsupported_by_conductor, Interface};
+use crate::connect::{connect_websocket, ConnectOptions};
 use crate::error::{ConductorApiError, ConductorApiResult};
-use anyhow::Result;
+use crate::history::{RequestHistory, RequestRecord};
+#[cfg(feature = "simulated_latency")]
+use crate::latency::{LatencyInjector, SimulatedLatency};
+use crate::reconnect::{AbortOnDropHandle, AppAuthTokenSource, Connection, ConnectionState};
+use crate::stats::{ConnectionStats, StatsRecorder};
+use crate::wire::{operation_name, PayloadSize};
+use anyhow::{anyhow, Result};
 use event_emitter_rs::EventEmitter;
 use holochain_conductor_api::{
     AppAuthenticationRequest, AppAuthenticationToken, AppInfo, AppRequest, AppResponse,
 };
 use holochain_types::signal::Signal;
-use holochain_websocket::{connect, WebsocketConfig, WebsocketSender};
-use std::{net::ToSocketAddrs, sync::Arc};
+use std::{
+    net::{SocketAddr, ToSocketAddrs},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 use tokio::sync::Mutex;
-use tokio::task::AbortHandle;
 
-struct AbortOnDropHandle(AbortHandle);
+/// The operations which are retried after reconnecting, because sending them again has no effect
+/// beyond sending them once.
+const IDEMPOTENT_APP_OPERATIONS: &[&str] = &[
+    "app_info",
+    "get_countersigning_session_state",
+    "list_wasm_host_functions",
+    "network_info",
+];
 
 /// The core functionality for an app websocket.
 #[derive(Clone)]
 pub(crate) struct AppWebsocketInner {
-    tx: WebsocketSender,
+    connection: Arc<Connection>,
     event_emitter: Arc<Mutex<EventEmitter>>,
+    stats: Arc<StatsRecorder>,
+    history: Arc<RequestHistory>,
+    signals_paused: Arc<AtomicBool>,
+    #[cfg(feature = "simulated_latency")]
+    latency: Arc<LatencyInjector>,
     _abort_handle: Arc<AbortOnDropHandle>,
 }
 
 impl AppWebsocketInner {
     /// Connect to a Conductor API AppWebsocket.
-    pub(crate) async fn connect(socket_addr: impl ToSocketAddrs) -> Result<Self> {
+    ///
+    /// The connection is only restored after it was lost if the options have a reconnect policy
+    /// and there is a `token_source` to authenticate the new connection with.
+    pub(crate) async fn connect(
+        socket_addr: impl ToSocketAddrs,
+        mut options: ConnectOptions,
+        token_source: Option<Arc<dyn AppAuthTokenSource>>,
+    ) -> Result<Self> {
         let addr = socket_addr
             .to_socket_addrs()?
             .next()
             .expect("invalid websocket address");
-        let websocket_config = Arc::new(WebsocketConfig::CLIENT_DEFAULT);
-        let (tx, mut rx) = again::retry(|| {
-            let websocket_config = Arc::clone(&websocket_config);
-            connect(websocket_config, addr)
-        })
-        .await?;
+        if token_source.is_none() {
+            options.reconnect = None;
+        }
+        let (tx, mut rx) = connect_websocket(addr, &options).await?;
+        let connection = Arc::new(Connection::new(addr, options, tx));
 
         let event_emitter = EventEmitter::new();
         let mutex = Arc::new(Mutex::new(event_emitter));
 
+        let signals_paused = Arc::new(AtomicBool::new(false));
+
         let poll_handle = tokio::task::spawn({
             let mutex = mutex.clone();
+            let signals_paused = signals_paused.clone();
+            let connection = connection.clone();
             async move {
-                while let Ok(msg) = rx.recv::<AppResponse>().await {
-                    if let holochain_websocket::ReceiveMessage::Signal(signal_bytes) = msg {
-                        let mut event_emitter = mutex.lock().await;
-                        let signal = Signal::try_from_vec(signal_bytes).expect("Malformed signal");
-                        event_emitter.emit("signal", signal);
+                loop {
+                    while let Ok(msg) = rx.recv::<AppResponse>().await {
+                        if let holochain_websocket::ReceiveMessage::Signal(signal_bytes) = msg {
+                            // The receiver keeps being polled for responses, but signals are
+                            // dropped without being decoded.
+                            if signals_paused.load(Ordering::Acquire) {
+                                continue;
+                            }
+                            let mut event_emitter = mutex.lock().await;
+                            let signal =
+                                Signal::try_from_vec(signal_bytes).expect("Malformed signal");
+                            event_emitter.emit("signal", signal);
+                        }
+                    }
+                    mutex.lock().await.emit("disconnected", ());
+
+                    // Signal handlers stay registered on the event emitter, so they receive the
+                    // signals of the new connection.
+                    let new_rx = connection
+                        .reconnect(|tx| {
+                            let token_source = token_source.clone();
+                            async move {
+                                let token = token_source
+                                    .ok_or_else(|| anyhow!("No token source to authenticate with"))?
+                                    .issue_token()
+                                    .await?;
+                                tx.authenticate(AppAuthenticationRequest { token }).await?;
+                                Ok(())
+                            }
+                        })
+                        .await;
+                    match new_rx {
+                        Some(new_rx) => {
+                            rx = new_rx;
+                            mutex.lock().await.emit("reconnected", ());
+                        }
+                        None => break,
                     }
                 }
             }
         });
 
         Ok(Self {
-            tx,
+            connection,
             event_emitter: mutex,
+            stats: Arc::new(StatsRecorder::default()),
+            history: Arc::new(RequestHistory::default()),
+            signals_paused,
+            #[cfg(feature = "simulated_latency")]
+            latency: Arc::new(LatencyInjector::default()),
             _abort_handle: Arc::new(AbortOnDropHandle(poll_handle.abort_handle())),
         })
     }
@@ -66,6 +138,33 @@ impl AppWebsocketInner {
         Ok(id)
     }
 
+    /// Register a handler that is called when the connection to the conductor is lost. The
+    /// handler is removed with [AppWebsocketInner::off_signal] like a signal handler.
+    pub(crate) async fn on_disconnected<F: Fn(()) + 'static + Sync + Send>(
+        &self,
+        handler: F,
+    ) -> Result<String> {
+        let mut event_emitter = self.event_emitter.lock().await;
+        let id = event_emitter.on("disconnected", handler);
+        Ok(id)
+    }
+
+    /// Register a handler that is called when the connection to the conductor was restored. The
+    /// handler is removed with [AppWebsocketInner::off_signal] like a signal handler.
+    pub(crate) async fn on_reconnected<F: Fn(()) + 'static + Sync + Send>(
+        &self,
+        handler: F,
+    ) -> Result<String> {
+        let mut event_emitter = self.event_emitter.lock().await;
+        let id = event_emitter.on("reconnected", handler);
+        Ok(id)
+    }
+
+    pub(crate) async fn off_signal(&self, id: &str) -> bool {
+        let mut event_emitter = self.event_emitter.lock().await;
+        event_emitter.remove_listener(id).is_some()
+    }
+
     pub(crate) async fn app_info(&self) -> ConductorApiResult<Option<AppInfo>> {
         let response = self.send(AppRequest::AppInfo).await?;
         match response {
@@ -78,28 +177,101 @@ impl AppWebsocketInner {
         &self,
         token: AppAuthenticationToken,
     ) -> ConductorApiResult<()> {
-        self.tx
+        self.connection
+            .sender()
             .authenticate(AppAuthenticationRequest { token })
             .await
             .map_err(ConductorApiError::WebsocketError)
     }
 
+    pub(crate) fn set_signals_paused(&self, paused: bool) {
+        self.signals_paused.store(paused, Ordering::Release);
+    }
+
+    pub(crate) fn signals_paused(&self) -> bool {
+        self.signals_paused.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn addr(&self) -> SocketAddr {
+        self.connection.addr()
+    }
+
+    pub(crate) fn connection_state(&self) -> ConnectionState {
+        self.connection.state()
+    }
+
+    pub(crate) fn stats(&self) -> ConnectionStats {
+        self.stats.snapshot()
+    }
+
+    pub(crate) fn enable_debug_history(&self, capacity: usize) {
+        self.history.set_capacity(capacity);
+    }
+
+    #[cfg(feature = "simulated_latency")]
+    pub(crate) fn set_simulated_latency(&self, latency: Option<SimulatedLatency>) {
+        self.latency.set(latency);
+    }
+
+    #[cfg(feature = "simulated_latency")]
+    pub(crate) fn simulated_latency(&self) -> Option<SimulatedLatency> {
+        self.latency.get()
+    }
+
+    pub(crate) fn debug_history(&self) -> Vec<RequestRecord> {
+        self.history.records()
+    }
+
     pub(crate) async fn send(&self, msg: AppRequest) -> ConductorApiResult<AppResponse> {
-        let response = self
-            .tx
-            .request(msg)
+        self.send_with_timeout(msg, None).await
+    }
+
+    /// Send a request which fails if no response arrives within `timeout`, or the request timeout
+    /// of the connection if `None`.
+    pub(crate) async fn send_with_timeout(
+        &self,
+        msg: AppRequest,
+        timeout: Option<Duration>,
+    ) -> ConductorApiResult<AppResponse> {
+        let pending = self.stats.start(operation_name(&msg), msg.payload_size());
+        let pending_record = self.history.start(&msg);
+        #[cfg(feature = "simulated_latency")]
+        self.latency.delay().await;
+        let idempotent = IDEMPOTENT_APP_OPERATIONS.contains(&pending.operation());
+        let response: AppResponse = match self
+            .connection
+            .request(msg, pending.operation(), idempotent, timeout)
             .await
-            .map_err(ConductorApiError::WebsocketError)?;
+        {
+            Ok(response) => response,
+            Err(err) => {
+                self.history
+                    .finish::<AppResponse>(pending_record, pending.operation(), None, true);
+                self.stats.finish(pending, 0, true);
+                return Err(err);
+            }
+        };
+        let is_error = matches!(response, AppResponse::Error(_));
+        let unsupported = match &response {
+            AppResponse::Error(error) => {
+                unsupported_by_conductor(Interface::App, pending.operation(), error)
+            }
+            _ => None,
+        };
+        self.history.finish(
+            pending_record,
+            pending.operation(),
+            Some(&response),
+            is_error,
+        );
+        self.stats
+            .finish(pending, response.payload_size(), is_error);
 
         match response {
-            AppResponse::Error(error) => Err(ConductorApiError::ExternalApiWireError(error)),
+            AppResponse::Error(error) => {
+                Err(unsupported.unwrap_or(ConductorApiError::ExternalApiWireError(error)))
+            }
             _ => Ok(response),
         }
     }
 }
-
-impl Drop for AbortOnDropHandle {
-    fn drop(&mut self) {
-        self.0.abort();
-    }
-}
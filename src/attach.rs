@@ -0,0 +1,68 @@
+use crate::{AdminWebsocket, AgentSigner, AppWebsocket};
+use anyhow::{anyhow, Context, Result};
+use holochain_types::{app::InstalledAppId, websocket::AllowedOrigins};
+use std::{
+    net::IpAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The delay between attempts to connect to a newly attached app interface.
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+impl AdminWebsocket {
+    /// Attach an app interface bound to an app and connect to it as soon as it accepts
+    /// connections.
+    ///
+    /// Connecting immediately after attaching an interface can fail while the conductor is still
+    /// setting it up, so connecting is retried until `ready_timeout` elapses. A new authentication
+    /// token is issued for every attempt.
+    ///
+    /// `host` is the address the conductor is reachable at, usually the host of the admin
+    /// interface.
+    pub async fn attach_and_connect(
+        &self,
+        host: impl Into<IpAddr>,
+        allowed_origins: AllowedOrigins,
+        installed_app_id: InstalledAppId,
+        signer: Arc<dyn AgentSigner + Send + Sync>,
+        ready_timeout: Duration,
+    ) -> Result<AppWebsocket> {
+        let host = host.into();
+        let port = self
+            .attach_app_interface(0, allowed_origins, Some(installed_app_id.clone()))
+            .await
+            .context("Failed to attach app interface")?;
+
+        let deadline = Instant::now() + ready_timeout;
+        loop {
+            let issued = self
+                .issue_app_auth_token(installed_app_id.clone().into())
+                .await
+                .with_context(|| format!("Failed to issue token for app {installed_app_id}"))?;
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let attempt = tokio::time::timeout(
+                remaining,
+                AppWebsocket::connect((host, port), issued.token, signer.clone()),
+            )
+            .await;
+            match attempt {
+                Ok(Ok(app_ws)) => return Ok(app_ws),
+                Ok(Err(_)) if Instant::now() + READY_POLL_INTERVAL < deadline => {
+                    tokio::time::sleep(READY_POLL_INTERVAL).await;
+                }
+                Ok(Err(err)) => {
+                    return Err(err.context(format!(
+                        "App interface on port {port} was not ready within {ready_timeout:?}"
+                    )))
+                }
+                Err(_) => {
+                    return Err(anyhow!(
+                        "App interface on port {port} was not ready within {ready_timeout:?}"
+                    ))
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use anyhow::Result;
+use holochain_types::{
+    app::{AppBundle, AppManifestV1, AppRoleDnaManifest, AppRoleManifest, CellProvisioning},
+    dna::DnaBundle,
+    prelude::DnaModifiersOpt,
+};
+use holochain_zome_types::prelude::RoleName;
+use std::path::{Path, PathBuf};
+
+/// Assembles an [AppBundle] in memory from DNA bundles.
+///
+/// Useful for tests and tooling that need a minimal app without packing a `.happ` file on disk.
+/// The built bundle can be installed with [AppBundleSource::Bundle](holochain_types::app::AppBundleSource::Bundle).
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// use holochain_client::AppBundleBuilder;
+/// use holochain_types::dna::DnaBundle;
+/// use std::path::Path;
+///
+/// let dna = DnaBundle::read_from_file(Path::new("./my-dna.dna")).await?;
+/// let bundle = AppBundleBuilder::new("my-app").role("my_role", dna).build().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct AppBundleBuilder {
+    name: String,
+    description: Option<String>,
+    allow_deferred_memproofs: bool,
+    roles: Vec<(AppRoleManifest, DnaBundle)>,
+}
+
+impl AppBundleBuilder {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            allow_deferred_memproofs: false,
+            roles: Vec::new(),
+        }
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn allow_deferred_memproofs(mut self, allow_deferred_memproofs: bool) -> Self {
+        self.allow_deferred_memproofs = allow_deferred_memproofs;
+        self
+    }
+
+    /// Add a role whose cell is created on install, with default modifiers and no clones allowed.
+    pub fn role(self, role_name: impl Into<RoleName>, dna: DnaBundle) -> Self {
+        let role = AppRoleManifest {
+            name: role_name.into(),
+            provisioning: Some(CellProvisioning::Create { deferred: false }),
+            dna: AppRoleDnaManifest {
+                location: None,
+                modifiers: DnaModifiersOpt::none(),
+                installed_hash: None,
+                clone_limit: 0,
+            },
+        };
+        self.role_with_manifest(role, dna)
+    }
+
+    /// Add a role with a custom manifest, for example to set modifiers or a clone limit.
+    ///
+    /// The location of the DNA in the manifest is replaced by the location of `dna` in the bundle.
+    pub fn role_with_manifest(mut self, role: AppRoleManifest, dna: DnaBundle) -> Self {
+        self.roles.push((role, dna));
+        self
+    }
+
+    /// Add a role with the DNA bundle read from a `.dna` file.
+    pub async fn role_from_file(
+        self,
+        role_name: impl Into<RoleName>,
+        dna_path: impl AsRef<Path>,
+    ) -> Result<Self> {
+        let dna = DnaBundle::read_from_file(dna_path.as_ref()).await?;
+        Ok(self.role(role_name, dna))
+    }
+
+    pub async fn build(self) -> Result<AppBundle> {
+        let (roles, resources): (Vec<_>, Vec<_>) = self
+            .roles
+            .into_iter()
+            .map(|(mut role, dna)| {
+                let path = PathBuf::from(format!("{}.dna", role.name));
+                role.dna.location = Some(path.clone().into());
+                (role, (path, dna))
+            })
+            .unzip();
+
+        let manifest = AppManifestV1 {
+            name: self.name,
+            description: self.description,
+            roles,
+            allow_deferred_memproofs: self.allow_deferred_memproofs,
+        };
+
+        Ok(AppBundle::new(manifest.into(), resources, PathBuf::from(".")).await?)
+    }
+}
@@ -0,0 +1,123 @@
+use crate::{AdminWebsocket, ConductorApiResult};
+use futures::{stream, Stream};
+use holochain_conductor_api::FullIntegrationStateDump;
+use holochain_types::prelude::{CellId, DhtOp, DhtOpHash, DhtOpHashed};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::{Duration, Instant},
+};
+
+/// The stage of processing a DHT op is waiting in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LimboStage {
+    Validation,
+    Integration,
+}
+
+/// DHT ops of a cell which have been waiting in a stage for longer than expected, see
+/// [AdminWebsocket::cell_error_watcher].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StuckOps {
+    pub cell_id: CellId,
+    pub stage: LimboStage,
+    pub ops: Vec<DhtOpHash>,
+}
+
+impl AdminWebsocket {
+    /// Watch a cell for background operations which don't complete.
+    ///
+    /// The conductor does not report failures of background operations such as validation to
+    /// clients, so this polls the state of the cell every `poll_interval`. DHT ops which stay in
+    /// validation or integration limbo for longer than `stuck_after` are reported once as
+    /// [StuckOps]. Errors while polling are reported without ending the stream.
+    ///
+    /// Each poll dumps the full state of the cell, so choose an interval of seconds rather than
+    /// milliseconds for cells with a lot of data.
+    pub fn cell_error_watcher(
+        &self,
+        cell_id: CellId,
+        poll_interval: Duration,
+        stuck_after: Duration,
+    ) -> impl Stream<Item = ConductorApiResult<StuckOps>> + '_ {
+        stream::unfold(
+            (LimboTracker::default(), true),
+            move |(mut tracker, mut first)| {
+                let cell_id = cell_id.clone();
+                async move {
+                    loop {
+                        if let Some(stuck) = tracker.pending.pop_front() {
+                            return Some((Ok(stuck), (tracker, first)));
+                        }
+                        if !first {
+                            tokio::time::sleep(poll_interval).await;
+                        }
+                        first = false;
+                        match self.dump_full_state(cell_id.clone(), None).await {
+                            Ok(dump) => {
+                                tracker.update(&cell_id, &dump.integration_dump, stuck_after)
+                            }
+                            Err(err) => return Some((Err(err), (tracker, first))),
+                        }
+                    }
+                }
+            },
+        )
+    }
+}
+
+#[derive(Default)]
+struct LimboTracker {
+    first_seen: HashMap<DhtOpHash, (LimboStage, Instant)>,
+    reported: HashSet<DhtOpHash>,
+    pending: VecDeque<StuckOps>,
+}
+
+impl LimboTracker {
+    fn update(&mut self, cell_id: &CellId, dump: &FullIntegrationStateDump, stuck_after: Duration) {
+        let now = Instant::now();
+        let in_limbo: HashMap<DhtOpHash, LimboStage> = dump
+            .validation_limbo
+            .iter()
+            .map(|op| (op_hash(op), LimboStage::Validation))
+            .chain(
+                dump.integration_limbo
+                    .iter()
+                    .map(|op| (op_hash(op), LimboStage::Integration)),
+            )
+            .collect();
+
+        // An op which moved on to the next stage starts waiting afresh.
+        self.first_seen
+            .retain(|hash, (stage, _)| in_limbo.get(hash) == Some(&*stage));
+        let first_seen = &self.first_seen;
+        self.reported.retain(|hash| first_seen.contains_key(hash));
+        for (hash, stage) in in_limbo {
+            self.first_seen.entry(hash).or_insert((stage, now));
+        }
+
+        for stage in [LimboStage::Validation, LimboStage::Integration] {
+            let ops: Vec<DhtOpHash> = self
+                .first_seen
+                .iter()
+                .filter(|(hash, (op_stage, since))| {
+                    *op_stage == stage
+                        && now.duration_since(*since) >= stuck_after
+                        && !self.reported.contains(*hash)
+                })
+                .map(|(hash, _)| hash.clone())
+                .collect();
+            if !ops.is_empty() {
+                self.reported.extend(ops.iter().cloned());
+                self.pending.push_back(StuckOps {
+                    cell_id: cell_id.clone(),
+                    stage,
+                    ops,
+                });
+            }
+        }
+    }
+}
+
+fn op_hash(op: &DhtOp) -> DhtOpHash {
+    DhtOpHashed::from_content_sync(op.clone()).hash
+}
@@ -0,0 +1,190 @@
+use crate::{
+    AdminWebsocket, AppWebsocket, AuthorizeSigningCredentialsPayload, ClientAgentSigner,
+    ConnectOptions, WebsocketConfig,
+};
+use anyhow::{anyhow, Context, Result};
+use holochain_conductor_api::CellInfo;
+use holochain_types::{app::InstalledAppId, websocket::AllowedOrigins};
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    net::{IpAddr, ToSocketAddrs},
+    sync::Arc,
+};
+use tokio::sync::OnceCell;
+
+/// A client for a conductor which manages the app connections for you.
+///
+/// The client owns an admin connection. App connections are created the first time an app is
+/// requested with [HolochainClient::app], which takes care of attaching an app interface, issuing an
+/// authentication token and authorizing signing credentials for the app's cells. Subsequent requests
+/// for the same app return the same connection.
+///
+/// The app interface is attached once and shared by all apps. By default it accepts connections
+/// from any origin, because the client connects from a native process rather than a browser. Use
+/// [HolochainClient::with_allowed_origins] to restrict it.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// use holochain_client::HolochainClient;
+/// use holochain_types::prelude::ExternIO;
+/// use std::net::Ipv4Addr;
+///
+/// let client = HolochainClient::connect((Ipv4Addr::LOCALHOST, 30_000)).await?;
+/// let response = client
+///     .app("my-app")
+///     .await?
+///     .call_zome(
+///         "my_role".to_string().into(),
+///         "my_zome".into(),
+///         "my_fn".into(),
+///         ExternIO::encode(())?,
+///     )
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct HolochainClient {
+    admin_ws: AdminWebsocket,
+    options: ConnectOptions,
+    host: IpAddr,
+    allowed_origins: AllowedOrigins,
+    app_port: OnceCell<u16>,
+    apps: Mutex<HashMap<InstalledAppId, Arc<OnceCell<AppWebsocket>>>>,
+}
+
+impl HolochainClient {
+    /// Connect to the admin interface of a conductor.
+    pub async fn connect(admin_addr: impl ToSocketAddrs) -> Result<Self> {
+        Self::connect_with_options(admin_addr, ConnectOptions::default()).await
+    }
+
+    /// Connect to the admin interface of a conductor with a custom WebsocketConfig, which is used
+    /// for the app connections too.
+    pub async fn connect_with_config(
+        admin_addr: impl ToSocketAddrs,
+        websocket_config: Arc<WebsocketConfig>,
+    ) -> Result<Self> {
+        Self::connect_with_options(
+            admin_addr,
+            ConnectOptions::default().with_websocket_config(websocket_config),
+        )
+        .await
+    }
+
+    /// Connect to the admin interface of a conductor with custom [ConnectOptions], which are used
+    /// for the app connections too.
+    pub async fn connect_with_options(
+        admin_addr: impl ToSocketAddrs,
+        options: ConnectOptions,
+    ) -> Result<Self> {
+        let admin_addr = admin_addr
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| anyhow!("Invalid admin websocket address"))?;
+        let admin_ws = AdminWebsocket::connect_with_options(admin_addr, options.clone()).await?;
+
+        Ok(Self {
+            admin_ws,
+            options,
+            host: admin_addr.ip(),
+            allowed_origins: AllowedOrigins::Any,
+            app_port: OnceCell::new(),
+            apps: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Set the origins the app interface accepts connections from, [AllowedOrigins::Any] by
+    /// default. Only applies if no app has been requested yet, since the interface is attached
+    /// when the first app is.
+    pub fn with_allowed_origins(mut self, allowed_origins: AllowedOrigins) -> Self {
+        self.allowed_origins = allowed_origins;
+        self
+    }
+
+    /// The admin connection, for operations such as installing apps.
+    pub fn admin(&self) -> &AdminWebsocket {
+        &self.admin_ws
+    }
+
+    /// Get a connection to an installed app, connecting on first use.
+    ///
+    /// Signing credentials are authorized for the cells the app has when it is first connected. If
+    /// clone cells are created later, authorize them with [AdminWebsocket::authorize_signing_credentials]
+    /// or drop the session with [HolochainClient::close_app] and request it again.
+    ///
+    /// Requests for different apps connect concurrently. Concurrent requests for the same app share
+    /// a single connection attempt.
+    pub async fn app(&self, installed_app_id: &str) -> Result<AppWebsocket> {
+        let app = self
+            .apps
+            .lock()
+            .entry(installed_app_id.to_string())
+            .or_default()
+            .clone();
+        let app_ws = app
+            .get_or_try_init(|| self.connect_app(installed_app_id))
+            .await?;
+
+        Ok(app_ws.clone())
+    }
+
+    async fn connect_app(&self, installed_app_id: &str) -> Result<AppWebsocket> {
+        let app_port = self
+            .app_port
+            .get_or_try_init(|| async {
+                self.admin_ws
+                    .attach_app_interface(0, self.allowed_origins.clone(), None)
+                    .await
+                    .context("Failed to attach app interface")
+            })
+            .await?;
+        let issued = self
+            .admin_ws
+            .issue_app_auth_token(installed_app_id.to_string().into())
+            .await
+            .with_context(|| format!("Failed to issue token for app {installed_app_id}"))?;
+
+        let signer = ClientAgentSigner::default();
+        let app_ws = AppWebsocket::connect_with_options(
+            (self.host, *app_port),
+            issued.token,
+            signer.clone().into(),
+            self.options.clone(),
+        )
+        .await?;
+        for cell_info in app_ws
+            .cached_app_info()
+            .app_info
+            .cell_info
+            .values()
+            .flatten()
+        {
+            let cell_id = match cell_info {
+                CellInfo::Provisioned(cell) => cell.cell_id.clone(),
+                CellInfo::Cloned(cell) => cell.cell_id.clone(),
+                _ => continue,
+            };
+            let credentials = self
+                .admin_ws
+                .authorize_signing_credentials(AuthorizeSigningCredentialsPayload {
+                    cell_id: cell_id.clone(),
+                    functions: None,
+                })
+                .await?;
+            signer.add_credentials(cell_id, credentials);
+        }
+
+        Ok(app_ws)
+    }
+
+    /// Drop the connection to an app, if one is open.
+    ///
+    /// The connection closes once all clones of it returned by [HolochainClient::app] are dropped.
+    pub async fn close_app(&self, installed_app_id: &str) {
+        self.apps.lock().remove(installed_app_id);
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{AdminWebsocket, AppWebsocket};
+use std::future::Future;
+use tokio::task::JoinHandle;
+
+/// Owns the connections and background tasks of a process, to shut them down in order.
+///
+/// Shutting down stops signal delivery on the app connections, then stops the background tasks,
+/// such as signal consumers, and waits for them to finish. Only then are the app connections and
+/// finally the admin connection closed, so tasks never observe a connection going away underneath
+/// them.
+///
+/// Dropping the set without calling [ClientSet::shutdown] tears down in the same order, without
+/// waiting for the tasks to finish.
+#[derive(Default)]
+pub struct ClientSet {
+    // Fields are dropped in declaration order.
+    tasks: Vec<JoinHandle<()>>,
+    app_connections: Vec<AppWebsocket>,
+    admin_ws: Option<AdminWebsocket>,
+}
+
+impl ClientSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take ownership of the admin connection. Any previous admin connection is closed.
+    pub fn set_admin(&mut self, admin_ws: AdminWebsocket) {
+        self.admin_ws = Some(admin_ws);
+    }
+
+    pub fn admin(&self) -> Option<&AdminWebsocket> {
+        self.admin_ws.as_ref()
+    }
+
+    /// Take ownership of an app connection.
+    pub fn add_app(&mut self, app_ws: AppWebsocket) {
+        self.app_connections.push(app_ws);
+    }
+
+    /// The app connections owned by the set, in the order they were added.
+    pub fn apps(&self) -> &[AppWebsocket] {
+        &self.app_connections
+    }
+
+    /// Spawn a background task that is stopped when the set shuts down.
+    pub fn spawn(&mut self, task: impl Future<Output = ()> + Send + 'static) {
+        self.tasks.push(tokio::spawn(task));
+    }
+
+    /// Shut down the background tasks and connections in order.
+    pub async fn shutdown(mut self) {
+        self.stop_signals_and_tasks();
+        for task in std::mem::take(&mut self.tasks) {
+            // Tasks end with a cancellation error once aborted.
+            let _ = task.await;
+        }
+
+        drop(std::mem::take(&mut self.app_connections));
+        drop(self.admin_ws.take());
+    }
+
+    fn stop_signals_and_tasks(&self) {
+        for app_ws in &self.app_connections {
+            app_ws.pause_signals();
+        }
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+impl Drop for ClientSet {
+    fn drop(&mut self) {
+        self.stop_signals_and_tasks();
+    }
+}
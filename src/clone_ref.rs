@@ -0,0 +1,137 @@
+use crate::CloneRefError;
+use holo_hash::DnaHash;
+use holochain_conductor_api::{AppInfo, CellInfo};
+use holochain_types::prelude::{CloneCellId, CloneId};
+use holochain_zome_types::{
+    clone::ClonedCell,
+    prelude::{CellId, RoleName},
+};
+use std::{fmt, str::FromStr};
+
+/// A reference to a clone cell of an app.
+///
+/// Within an app, a clone cell is identified by its clone id, such as `my_role.0`, or by the
+/// hash of its DNA, which is unique because every clone has different DNA modifiers. Both can be
+/// converted into a `CloneRef` which the clone cell methods of the clients accept:
+///
+/// - a [CloneId], or a string parsed with [str::parse],
+/// - a [CellId] or [DnaHash],
+/// - a [ClonedCell] returned when creating or enabling a clone cell,
+/// - a [CloneCellId] as used in the conductor API payloads.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CloneRef {
+    CloneId(CloneId),
+    DnaHash(DnaHash),
+}
+
+impl CloneRef {
+    /// Parse and validate a clone id of the form `<role name>.<clone index>`.
+    pub fn parse_clone_id(clone_id: &str) -> Result<CloneId, CloneRefError> {
+        let invalid = || CloneRefError::InvalidCloneId(clone_id.to_string());
+        let (role_name, index) = clone_id.rsplit_once('.').ok_or_else(invalid)?;
+        if role_name.is_empty() {
+            return Err(invalid());
+        }
+        let index = index.parse::<u32>().map_err(|_| invalid())?;
+        Ok(CloneId::new(&RoleName::from(role_name), index))
+    }
+
+    /// Find the referenced clone cell in the cells of an app.
+    pub fn find<'a>(&self, app_info: &'a AppInfo) -> Option<&'a ClonedCell> {
+        app_info
+            .cell_info
+            .values()
+            .flatten()
+            .find_map(|cell_info| match cell_info {
+                CellInfo::Cloned(cloned_cell) if self.matches(cloned_cell) => Some(cloned_cell),
+                _ => None,
+            })
+    }
+
+    /// The cell id of the referenced clone cell in the cells of an app.
+    pub fn cell_id(&self, app_info: &AppInfo) -> Result<CellId, CloneRefError> {
+        self.find(app_info)
+            .map(|cloned_cell| cloned_cell.cell_id.clone())
+            .ok_or_else(|| CloneRefError::NotFound(self.clone()))
+    }
+
+    /// Whether this refers to the given clone cell.
+    pub fn matches(&self, cloned_cell: &ClonedCell) -> bool {
+        match self {
+            CloneRef::CloneId(clone_id) => &cloned_cell.clone_id == clone_id,
+            CloneRef::DnaHash(dna_hash) => cloned_cell.cell_id.dna_hash() == dna_hash,
+        }
+    }
+}
+
+impl fmt::Display for CloneRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CloneRef::CloneId(clone_id) => write!(f, "{}", clone_id.0),
+            CloneRef::DnaHash(dna_hash) => write!(f, "{dna_hash}"),
+        }
+    }
+}
+
+impl FromStr for CloneRef {
+    type Err = CloneRefError;
+
+    /// Parse a clone id, see [CloneRef::parse_clone_id].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CloneRef::parse_clone_id(s).map(CloneRef::CloneId)
+    }
+}
+
+impl From<CloneId> for CloneRef {
+    fn from(clone_id: CloneId) -> Self {
+        CloneRef::CloneId(clone_id)
+    }
+}
+
+impl From<DnaHash> for CloneRef {
+    fn from(dna_hash: DnaHash) -> Self {
+        CloneRef::DnaHash(dna_hash)
+    }
+}
+
+impl From<CellId> for CloneRef {
+    fn from(cell_id: CellId) -> Self {
+        CloneRef::DnaHash(cell_id.dna_hash().clone())
+    }
+}
+
+impl From<&CellId> for CloneRef {
+    fn from(cell_id: &CellId) -> Self {
+        CloneRef::DnaHash(cell_id.dna_hash().clone())
+    }
+}
+
+impl From<&ClonedCell> for CloneRef {
+    fn from(cloned_cell: &ClonedCell) -> Self {
+        CloneRef::CloneId(cloned_cell.clone_id.clone())
+    }
+}
+
+impl From<ClonedCell> for CloneRef {
+    fn from(cloned_cell: ClonedCell) -> Self {
+        CloneRef::CloneId(cloned_cell.clone_id)
+    }
+}
+
+impl From<CloneCellId> for CloneRef {
+    fn from(clone_cell_id: CloneCellId) -> Self {
+        match clone_cell_id {
+            CloneCellId::CloneId(clone_id) => CloneRef::CloneId(clone_id),
+            CloneCellId::DnaHash(dna_hash) => CloneRef::DnaHash(dna_hash),
+        }
+    }
+}
+
+impl From<CloneRef> for CloneCellId {
+    fn from(clone_ref: CloneRef) -> Self {
+        match clone_ref {
+            CloneRef::CloneId(clone_id) => CloneCellId::CloneId(clone_id),
+            CloneRef::DnaHash(dna_hash) => CloneCellId::DnaHash(dna_hash),
+        }
+    }
+}
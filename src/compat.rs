@@ -0,0 +1,119 @@
+//! Knowledge of when requests were added to the conductor API, to explain requests which are
+//! rejected by older conductors.
+
+use crate::ConductorApiError;
+use holochain_conductor_api::ExternalApiWireError;
+
+/// The conductor interface a request is sent to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(not(all(feature = "admin", feature = "app")), allow(dead_code))]
+pub(crate) enum Interface {
+    Admin,
+    App,
+}
+
+/// The Holochain release which introduced a request, by the interface and the request type on the
+/// wire.
+///
+/// Requests which have been part of the conductor API since before 0.3 are not listed.
+const INTRODUCED_IN: &[(Interface, &str, &str)] = &[
+    (Interface::Admin, "issue_app_authentication_token", "0.3.0"),
+    (Interface::Admin, "revoke_app_authentication_token", "0.3.0"),
+    (Interface::Admin, "revoke_agent_key", "0.4.0"),
+    (Interface::Admin, "get_compatible_cells", "0.4.0"),
+    (Interface::App, "provide_memproofs", "0.4.0"),
+    (Interface::App, "enable_app", "0.4.0"),
+    (Interface::App, "list_wasm_host_functions", "0.4.0"),
+];
+
+/// The Holochain release which introduced a request, if it is known to be recent.
+pub(crate) fn minimum_version_hint(interface: Interface, operation: &str) -> Option<&'static str> {
+    INTRODUCED_IN
+        .iter()
+        .find(|(known_interface, name, _)| *known_interface == interface && *name == operation)
+        .map(|(_, _, version)| *version)
+}
+
+/// Recognise a conductor error which means that the conductor doesn't know the request type.
+///
+/// Conductors fail to deserialize request types they don't know, and report the type as an unknown
+/// variant. Unknown variants of enums within the request, such as a newer kind of clone cell id,
+/// are not recognised, since the conductor does know the request.
+pub(crate) fn unsupported_by_conductor(
+    interface: Interface,
+    operation: &str,
+    error: &ExternalApiWireError,
+) -> Option<ConductorApiError> {
+    match error {
+        ExternalApiWireError::Deserialization(message)
+            if message.contains(&format!("unknown variant `{operation}`")) =>
+        {
+            Some(ConductorApiError::UnsupportedByConductor {
+                operation: operation.to_string(),
+                minimum_version_hint: minimum_version_hint(interface, operation),
+            })
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deserialization_error(message: &str) -> ExternalApiWireError {
+        ExternalApiWireError::Deserialization(message.to_string())
+    }
+
+    #[test]
+    fn unknown_request_type_is_unsupported() {
+        let error = deserialization_error(
+            "unknown variant `list_wasm_host_functions`, expected one of `app_info`, `call_zome`",
+        );
+        let unsupported =
+            unsupported_by_conductor(Interface::App, "list_wasm_host_functions", &error);
+
+        assert!(matches!(
+            unsupported,
+            Some(ConductorApiError::UnsupportedByConductor {
+                ref operation,
+                minimum_version_hint: Some("0.4.0"),
+            }) if operation == "list_wasm_host_functions"
+        ));
+    }
+
+    #[test]
+    fn unknown_variant_within_the_request_is_not_unsupported() {
+        let error =
+            deserialization_error("unknown variant `clone_id`, expected `dna_hash` or `cell_id`");
+
+        assert!(unsupported_by_conductor(Interface::App, "disable_clone_cell", &error).is_none());
+        assert!(
+            unsupported_by_conductor(Interface::Admin, "attach_app_interface", &error).is_none()
+        );
+        assert!(unsupported_by_conductor(
+            Interface::Admin,
+            "attach_app_interface",
+            &deserialization_error("invalid type: string, expected a sequence"),
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn version_hints_are_per_interface() {
+        assert_eq!(
+            minimum_version_hint(Interface::App, "enable_app"),
+            Some("0.4.0")
+        );
+        // Enabling apps has always been part of the admin interface.
+        assert_eq!(minimum_version_hint(Interface::Admin, "enable_app"), None);
+        assert_eq!(
+            minimum_version_hint(Interface::Admin, "revoke_agent_key"),
+            Some("0.4.0")
+        );
+        assert_eq!(
+            minimum_version_hint(Interface::App, "revoke_agent_key"),
+            None
+        );
+    }
+}
@@ -0,0 +1,289 @@
+#[cfg(feature = "tls")]
+use crate::TlsOptions;
+use crate::{ConductorApiError, ReconnectPolicy};
+use anyhow::{bail, ensure, Context, Result};
+use holochain_types::app::InstalledAppId;
+use holochain_websocket::{connect, WebsocketConfig, WebsocketReceiver, WebsocketSender};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+/// Options for establishing a websocket connection to the conductor.
+#[derive(Clone, Debug)]
+pub struct ConnectOptions {
+    /// Configuration of the underlying websocket.
+    pub websocket_config: Arc<WebsocketConfig>,
+    /// How to retry when the connection can't be established.
+    pub retry: ConnectRetry,
+    /// The maximum time to spend establishing the connection, including all retries.
+    ///
+    /// Without a timeout, connecting to a host that drops packets only fails once the operating
+    /// system gives up on the TCP handshake, which can take minutes.
+    pub connect_timeout: Option<Duration>,
+    /// The maximum time to wait for the response to a request, after which the request fails with
+    /// [ConductorApiError::RequestTimedOut].
+    ///
+    /// Without a timeout, requests fail after the `default_request_timeout` of the websocket
+    /// config with a websocket error. A longer timeout than that also needs a longer
+    /// `default_request_timeout`.
+    pub request_timeout: Option<Duration>,
+    /// How to restore the connection after it was established and then lost. Without a policy,
+    /// the client stays disconnected.
+    pub reconnect: Option<ReconnectPolicy>,
+    /// Connect with TLS, to a conductor behind a TLS-terminating reverse proxy.
+    #[cfg(feature = "tls")]
+    pub tls: Option<TlsOptions>,
+}
+
+impl Default for ConnectOptions {
+    fn default() -> Self {
+        Self {
+            websocket_config: Arc::new(WebsocketConfig::CLIENT_DEFAULT),
+            retry: ConnectRetry::default(),
+            connect_timeout: None,
+            request_timeout: None,
+            reconnect: None,
+            #[cfg(feature = "tls")]
+            tls: None,
+        }
+    }
+}
+
+impl ConnectOptions {
+    pub fn with_websocket_config(mut self, websocket_config: Arc<WebsocketConfig>) -> Self {
+        self.websocket_config = websocket_config;
+        self
+    }
+
+    pub fn with_retry(mut self, retry: ConnectRetry) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn with_connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    pub fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = Some(request_timeout);
+        self
+    }
+
+    pub fn with_reconnect(mut self, reconnect: ReconnectPolicy) -> Self {
+        self.reconnect = Some(reconnect);
+        self
+    }
+
+    #[cfg(feature = "tls")]
+    pub fn with_tls(mut self, tls: TlsOptions) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+}
+
+/// The endpoint a client is connected to, for logging and reconnecting.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConnectionInfo {
+    pub addr: SocketAddr,
+    /// The app an app connection is authenticated for. Always `None` for admin connections.
+    pub installed_app_id: Option<InstalledAppId>,
+}
+
+/// Retry behavior for establishing a connection.
+///
+/// Retrying is useful when a conductor is still starting up, but it delays reporting genuine
+/// misconfiguration such as a wrong port. Use [ConnectRetry::Disabled] to fail on the first error.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConnectRetry {
+    /// Make a single connection attempt.
+    Disabled,
+    /// Retry with a fixed delay between attempts.
+    Fixed { delay: Duration, max_retries: usize },
+    /// Retry with a delay that doubles after every attempt.
+    Exponential {
+        initial_delay: Duration,
+        max_retries: usize,
+    },
+}
+
+impl Default for ConnectRetry {
+    /// Retry up to 5 times, starting with a delay of 1 second.
+    fn default() -> Self {
+        ConnectRetry::Exponential {
+            initial_delay: Duration::from_secs(1),
+            max_retries: 5,
+        }
+    }
+}
+
+impl ConnectRetry {
+    fn policy(&self) -> again::RetryPolicy {
+        match self {
+            ConnectRetry::Disabled => again::RetryPolicy::fixed(Duration::ZERO).with_max_retries(0),
+            ConnectRetry::Fixed { delay, max_retries } => {
+                again::RetryPolicy::fixed(*delay).with_max_retries(*max_retries)
+            }
+            ConnectRetry::Exponential {
+                initial_delay,
+                max_retries,
+            } => again::RetryPolicy::exponential(*initial_delay).with_max_retries(*max_retries),
+        }
+    }
+}
+
+/// Connect a websocket to the given address, retrying according to the options.
+///
+/// If the connect timeout elapses, the returned error is a [ConductorApiError::ConnectTimedOut].
+pub(crate) async fn connect_websocket(
+    addr: SocketAddr,
+    options: &ConnectOptions,
+) -> Result<(WebsocketSender, WebsocketReceiver)> {
+    match options.connect_timeout {
+        Some(timeout) => tokio::time::timeout(timeout, connect_with_retry(addr, options))
+            .await
+            .map_err(|_| ConductorApiError::ConnectTimedOut { addr, timeout })?,
+        None => connect_with_retry(addr, options).await,
+    }
+}
+
+async fn connect_with_retry(
+    addr: SocketAddr,
+    options: &ConnectOptions,
+) -> Result<(WebsocketSender, WebsocketReceiver)> {
+    let mut attempts = 0;
+    let result = options
+        .retry
+        .policy()
+        .retry(|| {
+            attempts += 1;
+            connect_once(addr, options)
+        })
+        .await;
+
+    result.with_context(|| format!("Failed to connect to {addr} after {attempts} attempt(s)"))
+}
+
+async fn connect_once(
+    addr: SocketAddr,
+    options: &ConnectOptions,
+) -> Result<(WebsocketSender, WebsocketReceiver)> {
+    #[cfg(feature = "tls")]
+    if let Some(tls) = &options.tls {
+        let tunnel = crate::tls::open_tunnel(addr, tls).await?;
+        let request = holochain_websocket::ConnectRequest::new(tunnel.addr)
+            .try_set_header(crate::tls::TUNNEL_TOKEN_HEADER, &tunnel.token)?;
+        return Ok(connect(options.websocket_config.clone(), request).await?);
+    }
+
+    Ok(connect(options.websocket_config.clone(), addr).await?)
+}
+
+/// Split a `ws://` or `wss://` URL into the host and port to connect to. For `wss://` URLs, TLS
+/// is enabled in the options with the host as the server name, unless the options configure TLS
+/// already, and the path of the URL is used as the [path](TlsOptions::path) of the TLS options.
+pub(crate) fn websocket_url_target(
+    url: &str,
+    options: ConnectOptions,
+) -> Result<((String, u16), ConnectOptions)> {
+    let (tls, rest) = if let Some(rest) = url.strip_prefix("wss://") {
+        (true, rest)
+    } else if let Some(rest) = url.strip_prefix("ws://") {
+        (false, rest)
+    } else {
+        bail!("Unsupported websocket URL {url}, expected ws:// or wss://");
+    };
+    let (authority, path) = match rest.find(['/', '?']) {
+        Some(index) => (&rest[..index], &rest[index..]),
+        None => (rest, ""),
+    };
+    // Only the tunnel of a TLS connection sends a path with the websocket handshake.
+    ensure!(
+        tls || path.is_empty() || path == "/",
+        "Websocket URLs with a path are only supported with wss://: {url}"
+    );
+    let (host, port) = match authority.rsplit_once(':') {
+        // The colons of an IPv6 address are enclosed in brackets.
+        Some((host, port)) if !port.ends_with(']') => (
+            host,
+            port.parse()
+                .with_context(|| format!("Invalid port in websocket URL {url}"))?,
+        ),
+        _ => (authority, if tls { 443 } else { 80 }),
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    ensure!(!host.is_empty(), "Missing host in websocket URL {url}");
+
+    let options = if tls {
+        with_url_tls(options, host, path, url)?
+    } else {
+        options
+    };
+
+    Ok(((host.to_string(), port), options))
+}
+
+#[cfg(feature = "tls")]
+fn with_url_tls(
+    mut options: ConnectOptions,
+    host: &str,
+    path: &str,
+    _url: &str,
+) -> Result<ConnectOptions> {
+    let tls = options.tls.get_or_insert_with(|| TlsOptions::new(host));
+    if !path.is_empty() {
+        tls.path = if path.starts_with('/') {
+            path.to_string()
+        } else {
+            format!("/{path}")
+        };
+    }
+    Ok(options)
+}
+
+#[cfg(not(feature = "tls"))]
+fn with_url_tls(
+    _options: ConnectOptions,
+    _host: &str,
+    _path: &str,
+    url: &str,
+) -> Result<ConnectOptions> {
+    bail!("Connecting to {url} needs the `tls` feature of holochain_client");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ws_url_target() {
+        let (target, _) =
+            websocket_url_target("ws://localhost:30000", ConnectOptions::default()).unwrap();
+        assert_eq!(target, ("localhost".to_string(), 30_000));
+
+        let (target, _) = websocket_url_target("ws://[::1]", ConnectOptions::default()).unwrap();
+        assert_eq!(target, ("::1".to_string(), 80));
+
+        assert!(websocket_url_target("ws://localhost/admin", ConnectOptions::default()).is_err());
+        assert!(websocket_url_target("http://localhost", ConnectOptions::default()).is_err());
+    }
+
+    #[cfg(feature = "tls")]
+    #[test]
+    fn wss_url_target() {
+        let (target, options) =
+            websocket_url_target("wss://example.org", ConnectOptions::default()).unwrap();
+        assert_eq!(target, ("example.org".to_string(), 443));
+        let tls = options.tls.unwrap();
+        assert_eq!(tls.server_name, "example.org");
+        assert_eq!(tls.path, "/");
+
+        let (target, options) = websocket_url_target(
+            "wss://[2001:db8::1]:8443/conductor/admin",
+            ConnectOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(target, ("2001:db8::1".to_string(), 8443));
+        let tls = options.tls.unwrap();
+        assert_eq!(tls.server_name, "2001:db8::1");
+        assert_eq!(tls.path, "/conductor/admin");
+    }
+}
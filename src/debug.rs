@@ -0,0 +1,172 @@
+//! Tools for debugging intermittent issues with an app, such as enabling apps or cloning cells.
+//!
+//! Enabled with the `debug` feature.
+
+use crate::{AppWebsocket, ConductorApiResult};
+use anyhow::Result;
+use holochain_conductor_api::{AppInfo, AppInfoStatus, CellInfo};
+use holochain_zome_types::prelude::Timestamp;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    future::Future,
+    io::{BufWriter, Write},
+    path::Path,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::task::JoinHandle;
+
+/// Records a sequence of snapshots of an app over time.
+///
+/// Snapshots are taken explicitly with [Timeline::snapshot], around an operation with
+/// [Timeline::around], or at an interval with [Timeline::record_every]. The timeline can be written
+/// to a file with one JSON object per line, which can be inspected with tools like `jq`.
+///
+/// Clones of a timeline record to the same sequence.
+#[derive(Clone, Default)]
+pub struct Timeline {
+    entries: Arc<Mutex<Vec<TimelineEntry>>>,
+}
+
+/// A snapshot of an app at a point in time.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TimelineEntry {
+    pub at: Timestamp,
+    pub label: String,
+    /// The status of the app, or `None` if the app was not found.
+    pub status: Option<AppInfoStatus>,
+    pub cells: Vec<CellSnapshot>,
+    /// The number of requests made on the connection so far.
+    pub requests: u64,
+    /// The number of requests on the connection that failed so far.
+    pub errors: u64,
+}
+
+/// The state of a cell in a [TimelineEntry].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CellSnapshot {
+    pub role_name: String,
+    /// `provisioned`, `cloned` or `stem`.
+    pub kind: String,
+    pub dna_hash: String,
+    /// Whether a clone cell is enabled. Always `true` for provisioned cells and `false` for stem
+    /// cells.
+    pub enabled: bool,
+}
+
+impl Timeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fetch the app info and record a snapshot with the given label.
+    pub async fn snapshot(
+        &self,
+        app_ws: &AppWebsocket,
+        label: impl Into<String>,
+    ) -> ConductorApiResult<()> {
+        let app_info = app_ws.app_info().await?;
+        let total = app_ws.stats().total();
+
+        self.entries.lock().push(TimelineEntry {
+            at: Timestamp::now(),
+            label: label.into(),
+            status: app_info.as_ref().map(|app_info| app_info.status.clone()),
+            cells: app_info.as_ref().map(cell_snapshots).unwrap_or_default(),
+            requests: total.count,
+            errors: total.errors,
+        });
+
+        Ok(())
+    }
+
+    /// Record a snapshot before and after an operation, labelled `<label>:before` and
+    /// `<label>:after`, and return the result of the operation.
+    ///
+    /// Failing to take a snapshot does not affect the operation.
+    pub async fn around<T>(
+        &self,
+        app_ws: &AppWebsocket,
+        label: &str,
+        operation: impl Future<Output = T>,
+    ) -> T {
+        let _ = self.snapshot(app_ws, format!("{label}:before")).await;
+        let output = operation.await;
+        let _ = self.snapshot(app_ws, format!("{label}:after")).await;
+        output
+    }
+
+    /// Record a snapshot every `interval` in a background task, until the returned handle is
+    /// dropped.
+    pub fn record_every(&self, app_ws: AppWebsocket, interval: Duration) -> TimelineRecorder {
+        let timeline = self.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                let _ = timeline.snapshot(&app_ws, "interval").await;
+                tokio::time::sleep(interval).await;
+            }
+        });
+
+        TimelineRecorder { handle }
+    }
+
+    pub fn entries(&self) -> Vec<TimelineEntry> {
+        self.entries.lock().clone()
+    }
+
+    /// Write the timeline with one JSON object per entry and line.
+    pub fn write_to(&self, writer: impl Write) -> Result<()> {
+        let mut writer = BufWriter::new(writer);
+        for entry in self.entries.lock().iter() {
+            serde_json::to_writer(&mut writer, entry)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Write the timeline to a file, see [Timeline::write_to].
+    pub fn dump_to_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        self.write_to(File::create(path)?)
+    }
+}
+
+/// Stops recording snapshots at an interval when dropped, see [Timeline::record_every].
+pub struct TimelineRecorder {
+    handle: JoinHandle<()>,
+}
+
+impl Drop for TimelineRecorder {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+fn cell_snapshots(app_info: &AppInfo) -> Vec<CellSnapshot> {
+    app_info
+        .cell_info
+        .iter()
+        .flat_map(|(role_name, cells)| {
+            cells.iter().map(move |cell| {
+                let (kind, dna_hash, enabled) = match cell {
+                    CellInfo::Provisioned(cell) => {
+                        ("provisioned", cell.cell_id.dna_hash().to_string(), true)
+                    }
+                    CellInfo::Cloned(cell) => {
+                        ("cloned", cell.cell_id.dna_hash().to_string(), cell.enabled)
+                    }
+                    CellInfo::Stem(cell) => ("stem", cell.original_dna_hash.to_string(), false),
+                };
+                CellSnapshot {
+                    role_name: role_name.clone(),
+                    kind: kind.to_string(),
+                    dna_hash,
+                    enabled,
+                }
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,50 @@
+use anyhow::{ensure, Result};
+use holo_hash::DnaHash;
+use holochain_types::{
+    dna::DnaBundle,
+    prelude::{DnaModifiersOpt, YamlProperties},
+};
+use std::path::Path;
+
+/// Compute the hash the conductor gives the DNA of a bundle when it is installed or registered
+/// with the given modifier overrides.
+///
+/// The hash covers the DNA definition with the modifiers applied, so comparing it with the hash
+/// of an installed cell shows whether the conductor installed exactly the intended DNA, see
+/// [verify_dna_hash].
+pub async fn compute_dna_hash(
+    bundle: &DnaBundle,
+    modifiers: DnaModifiersOpt<YamlProperties>,
+) -> Result<DnaHash> {
+    let (_, dna_hash) = bundle
+        .clone()
+        .into_dna_file(modifiers.serialized()?)
+        .await?;
+
+    Ok(dna_hash)
+}
+
+/// Read a `.dna` bundle from a local file and compute its hash, see [compute_dna_hash].
+pub async fn compute_dna_hash_from_file(
+    path: impl AsRef<Path>,
+    modifiers: DnaModifiersOpt<YamlProperties>,
+) -> Result<DnaHash> {
+    let bundle = DnaBundle::read_from_file(path.as_ref()).await?;
+    compute_dna_hash(&bundle, modifiers).await
+}
+
+/// Check that a DNA hash reported by the conductor, such as the DNA hash of a cell returned when
+/// installing an app, is the hash of the bundle with the given modifier overrides.
+pub async fn verify_dna_hash(
+    bundle: &DnaBundle,
+    modifiers: DnaModifiersOpt<YamlProperties>,
+    dna_hash: &DnaHash,
+) -> Result<()> {
+    let expected = compute_dna_hash(bundle, modifiers).await?;
+    ensure!(
+        expected == *dna_hash,
+        "DNA hash {dna_hash} does not match the hash {expected} of the bundle"
+    );
+
+    Ok(())
+}
@@ -1,13 +1,316 @@
 use holochain_conductor_api::ExternalApiWireError;
-use std::error::Error;
+use holochain_types::app::DisabledAppReason;
+use holochain_types::prelude::{SerializedBytesError, Timestamp};
+use holochain_types::signal::SystemSignal;
+use holochain_zome_types::prelude::{CellId, FunctionName, RoleName, ZomeName};
+use std::{error::Error, net::SocketAddr, path::PathBuf, time::Duration};
 
 #[derive(Debug)]
 pub enum ConductorApiError {
+    #[cfg(any(feature = "admin", feature = "app"))]
     WebsocketError(holochain_websocket::WebsocketError),
     ExternalApiWireError(ExternalApiWireError),
     FreshNonceError(Box<dyn Error + Sync + Send>),
     SignZomeCallError(String),
     CellNotFound,
+    /// The role has as many clone cells as its manifest allows.
+    CloneLimitReached {
+        limit: u32,
+    },
+    /// A zome call payload or response could not be (de)serialized.
+    SerializationError(SerializedBytesError),
+    /// The operation is not supported by the conductor or the keystore.
+    UnsupportedOperation {
+        operation: &'static str,
+        reason: &'static str,
+    },
+    /// The conductor does not know the request, because it runs an older version of Holochain.
+    ///
+    /// `minimum_version_hint` is the Holochain release which introduced the request, if known.
+    UnsupportedByConductor {
+        operation: String,
+        minimum_version_hint: Option<&'static str>,
+    },
+    /// No response to a zome call was received before its deadline, after which the conductor
+    /// rejects the call.
+    ZomeCallDeadlineExceeded {
+        expires_at: Timestamp,
+    },
+    /// An install app payload was rejected by the client before sending it.
+    InvalidInstallAppPayload(InstallAppPayloadError),
+    /// The connection to the conductor could not be established within the connect timeout.
+    ConnectTimedOut {
+        addr: SocketAddr,
+        timeout: Duration,
+    },
+    /// The input of a zome call was rejected by its validator, so the call was not sent. See
+    /// [InputValidators](crate::InputValidators).
+    InvalidZomeCallInput {
+        zome_name: ZomeName,
+        fn_name: FunctionName,
+        errors: Vec<String>,
+    },
+    /// No response to a request was received within the request timeout. The conductor may still
+    /// process the request.
+    RequestTimedOut {
+        operation: String,
+        timeout: Duration,
+    },
+    /// The app is disabled according to the cached app info, so the request was not sent.
+    AppDisabled {
+        reason: DisabledAppReason,
+    },
+    /// The operation is outside the role of the admin client, so the request was not sent. See
+    /// [AdminRole](crate::AdminRole).
+    OperationNotAllowed {
+        operation: String,
+        role: String,
+    },
+    /// None of the agents of the installed apps was selected, see
+    /// [AdminWebsocket::install_app_for_existing_agent](crate::AdminWebsocket::install_app_for_existing_agent).
+    NoMatchingAgent,
 }
 
+impl ConductorApiError {
+    /// The structured error reported by the conductor, if this error came from the conductor.
+    pub fn conductor_error(&self) -> Option<ConductorError> {
+        match self {
+            ConductorApiError::ExternalApiWireError(error) => Some(error.into()),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ConductorApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(any(feature = "admin", feature = "app"))]
+            ConductorApiError::WebsocketError(error) => write!(f, "Websocket error: {error}"),
+            ConductorApiError::ExternalApiWireError(error) => {
+                write!(f, "{}", ConductorError::from(error))
+            }
+            ConductorApiError::FreshNonceError(error) => {
+                write!(f, "Failed to generate a nonce: {error}")
+            }
+            ConductorApiError::SignZomeCallError(error) => {
+                write!(f, "Failed to sign zome call: {error}")
+            }
+            ConductorApiError::CellNotFound => write!(f, "Cell not found"),
+            ConductorApiError::CloneLimitReached { limit } => {
+                write!(f, "Clone limit of {limit} reached")
+            }
+            ConductorApiError::SerializationError(error) => {
+                write!(f, "Serialization error: {error}")
+            }
+            ConductorApiError::UnsupportedOperation { operation, reason } => {
+                write!(f, "Unsupported operation {operation}: {reason}")
+            }
+            ConductorApiError::UnsupportedByConductor {
+                operation,
+                minimum_version_hint: Some(version),
+            } => write!(
+                f,
+                "The conductor does not support {operation}, which requires Holochain {version} or later"
+            ),
+            ConductorApiError::UnsupportedByConductor {
+                operation,
+                minimum_version_hint: None,
+            } => write!(f, "The conductor does not support {operation}"),
+            ConductorApiError::ZomeCallDeadlineExceeded { expires_at } => {
+                write!(f, "Zome call deadline exceeded, the call expired at {expires_at}")
+            }
+            ConductorApiError::InvalidInstallAppPayload(error) => {
+                write!(f, "Invalid install app payload: {error}")
+            }
+            ConductorApiError::ConnectTimedOut { addr, timeout } => {
+                write!(f, "Timed out connecting to {addr} after {timeout:?}")
+            }
+            ConductorApiError::InvalidZomeCallInput {
+                zome_name,
+                fn_name,
+                errors,
+            } => write!(
+                f,
+                "Invalid input to {zome_name}/{fn_name}: {}",
+                errors.join("; ")
+            ),
+            ConductorApiError::RequestTimedOut { operation, timeout } => {
+                write!(f, "No response to {operation} within {timeout:?}")
+            }
+            ConductorApiError::AppDisabled { reason } => {
+                write!(f, "App is disabled: {reason:?}")
+            }
+            ConductorApiError::OperationNotAllowed { operation, role } => {
+                write!(f, "Operation {operation} is not allowed for role {role}")
+            }
+            ConductorApiError::NoMatchingAgent => write!(f, "No existing agent matches"),
+        }
+    }
+}
+
+impl Error for ConductorApiError {}
+
 pub type ConductorApiResult<T> = Result<T, ConductorApiError>;
+
+/// A mistake in an install app payload, found by validating it before sending it to the conductor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InstallAppPayloadError {
+    /// The installed app id is empty or has leading or trailing whitespace.
+    InvalidAppId(String),
+    /// The app bundle at the path could not be read or parsed.
+    UnreadableBundle { path: PathBuf, reason: String },
+    /// Role settings were given for a role that the app manifest doesn't have.
+    UnknownRole(RoleName),
+}
+
+impl std::fmt::Display for InstallAppPayloadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InstallAppPayloadError::InvalidAppId(installed_app_id) => {
+                write!(f, "invalid installed app id {installed_app_id:?}")
+            }
+            InstallAppPayloadError::UnreadableBundle { path, reason } => {
+                write!(f, "failed to read app bundle {}: {reason}", path.display())
+            }
+            InstallAppPayloadError::UnknownRole(role_name) => {
+                write!(f, "the app manifest has no role {role_name}")
+            }
+        }
+    }
+}
+
+impl Error for InstallAppPayloadError {}
+
+/// An invalid or unknown [CloneRef](crate::CloneRef).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CloneRefError {
+    /// The clone id is not of the form `<role name>.<clone index>`.
+    InvalidCloneId(String),
+    /// The app has no clone cell which matches the reference.
+    NotFound(crate::CloneRef),
+}
+
+impl std::fmt::Display for CloneRefError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CloneRefError::InvalidCloneId(clone_id) => {
+                write!(f, "invalid clone id {clone_id:?}")
+            }
+            CloneRefError::NotFound(clone_ref) => write!(f, "clone cell {clone_ref} not found"),
+        }
+    }
+}
+
+impl Error for CloneRefError {}
+
+/// A signal which could not be decoded to the expected app signal type.
+#[derive(Debug)]
+pub enum SignalDecodeError {
+    /// The signal was sent by the conductor rather than by a zome.
+    NotAppSignal(SystemSignal),
+    /// The payload of an app signal does not decode to the expected type.
+    Decode {
+        cell_id: CellId,
+        zome_name: ZomeName,
+        error: SerializedBytesError,
+    },
+}
+
+impl std::fmt::Display for SignalDecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignalDecodeError::NotAppSignal(signal) => {
+                write!(f, "expected an app signal, got system signal {signal:?}")
+            }
+            SignalDecodeError::Decode {
+                cell_id,
+                zome_name,
+                error,
+            } => write!(
+                f,
+                "could not decode signal from zome {zome_name} of cell {cell_id:?}: {error}"
+            ),
+        }
+    }
+}
+
+impl Error for SignalDecodeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SignalDecodeError::Decode { error, .. } => Some(error),
+            SignalDecodeError::NotAppSignal(_) => None,
+        }
+    }
+}
+
+/// An error reported by the conductor in response to a request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConductorError {
+    pub kind: ConductorErrorKind,
+    pub message: String,
+}
+
+/// The kind of a [ConductorError], mirrored from [ExternalApiWireError].
+///
+/// New kinds may be added as the conductor API evolves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ConductorErrorKind {
+    /// Any internal error in the conductor.
+    Internal,
+    /// The request could not be deserialized by the conductor.
+    Deserialization,
+    /// The DNA provided was invalid.
+    DnaReadError,
+    /// An error occurred in the ribosome.
+    RibosomeError,
+    /// The app could not be activated.
+    ActivateApp,
+    /// The zome call was not authorized.
+    ZomeCallUnauthorized,
+    /// A countersigning session failed.
+    CountersigningSessionError,
+    /// An error kind that this version of the client does not know about.
+    Other,
+}
+
+impl From<&ExternalApiWireError> for ConductorError {
+    fn from(error: &ExternalApiWireError) -> Self {
+        #[allow(unreachable_patterns)]
+        let (kind, message) = match error {
+            ExternalApiWireError::InternalError(message) => {
+                (ConductorErrorKind::Internal, message.clone())
+            }
+            ExternalApiWireError::Deserialization(message) => {
+                (ConductorErrorKind::Deserialization, message.clone())
+            }
+            ExternalApiWireError::DnaReadError(message) => {
+                (ConductorErrorKind::DnaReadError, message.clone())
+            }
+            ExternalApiWireError::RibosomeError(message) => {
+                (ConductorErrorKind::RibosomeError, message.clone())
+            }
+            ExternalApiWireError::ActivateApp(message) => {
+                (ConductorErrorKind::ActivateApp, message.clone())
+            }
+            ExternalApiWireError::ZomeCallUnauthorized(message) => {
+                (ConductorErrorKind::ZomeCallUnauthorized, message.clone())
+            }
+            ExternalApiWireError::CountersigningSessionError(message) => (
+                ConductorErrorKind::CountersigningSessionError,
+                message.clone(),
+            ),
+            other => (ConductorErrorKind::Other, format!("{other:?}")),
+        };
+
+        Self { kind, message }
+    }
+}
+
+impl std::fmt::Display for ConductorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Conductor error ({:?}): {}", self.kind, self.message)
+    }
+}
+
+impl Error for ConductorError {}
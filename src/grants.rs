@@ -0,0 +1,116 @@
+use holo_hash::ActionHash;
+use holochain_zome_types::{
+    capability::{CapAccess, ZomeCallCapGrant},
+    prelude::{CellId, GrantZomeCallCapabilityPayload, Timestamp},
+};
+use serde::{Deserialize, Serialize};
+
+/// The tag of the capability grants created by
+/// [AdminWebsocket::authorize_signing_credentials](crate::AdminWebsocket::authorize_signing_credentials).
+///
+/// Other clients, such as the JavaScript client, tag their signing credential grants the same
+/// way, so the tag identifies grants for signing credentials regardless of which client created
+/// them.
+pub const ZOME_CALL_SIGNING_KEY_TAG: &str = "zome-call-signing-key";
+
+/// A capability grant created through an [AdminWebsocket](crate::AdminWebsocket).
+///
+/// Grants for signing credentials include the capability secret, so an exported grant must be
+/// stored as securely as the credentials themselves. The secret is not printed by [Debug].
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GrantRecord {
+    pub cell_id: CellId,
+    pub cap_grant: ZomeCallCapGrant,
+}
+
+impl From<GrantZomeCallCapabilityPayload> for GrantRecord {
+    fn from(payload: GrantZomeCallCapabilityPayload) -> Self {
+        Self {
+            cell_id: payload.cell_id,
+            cap_grant: payload.cap_grant,
+        }
+    }
+}
+
+impl From<GrantRecord> for GrantZomeCallCapabilityPayload {
+    fn from(record: GrantRecord) -> Self {
+        Self {
+            cell_id: record.cell_id,
+            cap_grant: record.cap_grant,
+        }
+    }
+}
+
+impl GrantRecord {
+    /// Whether this grant was created for signing credentials, see [ZOME_CALL_SIGNING_KEY_TAG].
+    pub fn is_signing_key_grant(&self) -> bool {
+        is_signing_key_grant(&self.cap_grant)
+    }
+}
+
+/// Custom debug implementation which won't attempt to print the capability secret
+impl std::fmt::Debug for GrantRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GrantRecord")
+            .field("cell_id", &self.cell_id)
+            .field("tag", &self.cap_grant.tag)
+            .field("functions", &self.cap_grant.functions)
+            .field("access", &access_without_secret(&self.cap_grant.access))
+            .finish()
+    }
+}
+
+/// The capability grants created by an [AdminWebsocket](crate::AdminWebsocket), for re-applying
+/// them to a rebuilt conductor with [AdminWebsocket::import_grants](crate::AdminWebsocket::import_grants).
+///
+/// Grants are tied to cells, so the rebuilt conductor must have the same agent keys and DNAs for
+/// the grants to apply to the same cells.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct GrantsExport {
+    pub grants: Vec<GrantRecord>,
+}
+
+/// A capability grant on the source chain of a cell, see
+/// [AdminWebsocket::list_capability_grants](crate::AdminWebsocket::list_capability_grants).
+///
+/// The secret is not printed by [Debug].
+#[derive(Clone)]
+pub struct CapGrantInfo {
+    /// The hash of the action which created the grant.
+    pub action_hash: ActionHash,
+    pub created_at: Timestamp,
+    pub cap_grant: ZomeCallCapGrant,
+}
+
+impl CapGrantInfo {
+    /// Whether this grant was created for signing credentials, see [ZOME_CALL_SIGNING_KEY_TAG].
+    pub fn is_signing_key_grant(&self) -> bool {
+        is_signing_key_grant(&self.cap_grant)
+    }
+}
+
+impl std::fmt::Debug for CapGrantInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CapGrantInfo")
+            .field("action_hash", &self.action_hash)
+            .field("created_at", &self.created_at)
+            .field("tag", &self.cap_grant.tag)
+            .field("functions", &self.cap_grant.functions)
+            .field("access", &access_without_secret(&self.cap_grant.access))
+            .finish()
+    }
+}
+
+/// Grants for signing credentials are assigned to the signing key alone.
+fn is_signing_key_grant(cap_grant: &ZomeCallCapGrant) -> bool {
+    cap_grant.tag == ZOME_CALL_SIGNING_KEY_TAG
+        && matches!(&cap_grant.access, CapAccess::Assigned { assignees, .. } if assignees.len() == 1)
+}
+
+fn access_without_secret(access: &CapAccess) -> String {
+    match access {
+        CapAccess::Unrestricted => "Unrestricted".to_string(),
+        CapAccess::Transferable { .. } => "Transferable".to_string(),
+        CapAccess::Assigned { assignees, .. } => format!("Assigned {assignees:?}"),
+    }
+}
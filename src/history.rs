@@ -0,0 +1,160 @@
+use crate::wire::CarriesSecret;
+use holochain_zome_types::prelude::Timestamp;
+use parking_lot::Mutex;
+use std::{
+    collections::VecDeque,
+    fmt::{self, Debug, Write},
+    time::{Duration, Instant},
+};
+
+/// The maximum number of characters of a request or response kept in a [RequestRecord].
+const MAX_PAYLOAD_CHARS: usize = 512;
+
+/// A request in the debug history of a connection.
+#[derive(Clone, Debug)]
+pub struct RequestRecord {
+    /// The request type on the wire, such as `install_app` or `call_zome`.
+    pub operation: String,
+    pub started_at: Timestamp,
+    pub duration: Duration,
+    pub outcome: RequestOutcome,
+    /// The request, formatted with [Debug] and truncated.
+    ///
+    /// Requests which carry a secret, such as capability grants or zome calls with a capability
+    /// secret, are recorded as `<redacted>`.
+    pub request: String,
+    /// The response, formatted with [Debug] and truncated, if a response was received.
+    ///
+    /// Responses which carry a secret, such as app authentication tokens or state dumps, are
+    /// recorded as `<redacted>`.
+    pub response: Option<String>,
+}
+
+/// The outcome of a request in the debug history.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RequestOutcome {
+    Success,
+    /// The conductor responded with an error.
+    ConductorError,
+    /// No response was received, for example because the connection was closed.
+    NoResponse,
+}
+
+/// A bounded history of the most recent requests of a connection, which is disabled until a
+/// capacity is set.
+#[derive(Default)]
+pub(crate) struct RequestHistory {
+    inner: Mutex<BoundedHistory>,
+}
+
+#[derive(Default)]
+struct BoundedHistory {
+    capacity: usize,
+    records: VecDeque<RequestRecord>,
+}
+
+/// A request that is in flight, see [RequestHistory::start].
+pub(crate) struct PendingRecord {
+    request: String,
+    started_at: Timestamp,
+    instant: Instant,
+}
+
+impl RequestHistory {
+    /// Keep the last `capacity` requests. A capacity of 0 disables the history.
+    pub(crate) fn set_capacity(&self, capacity: usize) {
+        let mut inner = self.inner.lock();
+        inner.capacity = capacity;
+        while inner.records.len() > capacity {
+            inner.records.pop_front();
+        }
+    }
+
+    /// Start recording a request, if the history is enabled.
+    pub(crate) fn start<R: Debug + CarriesSecret>(&self, request: &R) -> Option<PendingRecord> {
+        if self.inner.lock().capacity == 0 {
+            return None;
+        }
+
+        Some(PendingRecord {
+            request: history_entry(request),
+            started_at: Timestamp::now(),
+            instant: Instant::now(),
+        })
+    }
+
+    /// Record a completed request. `response` is `None` if no response was received.
+    pub(crate) fn finish<R: Debug + CarriesSecret>(
+        &self,
+        pending: Option<PendingRecord>,
+        operation: &str,
+        response: Option<&R>,
+        is_error: bool,
+    ) {
+        let Some(pending) = pending else {
+            return;
+        };
+        let outcome = match (response, is_error) {
+            (None, _) => RequestOutcome::NoResponse,
+            (Some(_), true) => RequestOutcome::ConductorError,
+            (Some(_), false) => RequestOutcome::Success,
+        };
+        let record = RequestRecord {
+            operation: operation.to_string(),
+            started_at: pending.started_at,
+            duration: pending.instant.elapsed(),
+            outcome,
+            request: pending.request,
+            response: response.map(history_entry),
+        };
+
+        let mut inner = self.inner.lock();
+        if inner.capacity == 0 {
+            return;
+        }
+        if inner.records.len() == inner.capacity {
+            inner.records.pop_front();
+        }
+        inner.records.push_back(record);
+    }
+
+    pub(crate) fn records(&self) -> Vec<RequestRecord> {
+        self.inner.lock().records.iter().cloned().collect()
+    }
+}
+
+/// Format a request or response for the history, without formatting more of it than is kept.
+fn history_entry<T: Debug + CarriesSecret>(value: &T) -> String {
+    if value.carries_secret() {
+        return "<redacted>".to_string();
+    }
+
+    let mut writer = BoundedWriter {
+        output: String::new(),
+        remaining: MAX_PAYLOAD_CHARS,
+    };
+    // Formatting is aborted with an error once the limit is reached.
+    if write!(writer, "{value:?}").is_err() {
+        writer.output.push_str("...");
+    }
+    writer.output
+}
+
+/// Keeps the first characters written to it and fails once more are written.
+struct BoundedWriter {
+    output: String,
+    remaining: usize,
+}
+
+impl Write for BoundedWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            if self.remaining == 0 {
+                return Err(fmt::Error);
+            }
+            self.output.push(c);
+            self.remaining -= 1;
+        }
+        Ok(())
+    }
+}
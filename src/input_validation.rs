@@ -0,0 +1,140 @@
+use crate::{ConductorApiError, ConductorApiResult};
+use holochain_zome_types::prelude::{ExternIO, FunctionName, ZomeName};
+use serde::de::DeserializeOwned;
+use std::{collections::HashMap, fmt::Debug, marker::PhantomData, sync::Arc};
+
+/// Checks the input of a zome function before the call is signed and sent.
+pub trait InputValidator: Send + Sync {
+    /// Return a message for every problem with the payload, or an empty list if it is valid.
+    fn validate(&self, payload: &ExternIO) -> Vec<String>;
+}
+
+/// Validates inputs by deserializing them into `T`, so that an input is valid if the zome function
+/// can deserialize it. Serde reports the first problem it finds, including the field it is in.
+pub struct TypeValidator<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for TypeValidator<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: DeserializeOwned + Debug> InputValidator for TypeValidator<T> {
+    fn validate(&self, payload: &ExternIO) -> Vec<String> {
+        match payload.decode::<T>() {
+            Ok(_) => Vec::new(),
+            Err(err) => vec![err.to_string()],
+        }
+    }
+}
+
+/// Validates inputs against a JSON Schema. Every problem is reported with the path to the value it
+/// was found at.
+///
+/// Inputs are checked as JSON, so inputs with binary data, such as hashes, cannot be valid.
+#[cfg(feature = "schema")]
+pub struct JsonSchemaValidator {
+    schema: jsonschema::JSONSchema,
+}
+
+#[cfg(feature = "schema")]
+impl JsonSchemaValidator {
+    pub fn new(schema: &serde_json::Value) -> anyhow::Result<Self> {
+        let schema = jsonschema::JSONSchema::compile(schema)
+            .map_err(|err| anyhow::anyhow!("Invalid JSON Schema: {err}"))?;
+
+        Ok(Self { schema })
+    }
+
+    /// Validate inputs against the schema `schemars` generates for `T`.
+    pub fn for_type<T: schemars::JsonSchema>() -> anyhow::Result<Self> {
+        Self::new(&serde_json::to_value(schemars::schema_for!(T))?)
+    }
+}
+
+#[cfg(feature = "schema")]
+impl InputValidator for JsonSchemaValidator {
+    fn validate(&self, payload: &ExternIO) -> Vec<String> {
+        let input = match payload.decode::<serde_json::Value>() {
+            Ok(input) => input,
+            Err(err) => return vec![format!("Input is not valid JSON: {err}")],
+        };
+        match self.schema.validate(&input) {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors
+                .map(|err| match err.instance_path.to_string() {
+                    path if path.is_empty() => err.to_string(),
+                    path => format!("{path}: {err}"),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Validators for the inputs of zome functions, see
+/// [AppWebsocket::with_input_validators](crate::AppWebsocket::with_input_validators).
+///
+/// Useful when payloads come from untyped sources, such as config files or HTTP requests, so that
+/// malformed inputs are rejected with [ConductorApiError::InvalidZomeCallInput] before they are
+/// signed and sent. Functions without a validator are called as usual, unless the validators are
+/// strict.
+#[derive(Clone, Default)]
+pub struct InputValidators {
+    validators: HashMap<(ZomeName, FunctionName), Arc<dyn InputValidator>>,
+    strict: bool,
+}
+
+impl InputValidators {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reject calls to functions which have no validator.
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    pub fn with_validator(
+        mut self,
+        zome_name: impl Into<ZomeName>,
+        fn_name: impl Into<FunctionName>,
+        validator: impl InputValidator + 'static,
+    ) -> Self {
+        self.validators
+            .insert((zome_name.into(), fn_name.into()), Arc::new(validator));
+        self
+    }
+
+    /// Validate the inputs of a function by deserializing them into `T`, see [TypeValidator].
+    pub fn with_type<T: DeserializeOwned + Debug + 'static>(
+        self,
+        zome_name: impl Into<ZomeName>,
+        fn_name: impl Into<FunctionName>,
+    ) -> Self {
+        self.with_validator(zome_name, fn_name, TypeValidator::<T>::default())
+    }
+
+    /// Check the input of a call to a zome function.
+    pub fn validate(
+        &self,
+        zome_name: &ZomeName,
+        fn_name: &FunctionName,
+        payload: &ExternIO,
+    ) -> ConductorApiResult<()> {
+        let errors = match self.validators.get(&(zome_name.clone(), fn_name.clone())) {
+            Some(validator) => validator.validate(payload),
+            None if self.strict => vec!["No input validator is registered".to_string()],
+            None => Vec::new(),
+        };
+        if errors.is_empty() {
+            return Ok(());
+        }
+
+        Err(ConductorApiError::InvalidZomeCallInput {
+            zome_name: zome_name.clone(),
+            fn_name: fn_name.clone(),
+            errors,
+        })
+    }
+}
@@ -0,0 +1,63 @@
+use crate::InstallAppPayloadError;
+use holochain_types::{
+    app::{AppBundle, AppBundleSource, AppManifest, InstallAppPayload},
+    prelude::RoleName,
+};
+use std::collections::HashSet;
+
+/// Check an [InstallAppPayload] for mistakes before sending it to the conductor, see
+/// [AdminWebsocket::install_app_checked](crate::AdminWebsocket::install_app_checked).
+///
+/// The following is checked:
+///
+/// - The installed app id, if given, is not empty and has no leading or trailing whitespace.
+/// - A bundle path can be read and parsed as an app bundle.
+/// - Role settings only refer to roles in the app manifest.
+///
+/// Bundle paths are read from the local file system, so only validate payloads with a path source
+/// when the conductor runs on the same machine.
+pub async fn validate_install_app_payload(
+    payload: &InstallAppPayload,
+) -> Result<(), InstallAppPayloadError> {
+    if let Some(installed_app_id) = &payload.installed_app_id {
+        if installed_app_id.trim().is_empty() || installed_app_id.trim() != installed_app_id {
+            return Err(InstallAppPayloadError::InvalidAppId(
+                installed_app_id.clone(),
+            ));
+        }
+    }
+
+    #[allow(unreachable_patterns)]
+    let known_roles = match &payload.source {
+        AppBundleSource::Bundle(bundle) => role_names(bundle.manifest()),
+        AppBundleSource::Path(path) => {
+            let bundle = AppBundle::read_from_file(path).await.map_err(|err| {
+                InstallAppPayloadError::UnreadableBundle {
+                    path: path.clone(),
+                    reason: err.to_string(),
+                }
+            })?;
+            role_names(bundle.manifest())
+        }
+        // Other sources are left to the conductor to check.
+        _ => return Ok(()),
+    };
+    if let Some(role_name) = payload
+        .roles_settings
+        .iter()
+        .flat_map(|roles_settings| roles_settings.keys())
+        .find(|role_name| !known_roles.contains(*role_name))
+    {
+        return Err(InstallAppPayloadError::UnknownRole(role_name.clone()));
+    }
+
+    Ok(())
+}
+
+fn role_names(manifest: &AppManifest) -> HashSet<RoleName> {
+    manifest
+        .app_roles()
+        .into_iter()
+        .map(|role| role.name)
+        .collect()
+}
@@ -0,0 +1,154 @@
+//! JSON-friendly views of conductor types, for embedding in the responses of REST APIs and
+//! gateways.
+//!
+//! The types of the Conductor API are designed for msgpack: hashes serialize as raw bytes and
+//! enums with data as externally tagged maps. Serialized to JSON, a hash becomes an array of 39
+//! numbers. The views here render hashes in their base64 form, such as `uhCAk...`, use camelCase
+//! field names and plain structs, so they can be used with `#[serde(flatten)]`. With the `schema`
+//! feature they implement `schemars::JsonSchema` for generating OpenAPI specs.
+//!
+//! The views are built from the conductor types and are not meant to be converted back.
+
+use holochain_conductor_api::{AppInfo, AppInfoStatus, CellInfo};
+use holochain_zome_types::prelude::CellId;
+use serde::{Deserialize, Serialize};
+
+/// A JSON view of an [AppInfo].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct JsonAppInfo {
+    pub installed_app_id: String,
+    pub agent_pub_key: String,
+    pub status: JsonAppStatus,
+    /// Why the app is paused or disabled, if it is.
+    pub status_reason: Option<String>,
+    /// The cells of all roles, in the order of the roles in the manifest.
+    pub cells: Vec<JsonCellInfo>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum JsonAppStatus {
+    Running,
+    Paused,
+    Disabled,
+    AwaitingMemproofs,
+}
+
+/// A JSON view of a [CellInfo] and the role it belongs to.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct JsonCellInfo {
+    pub role_name: String,
+    pub kind: JsonCellKind,
+    /// The id of the cell. `None` for stem cells, which have not been created yet.
+    pub cell_id: Option<JsonCellId>,
+    /// The clone id of a clone cell.
+    pub clone_id: Option<String>,
+    pub name: Option<String>,
+    pub network_seed: String,
+    /// Whether the cell is enabled. Only clone cells can be disabled.
+    pub enabled: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum JsonCellKind {
+    Provisioned,
+    Cloned,
+    Stem,
+}
+
+/// A JSON view of a [CellId].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct JsonCellId {
+    pub dna_hash: String,
+    pub agent_pub_key: String,
+}
+
+impl From<&AppInfo> for JsonAppInfo {
+    fn from(app_info: &AppInfo) -> Self {
+        let (status, status_reason) = match &app_info.status {
+            AppInfoStatus::Running => (JsonAppStatus::Running, None),
+            AppInfoStatus::Paused { reason } => {
+                (JsonAppStatus::Paused, Some(format!("{reason:?}")))
+            }
+            AppInfoStatus::Disabled { reason } => {
+                (JsonAppStatus::Disabled, Some(format!("{reason:?}")))
+            }
+            AppInfoStatus::AwaitingMemproofs => (JsonAppStatus::AwaitingMemproofs, None),
+        };
+
+        Self {
+            installed_app_id: app_info.installed_app_id.clone(),
+            agent_pub_key: app_info.agent_pub_key.to_string(),
+            status,
+            status_reason,
+            cells: app_info
+                .cell_info
+                .iter()
+                .flat_map(|(role_name, cells)| {
+                    cells
+                        .iter()
+                        .map(move |cell_info| JsonCellInfo::new(role_name, cell_info))
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<AppInfo> for JsonAppInfo {
+    fn from(app_info: AppInfo) -> Self {
+        Self::from(&app_info)
+    }
+}
+
+impl JsonCellInfo {
+    pub fn new(role_name: &str, cell_info: &CellInfo) -> Self {
+        let role_name = role_name.to_string();
+        match cell_info {
+            CellInfo::Provisioned(cell) => Self {
+                role_name,
+                kind: JsonCellKind::Provisioned,
+                cell_id: Some((&cell.cell_id).into()),
+                clone_id: None,
+                name: Some(cell.name.clone()),
+                network_seed: cell.dna_modifiers.network_seed.clone(),
+                enabled: true,
+            },
+            CellInfo::Cloned(cell) => Self {
+                role_name,
+                kind: JsonCellKind::Cloned,
+                cell_id: Some((&cell.cell_id).into()),
+                clone_id: Some(cell.clone_id.to_string()),
+                name: Some(cell.name.clone()),
+                network_seed: cell.dna_modifiers.network_seed.clone(),
+                enabled: cell.enabled,
+            },
+            CellInfo::Stem(cell) => Self {
+                role_name,
+                kind: JsonCellKind::Stem,
+                cell_id: None,
+                clone_id: None,
+                name: cell.name.clone(),
+                network_seed: cell.dna_modifiers.network_seed.clone(),
+                enabled: false,
+            },
+        }
+    }
+}
+
+impl From<&CellId> for JsonCellId {
+    fn from(cell_id: &CellId) -> Self {
+        Self {
+            dna_hash: cell_id.dna_hash().to_string(),
+            agent_pub_key: cell_id.agent_pubkey().to_string(),
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! A small key-value storage interface for the data the client keeps between runs.
+//!
+//! The offline queue of a [KvLocalStore](crate::sync::KvLocalStore), a
+//! [SignalJournal](crate::SignalJournal) and the credentials of a
+//! [ClientAgentSigner](crate::ClientAgentSigner) can all be persisted to a [KvStore]. The crate
+//! provides [MemoryKvStore] and, with the `sled` feature, [SledKvStore]. Embedded and mobile apps
+//! can implement [KvStore] on top of the storage of the host app instead, such as SQLite.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use std::{collections::BTreeMap, sync::Arc};
+
+/// Asynchronous storage of byte values by byte keys.
+///
+/// Keys are ordered by their bytes, and [KvStore::scan_prefix] returns entries in that order.
+#[async_trait]
+pub trait KvStore: Send + Sync {
+    async fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>>;
+
+    /// Store a value, replacing any value stored under the same key.
+    async fn put(&self, key: &[u8], value: Vec<u8>) -> Result<()>;
+
+    /// Remove the value stored under a key, if there is one.
+    async fn delete(&self, key: &[u8]) -> Result<()>;
+
+    /// All entries whose key starts with `prefix`, ordered by key.
+    async fn scan_prefix(&self, prefix: &[u8]) -> Result<Vec<(Vec<u8>, Vec<u8>)>>;
+}
+
+#[async_trait]
+impl<S: KvStore + ?Sized> KvStore for Arc<S> {
+    async fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        (**self).get(key).await
+    }
+
+    async fn put(&self, key: &[u8], value: Vec<u8>) -> Result<()> {
+        (**self).put(key, value).await
+    }
+
+    async fn delete(&self, key: &[u8]) -> Result<()> {
+        (**self).delete(key).await
+    }
+
+    async fn scan_prefix(&self, prefix: &[u8]) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        (**self).scan_prefix(prefix).await
+    }
+}
+
+/// A [KvStore] which keeps its entries in memory, for tests and for data that need not outlive
+/// the process.
+///
+/// Clones share the same entries.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryKvStore {
+    entries: Arc<RwLock<BTreeMap<Vec<u8>, Vec<u8>>>>,
+}
+
+impl MemoryKvStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl KvStore for MemoryKvStore {
+    async fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self.entries.read().get(key).cloned())
+    }
+
+    async fn put(&self, key: &[u8], value: Vec<u8>) -> Result<()> {
+        self.entries.write().insert(key.to_vec(), value);
+        Ok(())
+    }
+
+    async fn delete(&self, key: &[u8]) -> Result<()> {
+        self.entries.write().remove(key);
+        Ok(())
+    }
+
+    async fn scan_prefix(&self, prefix: &[u8]) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        Ok(self
+            .entries
+            .read()
+            .range(prefix.to_vec()..)
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect())
+    }
+}
+
+/// A [KvStore] backed by a [sled] tree on disk.
+///
+/// Sled flushes writes to disk in the background, see [SledKvStore::flush] to wait for them.
+#[cfg(feature = "sled")]
+#[derive(Clone, Debug)]
+pub struct SledKvStore {
+    tree: sled::Tree,
+}
+
+#[cfg(feature = "sled")]
+impl SledKvStore {
+    /// Open or create a sled database at `path` and store entries in its default tree.
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let db = sled::open(path)?;
+        Ok(Self::new((*db).clone()))
+    }
+
+    /// Store entries in a tree of an open sled database.
+    pub fn new(tree: sled::Tree) -> Self {
+        Self { tree }
+    }
+
+    /// Wait until all previous writes are on disk.
+    pub async fn flush(&self) -> Result<()> {
+        self.tree.flush_async().await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sled")]
+#[async_trait]
+impl KvStore for SledKvStore {
+    async fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self.tree.get(key)?.map(|value| value.to_vec()))
+    }
+
+    async fn put(&self, key: &[u8], value: Vec<u8>) -> Result<()> {
+        self.tree.insert(key, value)?;
+        Ok(())
+    }
+
+    async fn delete(&self, key: &[u8]) -> Result<()> {
+        self.tree.remove(key)?;
+        Ok(())
+    }
+
+    async fn scan_prefix(&self, prefix: &[u8]) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        self.tree
+            .scan_prefix(prefix)
+            .map(|entry| {
+                let (key, value) = entry?;
+                Ok((key.to_vec(), value.to_vec()))
+            })
+            .collect()
+    }
+}
+
+/// Concatenate a key prefix and the rest of a key.
+pub(crate) fn prefixed_key(prefix: &[u8], rest: &[u8]) -> Vec<u8> {
+    let mut key = Vec::with_capacity(prefix.len() + rest.len());
+    key.extend_from_slice(prefix);
+    key.extend_from_slice(rest);
+    key
+}
@@ -0,0 +1,50 @@
+use parking_lot::RwLock;
+use rand::Rng;
+use std::time::Duration;
+
+/// Latency to add to every request of a connection, to try a UI against a local conductor under
+/// the conditions of a real network.
+///
+/// Each request is delayed by `latency` plus a random duration of up to `jitter`, before it is
+/// sent. The delay counts towards the request durations in the connection stats.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SimulatedLatency {
+    pub latency: Duration,
+    pub jitter: Duration,
+}
+
+impl SimulatedLatency {
+    pub fn new(latency: Duration, jitter: Duration) -> Self {
+        Self { latency, jitter }
+    }
+
+    fn sample(&self) -> Duration {
+        if self.jitter.is_zero() {
+            return self.latency;
+        }
+        self.latency + rand::thread_rng().gen_range(Duration::ZERO..=self.jitter)
+    }
+}
+
+/// The [SimulatedLatency] of a connection, if any.
+#[derive(Debug, Default)]
+pub(crate) struct LatencyInjector {
+    latency: RwLock<Option<SimulatedLatency>>,
+}
+
+impl LatencyInjector {
+    pub(crate) fn set(&self, latency: Option<SimulatedLatency>) {
+        *self.latency.write() = latency;
+    }
+
+    pub(crate) fn get(&self) -> Option<SimulatedLatency> {
+        *self.latency.read()
+    }
+
+    pub(crate) async fn delay(&self) {
+        let delay = self.get().map(|latency| latency.sample());
+        if let Some(delay) = delay {
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
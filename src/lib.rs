@@ -1,23 +1,185 @@
+//! A Rust client for the Holochain Conductor API.
+//!
+//! The client is split into cargo features so that binaries only compile what they use:
+//!
+//! - `admin`: [AdminWebsocket] for the admin interface.
+//! - `app`: [AppWebsocket] for app interfaces.
+//! - `lair_signing`: [LairAgentSigner] for signing zome calls with keys held in Lair.
+//!
+//! All of these are enabled by default. With no features enabled, the crate can still be used to
+//! sign zome calls, for example in a signing service that has no connection to a conductor. See
+//! [sign_zome_call] and [SerializedZomeCall].
+//!
+//! The [prelude] re-exports the client together with the Holochain types that appear in its API.
+
+#[cfg(feature = "admin")]
+mod admin_role;
+#[cfg(feature = "admin")]
 mod admin_websocket;
+#[cfg(feature = "admin")]
+mod app_watch;
+#[cfg(feature = "app")]
 mod app_websocket;
+#[cfg(feature = "app")]
 mod app_websocket_inner;
+#[cfg(all(feature = "admin", feature = "app"))]
+mod attach;
+#[cfg(feature = "admin")]
+mod bundle;
+#[cfg(feature = "admin")]
+mod cell_watch;
+#[cfg(all(feature = "admin", feature = "app"))]
+mod client;
+#[cfg(all(feature = "admin", feature = "app"))]
+mod client_set;
+mod clone_ref;
+#[cfg(any(feature = "admin", feature = "app"))]
+mod compat;
+#[cfg(any(feature = "admin", feature = "app"))]
+mod connect;
+#[cfg(feature = "debug")]
+pub mod debug;
+mod dna_hash;
 mod error;
+#[cfg(feature = "admin")]
+mod grants;
+#[cfg(any(feature = "admin", feature = "app"))]
+mod history;
+#[cfg(feature = "app")]
+mod input_validation;
+#[cfg(feature = "admin")]
+mod install_validation;
+pub mod json;
+pub mod kv_store;
+#[cfg(feature = "simulated_latency")]
+mod latency;
+mod modifiers;
+#[cfg(feature = "admin")]
+mod network_stats;
+#[cfg(feature = "app")]
+mod paging;
+pub mod prelude;
+#[cfg(feature = "app")]
+mod projection;
+#[cfg(all(feature = "admin", feature = "app"))]
+mod quickstart;
+#[cfg(all(feature = "admin", feature = "app"))]
+mod readiness;
+#[cfg(any(feature = "admin", feature = "app"))]
+mod reconnect;
+#[cfg(feature = "app")]
+mod replay;
+#[cfg(feature = "admin")]
+pub mod service;
+#[cfg(feature = "app")]
+mod signal_journal;
+#[cfg(feature = "app")]
+mod signal_stream;
 mod signing;
+#[cfg(any(feature = "admin", feature = "app"))]
+mod stats;
+#[cfg(feature = "admin")]
+mod storage;
+#[cfg(feature = "app")]
+pub mod sync;
+#[cfg(feature = "test_utils")]
+pub mod test_utils;
+#[cfg(feature = "tls")]
+mod tls;
+#[cfg(feature = "app")]
+mod typed_signal;
+#[cfg(any(feature = "admin", feature = "app"))]
+mod wire;
 
-pub use admin_websocket::{AdminWebsocket, AuthorizeSigningCredentialsPayload, EnableAppResponse};
-pub use app_websocket::{AppWebsocket, ZomeCallTarget};
-pub use error::{ConductorApiError, ConductorApiResult};
+#[cfg(feature = "admin")]
+pub use admin_role::{AdminRole, READ_ONLY_ADMIN_OPERATIONS};
+#[cfg(feature = "admin")]
+pub use admin_websocket::{
+    AdminWebsocket, AuthorizeSigningCredentialsPayload, EnableAppProgress, EnableAppResponse,
+    ExistingAgent, RevertPolicy, SetAppsEnabledReport,
+};
+#[cfg(feature = "admin")]
+pub use app_watch::AppChange;
+#[cfg(feature = "app")]
+pub use app_websocket::{
+    AppWebsocket, CachedAppInfo, DisabledAppGuard, FetchedAppInfo, ZomeCallOutcome, ZomeCallTarget,
+    DEFAULT_APP_INFO_REFRESH_WINDOW,
+};
+#[cfg(feature = "admin")]
+pub use bundle::AppBundleBuilder;
+#[cfg(feature = "admin")]
+pub use cell_watch::{LimboStage, StuckOps};
+#[cfg(all(feature = "admin", feature = "app"))]
+pub use client::HolochainClient;
+#[cfg(all(feature = "admin", feature = "app"))]
+pub use client_set::ClientSet;
+pub use clone_ref::CloneRef;
+#[cfg(any(feature = "admin", feature = "app"))]
+pub use connect::{ConnectOptions, ConnectRetry, ConnectionInfo};
+pub use dna_hash::{compute_dna_hash, compute_dna_hash_from_file, verify_dna_hash};
+pub use error::{
+    CloneRefError, ConductorApiError, ConductorApiResult, ConductorError, ConductorErrorKind,
+    InstallAppPayloadError, SignalDecodeError,
+};
+#[cfg(feature = "admin")]
+pub use grants::{CapGrantInfo, GrantRecord, GrantsExport, ZOME_CALL_SIGNING_KEY_TAG};
+#[cfg(any(feature = "admin", feature = "app"))]
+pub use history::{RequestOutcome, RequestRecord};
 pub use holochain_conductor_api::{
     AdminRequest, AdminResponse, AppAuthenticationRequest, AppAuthenticationToken,
-    AppAuthenticationTokenIssued, AppInfo, AppRequest, AppResponse, AppStatusFilter,
-    IssueAppAuthenticationTokenPayload,
+    AppAuthenticationTokenIssued, AppInfo, AppInterfaceInfo, AppRequest, AppResponse,
+    AppStatusFilter, IssueAppAuthenticationTokenPayload,
 };
 pub use holochain_types::{
     app::{InstallAppPayload, InstalledAppId},
     dna::AgentPubKey,
+    signal::SystemSignal,
 };
+#[cfg(any(feature = "admin", feature = "app"))]
 pub use holochain_websocket::WebsocketConfig;
-pub use signing::client_signing::{ClientAgentSigner, SigningCredentials};
+#[cfg(all(feature = "app", feature = "schema"))]
+pub use input_validation::JsonSchemaValidator;
+#[cfg(feature = "app")]
+pub use input_validation::{InputValidator, InputValidators, TypeValidator};
+#[cfg(feature = "admin")]
+pub use install_validation::validate_install_app_payload;
+#[cfg(feature = "simulated_latency")]
+pub use latency::SimulatedLatency;
+pub use modifiers::{check_modifiers, origin_time_before_now, ModifiersWarning};
+#[cfg(feature = "admin")]
+pub use network_stats::{NetworkStats, PeerConnectionStats};
+#[cfg(feature = "app")]
+pub use paging::{Page, PageRequest};
+#[cfg(feature = "app")]
+pub use projection::{Projection, ProjectionQuery};
+#[cfg(all(feature = "admin", feature = "app"))]
+pub use quickstart::quickstart;
+#[cfg(all(feature = "admin", feature = "app"))]
+pub use readiness::{ProbeZomeFn, ReadinessCheck, ReadinessFailure, ReadinessReport};
+#[cfg(feature = "app")]
+pub use reconnect::AppAuthTokenSource;
+#[cfg(any(feature = "admin", feature = "app"))]
+pub use reconnect::{ConnectionState, ReconnectPolicy};
+#[cfg(feature = "app")]
+pub use replay::{replay_zome_calls, RecordedZomeCall, ReplayErrorPolicy, ReplayReport};
+#[cfg(feature = "app")]
+pub use signal_journal::SignalJournal;
+#[cfg(feature = "app")]
+pub use signal_stream::{
+    BufferedSignalStream, SignalEvent, SignalGap, SignalStream, SignalSubscription,
+    SIGNAL_BROADCAST_CAPACITY,
+};
+pub use signing::client_signing::{ClientAgentSigner, SigningCredentials, DEFAULT_SIGNER_PROFILE};
+pub use signing::composite_signing::CompositeSigner;
+pub use signing::detached_signing::{DetachedSigningBatch, SigningRequest};
 #[cfg(feature = "lair_signing")]
 pub use signing::lair_signing::LairAgentSigner;
-pub use signing::AgentSigner;
+pub use signing::{sign_zome_call, verify_signed_zome_call, AgentSigner, SerializedZomeCall};
+#[cfg(any(feature = "admin", feature = "app"))]
+pub use stats::{ConnectionStats, OperationStats};
+#[cfg(feature = "admin")]
+pub use storage::AppStorageInfo;
+#[cfg(feature = "tls")]
+pub use tls::TlsOptions;
+#[cfg(feature = "app")]
+pub use typed_signal::{SignalExt, TypedSignal};
@@ -0,0 +1,39 @@
+use holochain_types::prelude::{DnaModifiers, Timestamp};
+use std::time::Duration;
+
+/// An origin time which lies `margin` before the current time.
+///
+/// Data created before the origin time of a DNA is not gossiped as expected, so when choosing an
+/// origin time for a new network or clone cell, pick one slightly in the past to allow for clock
+/// differences between peers.
+pub fn origin_time_before_now(margin: Duration) -> Timestamp {
+    let margin = i64::try_from(margin.as_micros()).unwrap_or(i64::MAX);
+    Timestamp::from_micros(Timestamp::now().as_micros().saturating_sub(margin))
+}
+
+/// A likely mistake in the DNA modifiers of a cell, see [check_modifiers].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ModifiersWarning {
+    /// The origin time lies in the future, so data created until then is not gossiped as
+    /// expected.
+    OriginTimeInFuture { origin_time: Timestamp },
+    /// The quantum time is zero, which gossip can't divide time by.
+    ZeroQuantumTime,
+}
+
+/// Check the DNA modifiers of a cell for mistakes which cause subtle gossip issues.
+///
+/// The modifiers of a clone cell are returned in
+/// [ClonedCell::dna_modifiers](holochain_zome_types::clone::ClonedCell) when creating it.
+pub fn check_modifiers(modifiers: &DnaModifiers) -> Vec<ModifiersWarning> {
+    let mut warnings = Vec::new();
+    if modifiers.origin_time > Timestamp::now() {
+        warnings.push(ModifiersWarning::OriginTimeInFuture {
+            origin_time: modifiers.origin_time,
+        });
+    }
+    if modifiers.quantum_time.is_zero() {
+        warnings.push(ModifiersWarning::ZeroQuantumTime);
+    }
+    warnings
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+/// Statistics of the network transport of a conductor, see
+/// [AdminWebsocket::network_stats](crate::AdminWebsocket::network_stats).
+///
+/// The conductor reports the statistics of its transport as JSON without a documented format.
+/// Fields which are missing from the report are left at their default, and fields which are not
+/// known to this client are ignored.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NetworkStats {
+    /// The transport backend, such as `backendMem` or `backendGo`.
+    pub backend: String,
+    /// The URLs this conductor is reachable at.
+    #[serde(rename = "peerUrlList")]
+    pub peer_urls: Vec<String>,
+    /// The open connections to peers.
+    #[serde(rename = "connectionList")]
+    pub connections: Vec<PeerConnectionStats>,
+}
+
+impl NetworkStats {
+    /// The number of bytes sent over all open connections.
+    pub fn bytes_sent(&self) -> u64 {
+        self.connections.iter().map(|conn| conn.send_bytes).sum()
+    }
+
+    /// The number of bytes received over all open connections.
+    pub fn bytes_received(&self) -> u64 {
+        self.connections.iter().map(|conn| conn.recv_bytes).sum()
+    }
+}
+
+/// Statistics of a connection to a peer, see [NetworkStats].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PeerConnectionStats {
+    /// The public key of the peer's transport, which is not its agent key.
+    pub pub_key: String,
+    pub send_message_count: u64,
+    pub send_bytes: u64,
+    pub recv_message_count: u64,
+    pub recv_bytes: u64,
+    /// When the connection was opened, in seconds since the Unix epoch.
+    pub opened_at_s: f64,
+    /// Whether the connection is a direct WebRTC connection rather than relayed.
+    pub is_webrtc: bool,
+}
@@ -0,0 +1,68 @@
+use crate::{AppWebsocket, ConductorApiError, ConductorApiResult, ZomeCallTarget};
+use futures::{stream, Stream, TryStreamExt};
+use holochain_zome_types::prelude::{ExternIO, FunctionName, ZomeName};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::fmt::Debug;
+
+/// The input to a zome function that follows the paging convention.
+///
+/// The first request has no cursor. Each following request passes the cursor returned in the
+/// previous [Page].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PageRequest<I, C> {
+    pub input: I,
+    pub cursor: Option<C>,
+}
+
+/// The output of a zome function that follows the paging convention.
+///
+/// `next` is the cursor to request the next page with, or `None` if this is the last page.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Page<T, C> {
+    pub items: Vec<T>,
+    pub next: Option<C>,
+}
+
+impl AppWebsocket {
+    /// Call a paged zome function and stream the items of all pages.
+    ///
+    /// The zome function must take a [PageRequest] and return a [Page]. Pages are requested one
+    /// at a time as the stream is consumed, until a page is returned without a `next` cursor. The
+    /// stream ends after the first error.
+    pub fn paged_zome_call<I, C, T>(
+        &self,
+        target: ZomeCallTarget,
+        zome_name: ZomeName,
+        fn_name: FunctionName,
+        input: I,
+    ) -> impl Stream<Item = ConductorApiResult<T>> + '_
+    where
+        I: Serialize + Clone + Debug + 'static,
+        C: Serialize + DeserializeOwned + Debug + 'static,
+        T: DeserializeOwned + Debug + 'static,
+    {
+        stream::try_unfold(Some(None), move |cursor: Option<Option<C>>| {
+            let target = target.clone();
+            let zome_name = zome_name.clone();
+            let fn_name = fn_name.clone();
+            let input = input.clone();
+            async move {
+                let Some(cursor) = cursor else {
+                    return Ok(None);
+                };
+
+                let payload = ExternIO::encode(PageRequest { input, cursor })
+                    .map_err(ConductorApiError::SerializationError)?;
+                let page: Page<T, C> = self
+                    .call_zome(target, zome_name, fn_name, payload)
+                    .await?
+                    .decode()
+                    .map_err(ConductorApiError::SerializationError)?;
+
+                Ok(Some((page.items, page.next.map(Some))))
+            }
+        })
+        .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+        .try_flatten()
+    }
+}
@@ -0,0 +1,45 @@
+//! Re-exports of the client and of the Holochain types that appear in its public API.
+//!
+//! Downstream crates can name the payloads and results of client methods through this module,
+//! without depending on matching versions of `holochain_types`, `holochain_zome_types` or
+//! `holochain_conductor_api` themselves.
+//!
+//! ```rust,no_run
+//! use holochain_client::prelude::*;
+//! ```
+
+pub use crate::*;
+
+pub use holo_hash::{ActionHash, AgentPubKey, DnaHash};
+pub use holochain_conductor_api::{
+    AppInfoStatus, AppInterfaceInfo, CellInfo, CompatibleCells, DnaStorageInfo,
+    ExternalApiWireError, FullStateDump, NetworkInfo, ProvisionedCell, RevokeAgentKeyPayload,
+    StorageBlob, StorageInfo, ZomeCallParamsSigned,
+};
+#[cfg(feature = "unstable-countersigning")]
+pub use holochain_types::prelude::CountersigningSessionState;
+pub use holochain_types::{
+    app::{
+        AppBundle, AppBundleSource, AppManifest, CreateCloneCellPayload, DisableCloneCellPayload,
+        DisabledAppReason, EnableCloneCellPayload, MemproofMap, NetworkInfoRequestPayload,
+    },
+    dna::DnaBundle,
+    prelude::{
+        CloneCellId, CloneId, DeleteCloneCellPayload, DnaModifiers, DnaModifiersOpt, DnaSource,
+        RegisterDnaPayload, RoleSettings, UpdateCoordinatorsPayload, YamlProperties,
+    },
+    signal::{Signal, SystemSignal},
+    websocket::AllowedOrigins,
+};
+pub use holochain_zome_types::{
+    capability::{CapSecret, GrantedFunctions},
+    clone::ClonedCell,
+    dependencies::holochain_integrity_types::Signature,
+    prelude::{
+        CellId, DnaDef, ExternIO, FunctionName, GrantZomeCallCapabilityPayload, Record, RoleName,
+        Timestamp, ZomeName,
+    },
+    zome_io::ZomeCallParams,
+};
+#[cfg(feature = "admin")]
+pub use kitsune_p2p_types::agent_info::AgentInfoSigned;
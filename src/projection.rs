@@ -0,0 +1,228 @@
+use crate::{AppWebsocket, SignalSubscription, ZomeCallTarget};
+use anyhow::Result;
+use holochain_types::prelude::{SerializedBytesError, Signal};
+use holochain_zome_types::prelude::{ExternIO, FunctionName, ZomeName};
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use std::fmt::Debug;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// The zome call a [Projection] is built from.
+#[derive(Clone, Debug)]
+pub struct ProjectionQuery {
+    pub target: ZomeCallTarget,
+    pub zome_name: ZomeName,
+    pub fn_name: FunctionName,
+    pub payload: ExternIO,
+}
+
+/// An in-memory view that is kept current with app signals.
+///
+/// The view is built from the response to a [ProjectionQuery] and then updated with every app
+/// signal emitted by the zome of the query in the cell it targets. Signals that do not decode to
+/// the signal type of the projection are ignored. Signals that arrive while the query is in flight
+/// are applied once the view has been built, so no update is lost between the query and the
+/// subscription.
+///
+/// Signals are not delivered while the client is disconnected, so the view is queried again when
+/// the connection is restored. After connecting a new [AppWebsocket], call [Projection::rebuild]
+/// to query the view again and follow the signals of the new connection.
+pub struct Projection<V> {
+    shared: Arc<ProjectionShared<V>>,
+    /// The handlers following the connection the view was last built from, which are removed
+    /// when the projection is rebuilt or dropped.
+    subscription: Mutex<Option<SignalSubscription>>,
+}
+
+struct ProjectionShared<V> {
+    state: Mutex<ProjectionState<V>>,
+    query: ProjectionQuery,
+    init: Box<dyn Fn(ExternIO) -> Result<V, SerializedBytesError> + Send + Sync>,
+    apply: Box<dyn Fn(&mut V, ExternIO) + Send + Sync>,
+}
+
+struct ProjectionState<V> {
+    /// Incremented on every rebuild, so that handlers of previous connections stop applying signals.
+    generation: u64,
+    /// `None` only until the first build has completed.
+    view: Option<V>,
+    /// Signals received while a build is in progress.
+    pending: Option<Vec<ExternIO>>,
+}
+
+impl<V: Send + 'static> Projection<V> {
+    /// Build a projection by calling the query and then following signals.
+    ///
+    /// `init` builds the view from the decoded query response, and `apply` updates it with a decoded
+    /// signal.
+    pub async fn new<Q, S>(
+        app_ws: &AppWebsocket,
+        query: ProjectionQuery,
+        init: impl Fn(Q) -> V + Send + Sync + 'static,
+        apply: impl Fn(&mut V, S) + Send + Sync + 'static,
+    ) -> Result<Self>
+    where
+        Q: DeserializeOwned + Debug,
+        S: DeserializeOwned + Debug,
+    {
+        let projection = Self {
+            shared: Arc::new(ProjectionShared {
+                state: Mutex::new(ProjectionState {
+                    generation: 0,
+                    view: None,
+                    pending: None,
+                }),
+                query,
+                init: Box::new(move |response: ExternIO| response.decode().map(&init)),
+                apply: Box::new(move |view: &mut V, signal: ExternIO| {
+                    if let Ok(signal) = signal.decode() {
+                        apply(view, signal);
+                    }
+                }),
+            }),
+            subscription: Mutex::new(None),
+        };
+        projection.rebuild(app_ws).await?;
+
+        Ok(projection)
+    }
+
+    /// Query the view again and follow the signals of the given connection.
+    ///
+    /// The previous view stays readable until the new one has been built. Signals from connections
+    /// used by earlier builds are no longer applied, so if the rebuild fails, the previous view is
+    /// kept but no longer updated.
+    pub async fn rebuild(&self, app_ws: &AppWebsocket) -> Result<()> {
+        let cell_id = app_ws.target_cell_id(self.shared.query.target.clone())?;
+        let generation = {
+            let mut state = self.shared.state.lock();
+            state.generation += 1;
+            state.pending = Some(Vec::new());
+            state.generation
+        };
+
+        let mut subscription = app_ws
+            .signal_subscription({
+                let shared = self.shared.clone();
+                move |signal| {
+                    let Signal::App {
+                        cell_id: signal_cell_id,
+                        zome_name,
+                        signal,
+                    } = signal
+                    else {
+                        return;
+                    };
+                    if signal_cell_id == cell_id && zome_name == shared.query.zome_name {
+                        shared.receive(generation, signal.into_inner());
+                    }
+                }
+            })
+            .await?;
+
+        let (reconnected_tx, mut reconnected_rx) = mpsc::unbounded_channel();
+        subscription.add_handler(
+            app_ws
+                .on_reconnected(move |()| {
+                    let _ = reconnected_tx.send(());
+                })
+                .await?,
+        );
+        // The task ends when the handler and with it the sender is removed together with the
+        // subscription.
+        tokio::spawn({
+            let shared = self.shared.clone();
+            let app_ws = app_ws.clone();
+            async move {
+                while reconnected_rx.recv().await.is_some() {
+                    let _ = shared.refresh(&app_ws, generation).await;
+                }
+            }
+        });
+        *self.subscription.lock() = Some(subscription);
+
+        let view = self.shared.query(app_ws).await;
+        self.shared.finish(generation, view)
+    }
+
+    /// Read the current view.
+    pub fn read<R>(&self, f: impl FnOnce(&V) -> R) -> R {
+        let state = self.shared.state.lock();
+        f(state
+            .view
+            .as_ref()
+            .expect("The view is built when the projection is created"))
+    }
+}
+
+impl<V: Send + 'static> ProjectionShared<V> {
+    fn receive(&self, generation: u64, signal: ExternIO) {
+        let mut state = self.state.lock();
+        if state.generation != generation {
+            return;
+        }
+        let state = &mut *state;
+        match (&mut state.pending, &mut state.view) {
+            (Some(pending), _) => pending.push(signal),
+            (None, Some(view)) => (self.apply)(view, signal),
+            (None, None) => {}
+        }
+    }
+
+    /// Query the view again on the same connection, after signals may have been missed.
+    async fn refresh(&self, app_ws: &AppWebsocket, generation: u64) -> Result<()> {
+        {
+            let mut state = self.state.lock();
+            if state.generation != generation {
+                return Ok(());
+            }
+            state.pending.get_or_insert_with(Vec::new);
+        }
+
+        let view = self.query(app_ws).await;
+        self.finish(generation, view)
+    }
+
+    async fn query(&self, app_ws: &AppWebsocket) -> Result<V> {
+        let response = app_ws
+            .call_zome(
+                self.query.target.clone(),
+                self.query.zome_name.clone(),
+                self.query.fn_name.clone(),
+                self.query.payload.clone(),
+            )
+            .await?;
+
+        Ok((self.init)(response)?)
+    }
+
+    /// Replace the view with a newly queried one and apply the signals received in the meantime.
+    fn finish(&self, generation: u64, view: Result<V>) -> Result<()> {
+        let mut state = self.state.lock();
+        if state.generation != generation {
+            // A newer rebuild has started, which will replace the view.
+            return view.map(|_| ());
+        }
+        match view {
+            Ok(mut view) => {
+                for signal in state.pending.take().unwrap_or_default() {
+                    (self.apply)(&mut view, signal);
+                }
+                state.view = Some(view);
+                Ok(())
+            }
+            Err(err) => {
+                state.pending = None;
+                Err(err)
+            }
+        }
+    }
+}
+
+impl<V: Clone + Send + 'static> Projection<V> {
+    /// Get a copy of the current view.
+    pub fn snapshot(&self) -> V {
+        self.read(V::clone)
+    }
+}
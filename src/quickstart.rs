@@ -0,0 +1,97 @@
+use crate::{AdminWebsocket, AppWebsocket, AuthorizeSigningCredentialsPayload, ClientAgentSigner};
+use anyhow::{anyhow, Context, Result};
+use holochain_conductor_api::CellInfo;
+use holochain_types::{
+    app::{AppBundleSource, InstallAppPayload},
+    websocket::AllowedOrigins,
+};
+use std::{net::ToSocketAddrs, path::Path, time::Duration};
+
+const APP_INTERFACE_READY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Install and connect to an app in a single call.
+///
+/// This performs the steps most prototypes start with:
+///
+/// 1. Connect to the admin interface at `admin_addr`.
+/// 2. Generate a new agent key and install the hApp at `happ_path` with it. The app id is the file
+///    name of the hApp without its extension.
+/// 3. Enable the app and attach an app interface bound to it.
+/// 4. Connect an [AppWebsocket] and authorize signing credentials for all provisioned cells.
+///
+/// The app interface allows connections from any origin, so this is not suitable for production
+/// deployments.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// use std::net::Ipv4Addr;
+/// let (admin_ws, app_ws) =
+///     holochain_client::quickstart((Ipv4Addr::LOCALHOST, 30_000), "./my-app.happ").await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn quickstart(
+    admin_addr: impl ToSocketAddrs,
+    happ_path: impl AsRef<Path>,
+) -> Result<(AdminWebsocket, AppWebsocket)> {
+    let happ_path = happ_path.as_ref();
+    let installed_app_id = happ_path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or_else(|| anyhow!("Invalid hApp path {}", happ_path.display()))?
+        .to_string();
+    let admin_addr = admin_addr
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| anyhow!("Invalid admin websocket address"))?;
+
+    let admin_ws = AdminWebsocket::connect(admin_addr).await?;
+    let agent_key = admin_ws
+        .generate_agent_pub_key()
+        .await
+        .context("Failed to generate agent key")?;
+    let app_info = admin_ws
+        .install_app(InstallAppPayload {
+            agent_key: Some(agent_key),
+            installed_app_id: Some(installed_app_id.clone()),
+            network_seed: None,
+            roles_settings: None,
+            source: AppBundleSource::Path(happ_path.to_path_buf()),
+            ignore_genesis_failure: false,
+            allow_throwaway_random_agent_key: false,
+        })
+        .await
+        .context("Failed to install app")?;
+    admin_ws
+        .enable_app(installed_app_id.clone())
+        .await
+        .context("Failed to enable app")?;
+
+    let signer = ClientAgentSigner::default();
+    for cell_info in app_info.cell_info.values().flatten() {
+        if let CellInfo::Provisioned(cell) = cell_info {
+            let credentials = admin_ws
+                .authorize_signing_credentials(AuthorizeSigningCredentialsPayload {
+                    cell_id: cell.cell_id.clone(),
+                    functions: None,
+                })
+                .await?;
+            signer.add_credentials(cell.cell_id.clone(), credentials);
+        }
+    }
+
+    let app_ws = admin_ws
+        .attach_and_connect(
+            admin_addr.ip(),
+            AllowedOrigins::Any,
+            installed_app_id,
+            signer.into(),
+            APP_INTERFACE_READY_TIMEOUT,
+        )
+        .await?;
+
+    Ok((admin_ws, app_ws))
+}
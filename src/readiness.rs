@@ -0,0 +1,203 @@
+use crate::{AdminWebsocket, AgentSigner, AppWebsocket, ClientAgentSigner, ZomeCallTarget};
+use anyhow::{anyhow, Context, Result};
+use holochain_conductor_api::AppInfoStatus;
+use holochain_types::app::InstalledAppId;
+use holochain_zome_types::prelude::{ExternIO, FunctionName, ZomeName};
+use serde::{Deserialize, Serialize};
+use std::{
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// A check of [AdminWebsocket::readiness_probe], in the order they are made.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadinessCheck {
+    /// The app is installed.
+    Installed,
+    /// The app is enabled and running.
+    Enabled,
+    /// An app interface that the app can connect to is attached.
+    InterfaceAttached,
+    /// A client can authenticate and connect to the app interface.
+    Authenticated,
+    /// The probe zome function can be called.
+    ZomeCall,
+}
+
+/// A check of [AdminWebsocket::readiness_probe] which did not pass.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadinessFailure {
+    pub check: ReadinessCheck,
+    pub reason: String,
+}
+
+/// The outcome of [AdminWebsocket::readiness_probe], which can be serialized as the body of a
+/// health endpoint.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadinessReport {
+    pub installed_app_id: InstalledAppId,
+    /// The checks which passed, in order.
+    pub passed: Vec<ReadinessCheck>,
+    /// The first check which did not pass. Later checks were not made.
+    pub failure: Option<ReadinessFailure>,
+    pub elapsed: Duration,
+}
+
+impl ReadinessReport {
+    pub fn is_ready(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+/// The zome function that [AdminWebsocket::readiness_probe] calls to check that the app responds.
+///
+/// The function should be cheap and have no side effects. The signer must hold credentials for
+/// the target cell, such as credentials authorized once for the probe with
+/// [AdminWebsocket::authorize_signing_credentials].
+#[derive(Clone)]
+pub struct ProbeZomeFn {
+    pub target: ZomeCallTarget,
+    pub zome_name: ZomeName,
+    pub fn_name: FunctionName,
+    pub payload: ExternIO,
+    pub signer: Arc<dyn AgentSigner + Send + Sync>,
+}
+
+impl AdminWebsocket {
+    /// Check in one call that an app is fully reachable: it is installed and running, an app
+    /// interface for it is attached, a client can authenticate to the interface, and, if given,
+    /// the probe zome function succeeds.
+    ///
+    /// The checks are made in order until one fails or `timeout` elapses. The report names the
+    /// checks which passed and the first one which failed. The probe does not change the
+    /// conductor, apart from issuing an authentication token for the app interface.
+    pub async fn readiness_probe(
+        &self,
+        installed_app_id: InstalledAppId,
+        probe_zome_fn: Option<ProbeZomeFn>,
+        timeout: Duration,
+    ) -> ReadinessReport {
+        let started_at = Instant::now();
+        let mut probe = Probe {
+            deadline: started_at + timeout,
+            timeout,
+            passed: Vec::new(),
+        };
+        let failure = self
+            .run_readiness_checks(&installed_app_id, probe_zome_fn, &mut probe)
+            .await
+            .err();
+
+        ReadinessReport {
+            installed_app_id,
+            passed: probe.passed,
+            failure,
+            elapsed: started_at.elapsed(),
+        }
+    }
+
+    async fn run_readiness_checks(
+        &self,
+        installed_app_id: &InstalledAppId,
+        probe_zome_fn: Option<ProbeZomeFn>,
+        probe: &mut Probe,
+    ) -> Result<(), ReadinessFailure> {
+        let app = probe
+            .check(ReadinessCheck::Installed, async {
+                self.list_apps(None)
+                    .await?
+                    .into_iter()
+                    .find(|app| app.installed_app_id == *installed_app_id)
+                    .ok_or_else(|| anyhow!("App {installed_app_id} is not installed"))
+            })
+            .await?;
+
+        probe
+            .check(ReadinessCheck::Enabled, async {
+                match &app.status {
+                    AppInfoStatus::Running => Ok(()),
+                    status => Err(anyhow!("App is not running: {status:?}")),
+                }
+            })
+            .await?;
+
+        let port = probe
+            .check(ReadinessCheck::InterfaceAttached, async {
+                self.list_app_interfaces()
+                    .await?
+                    .into_iter()
+                    .find(|interface| match &interface.installed_app_id {
+                        Some(app_id) => app_id == installed_app_id,
+                        None => true,
+                    })
+                    .map(|interface| interface.port)
+                    .ok_or_else(|| anyhow!("No app interface is attached for the app"))
+            })
+            .await?;
+
+        let signer = match &probe_zome_fn {
+            Some(probe_zome_fn) => probe_zome_fn.signer.clone(),
+            None => ClientAgentSigner::default().into(),
+        };
+        let app_ws = probe
+            .check(ReadinessCheck::Authenticated, async {
+                let issued = self
+                    .issue_app_auth_token(installed_app_id.clone().into())
+                    .await?;
+                AppWebsocket::connect(
+                    (self.connection_info().addr.ip(), port),
+                    issued.token,
+                    signer,
+                )
+                .await
+                .with_context(|| format!("Failed to connect to the app interface on port {port}"))
+            })
+            .await?;
+
+        if let Some(probe_zome_fn) = probe_zome_fn {
+            probe
+                .check(ReadinessCheck::ZomeCall, async {
+                    app_ws
+                        .call_zome(
+                            probe_zome_fn.target,
+                            probe_zome_fn.zome_name,
+                            probe_zome_fn.fn_name,
+                            probe_zome_fn.payload,
+                        )
+                        .await?;
+                    Ok(())
+                })
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+struct Probe {
+    deadline: Instant,
+    timeout: Duration,
+    passed: Vec<ReadinessCheck>,
+}
+
+impl Probe {
+    async fn check<T>(
+        &mut self,
+        check: ReadinessCheck,
+        f: impl Future<Output = Result<T>>,
+    ) -> Result<T, ReadinessFailure> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        let reason = match tokio::time::timeout(remaining, f).await {
+            Ok(Ok(value)) => {
+                self.passed.push(check);
+                return Ok(value);
+            }
+            Ok(Err(err)) => format!("{err:#}"),
+            Err(_) => format!("Timed out after {:?}", self.timeout),
+        };
+
+        Err(ReadinessFailure { check, reason })
+    }
+}
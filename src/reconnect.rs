@@ -0,0 +1,278 @@
+use crate::connect::{connect_websocket, ConnectOptions, ConnectRetry};
+use crate::{ConductorApiError, ConductorApiResult};
+#[cfg(feature = "app")]
+use anyhow::Result;
+#[cfg(feature = "app")]
+use holochain_conductor_api::AppAuthenticationToken;
+use holochain_websocket::{WebsocketError, WebsocketReceiver, WebsocketSender};
+use parking_lot::RwLock;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{fmt::Debug, future::Future, net::SocketAddr, time::Duration};
+use tokio::{sync::watch, task::AbortHandle};
+
+/// How a client restores its connection after it was lost, see [ConnectOptions::with_reconnect].
+///
+/// The client reconnects in the background, with a delay that doubles after every failed attempt
+/// up to `max_delay`. App clients authenticate again with a token from their
+/// [AppAuthTokenSource]. Signal handlers and streams stay registered and receive signals again
+/// once the connection is restored, but signals sent in the meantime are not delivered.
+///
+/// Requests that fail because the connection was lost are retried once the client has
+/// reconnected if they are idempotent, such as [AdminWebsocket::list_apps](crate::AdminWebsocket::list_apps)
+/// or [AppWebsocket::app_info](crate::AppWebsocket::app_info), and `retry_idempotent_requests` is
+/// set. Other requests, including zome calls, fail as usual since they may have been applied.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    /// Give up after this many failed attempts in a row, or never if `None`.
+    pub max_attempts: Option<usize>,
+    pub retry_idempotent_requests: bool,
+}
+
+impl Default for ReconnectPolicy {
+    /// Reconnect until it succeeds, starting with a delay of 500 milliseconds and waiting at most
+    /// 30 seconds between attempts, and retry idempotent requests.
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            max_attempts: None,
+            retry_idempotent_requests: true,
+        }
+    }
+}
+
+/// The state of the connection of a client to the conductor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connected,
+    /// The connection was lost and the client is trying to restore it.
+    Reconnecting {
+        /// The number of the attempt being made, starting at 1.
+        attempt: usize,
+    },
+    /// The connection was lost and will not be restored, either because the client has no
+    /// [ReconnectPolicy] or because it gave up.
+    Closed,
+}
+
+/// Issues the tokens an [AppWebsocket](crate::AppWebsocket) authenticates with when it
+/// reconnects, see [AppWebsocket::connect_with_token_source](crate::AppWebsocket::connect_with_token_source).
+///
+/// Tokens are single use by default, so a new token is needed for every connection. Any async
+/// closure returning a token is a source, for example one that issues tokens through an admin
+/// connection:
+///
+/// ```rust,no_run
+/// # async fn example(admin_ws: holochain_client::AdminWebsocket) {
+/// let token_source = move || {
+///     let admin_ws = admin_ws.clone();
+///     async move {
+///         let issued = admin_ws.issue_app_auth_token("test-app".to_string().into()).await?;
+///         anyhow::Ok(issued.token)
+///     }
+/// };
+/// # }
+/// ```
+#[cfg(feature = "app")]
+#[async_trait::async_trait]
+pub trait AppAuthTokenSource: Send + Sync {
+    async fn issue_token(&self) -> Result<AppAuthenticationToken>;
+}
+
+#[cfg(feature = "app")]
+#[async_trait::async_trait]
+impl<F, Fut> AppAuthTokenSource for F
+where
+    F: Fn() -> Fut + Send + Sync,
+    Fut: Future<Output = Result<AppAuthenticationToken>> + Send,
+{
+    async fn issue_token(&self) -> Result<AppAuthenticationToken> {
+        self().await
+    }
+}
+
+/// Aborts the task polling the receiver of a connection when the last handle to the client is
+/// dropped.
+pub(crate) struct AbortOnDropHandle(pub(crate) AbortHandle);
+
+impl Drop for AbortOnDropHandle {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// A request may fail before the receiver of the connection notices that it was lost. If the
+/// connection is still up after this long, the request failed for another reason.
+const LOSS_DETECTION_GRACE: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy, Debug)]
+struct Status {
+    /// Incremented every time the connection is restored.
+    generation: u64,
+    state: ConnectionState,
+}
+
+/// The websocket of a client, which is replaced when the client reconnects.
+pub(crate) struct Connection {
+    addr: SocketAddr,
+    options: ConnectOptions,
+    tx: RwLock<WebsocketSender>,
+    status: watch::Sender<Status>,
+}
+
+impl Connection {
+    pub(crate) fn new(addr: SocketAddr, options: ConnectOptions, tx: WebsocketSender) -> Self {
+        Self {
+            addr,
+            options,
+            tx: RwLock::new(tx),
+            status: watch::Sender::new(Status {
+                generation: 0,
+                state: ConnectionState::Connected,
+            }),
+        }
+    }
+
+    pub(crate) fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    pub(crate) fn sender(&self) -> WebsocketSender {
+        self.tx.read().clone()
+    }
+
+    pub(crate) fn state(&self) -> ConnectionState {
+        self.status.borrow().state
+    }
+
+    /// Send a request, and if `idempotent` and the policy allows it, send it again after the
+    /// connection was lost and restored.
+    ///
+    /// The request fails with [ConductorApiError::RequestTimedOut] if no response arrives within
+    /// `timeout`, or the request timeout of the [ConnectOptions] if `None`. The time spent
+    /// waiting for the connection to be restored counts towards the timeout.
+    pub(crate) async fn request<Req, Resp>(
+        &self,
+        msg: Req,
+        operation: &str,
+        idempotent: bool,
+        timeout: Option<Duration>,
+    ) -> ConductorApiResult<Resp>
+    where
+        Req: Serialize + Clone + Debug,
+        Resp: DeserializeOwned + Debug,
+    {
+        let request = self.request_with_retry(msg, idempotent);
+        let result = match timeout.or(self.options.request_timeout) {
+            Some(timeout) => tokio::time::timeout(timeout, request).await.map_err(|_| {
+                ConductorApiError::RequestTimedOut {
+                    operation: operation.to_string(),
+                    timeout,
+                }
+            })?,
+            None => request.await,
+        };
+
+        result.map_err(ConductorApiError::WebsocketError)
+    }
+
+    async fn request_with_retry<Req, Resp>(
+        &self,
+        msg: Req,
+        idempotent: bool,
+    ) -> Result<Resp, WebsocketError>
+    where
+        Req: Serialize + Clone + Debug,
+        Resp: DeserializeOwned + Debug,
+    {
+        let retry = idempotent
+            && match &self.options.reconnect {
+                Some(policy) => policy.retry_idempotent_requests,
+                None => false,
+            };
+        if !retry {
+            return self.sender().request(msg).await;
+        }
+        loop {
+            let generation = self.status.borrow().generation;
+            match self.sender().request(msg.clone()).await {
+                Err(_) if self.wait_for_reconnect(generation).await => continue,
+                result => return result,
+            }
+        }
+    }
+
+    /// Wait for the connection of `generation` to be lost and restored. Returns `false` if it
+    /// turns out not to be lost, or won't be restored.
+    async fn wait_for_reconnect(&self, generation: u64) -> bool {
+        let mut status = self.status.subscribe();
+        if tokio::time::timeout(
+            LOSS_DETECTION_GRACE,
+            status.wait_for(|status| {
+                status.generation != generation || status.state != ConnectionState::Connected
+            }),
+        )
+        .await
+        .is_err()
+        {
+            return false;
+        }
+        match status
+            .wait_for(|status| {
+                (status.generation != generation && status.state == ConnectionState::Connected)
+                    || status.state == ConnectionState::Closed
+            })
+            .await
+        {
+            Ok(status) => status.state == ConnectionState::Connected,
+            Err(_) => false,
+        }
+    }
+
+    /// Restore the connection after it was lost, according to the [ReconnectPolicy]. A new
+    /// websocket is only used once `authenticate` succeeded on it.
+    ///
+    /// Returns the receiver of the new websocket, or `None` if the connection is closed for good.
+    pub(crate) async fn reconnect<F, Fut>(&self, authenticate: F) -> Option<WebsocketReceiver>
+    where
+        F: Fn(WebsocketSender) -> Fut,
+        Fut: Future<Output = anyhow::Result<()>>,
+    {
+        let Some(policy) = self.options.reconnect.clone() else {
+            self.set_state(ConnectionState::Closed);
+            return None;
+        };
+        // Each attempt is a single connection, the policy takes care of the delays.
+        let options = self.options.clone().with_retry(ConnectRetry::Disabled);
+        let mut delay = policy.initial_delay;
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            self.set_state(ConnectionState::Reconnecting { attempt });
+            if let Ok((tx, rx)) = connect_websocket(self.addr, &options).await {
+                if authenticate(tx.clone()).await.is_ok() {
+                    *self.tx.write() = tx;
+                    self.status.send_modify(|status| {
+                        status.generation += 1;
+                        status.state = ConnectionState::Connected;
+                    });
+                    return Some(rx);
+                }
+            }
+            if let Some(max_attempts) = policy.max_attempts {
+                if attempt >= max_attempts {
+                    self.set_state(ConnectionState::Closed);
+                    return None;
+                }
+            }
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(policy.max_delay);
+        }
+    }
+
+    fn set_state(&self, state: ConnectionState) {
+        self.status.send_modify(|status| status.state = state);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{
+    sync::{LocalChange, QueuedChange, SyncEndpoints},
+    AppWebsocket, ConductorError, ZomeCallTarget,
+};
+use anyhow::Result;
+use holochain_zome_types::prelude::{ExternIO, FunctionName, RoleName, ZomeName};
+use serde::{Deserialize, Serialize};
+
+/// A zome call recorded for replaying it later, for example in an audit log kept by the app.
+///
+/// The call is recorded by role name rather than by cell id, so that it can be replayed against
+/// a fresh cell of the same role after the app was reinstalled with a new agent.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedZomeCall {
+    /// The role name or clone id of the cell the call was made to.
+    pub role_name: RoleName,
+    pub zome_name: ZomeName,
+    pub fn_name: FunctionName,
+    pub payload: ExternIO,
+}
+
+impl RecordedZomeCall {
+    /// The call a [SyncEngine](crate::sync::SyncEngine) would make to push a queued local edit.
+    ///
+    /// Returns `None` if the endpoints target a cell by cell id, which does not survive a reset.
+    pub fn from_queued_change<R: Serialize>(
+        endpoints: &SyncEndpoints,
+        queued: &QueuedChange<R>,
+    ) -> Result<Option<Self>> {
+        let role_name = match &endpoints.target {
+            ZomeCallTarget::RoleName(role_name) => role_name.clone(),
+            ZomeCallTarget::CloneId(clone_id) => clone_id.0.clone(),
+            ZomeCallTarget::CellId(_) => return Ok(None),
+        };
+        let fn_name = match &queued.change {
+            LocalChange::Create(_) => endpoints.create_fn.clone(),
+            LocalChange::Update(_) => endpoints.update_fn.clone(),
+        };
+
+        Ok(Some(Self {
+            role_name,
+            zome_name: endpoints.zome_name.clone(),
+            fn_name,
+            payload: ExternIO::encode(queued.change.record())?,
+        }))
+    }
+}
+
+/// What [replay_zome_calls] does when the conductor rejects a call.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReplayErrorPolicy {
+    /// Stop at the rejected call, so that it can be fixed and the replay resumed from there.
+    #[default]
+    Stop,
+    /// Record the rejected call in the report and continue with the next one.
+    Skip,
+}
+
+/// The outcome of [replay_zome_calls].
+#[derive(Clone, Debug, Default)]
+pub struct ReplayReport {
+    /// The number of calls that succeeded.
+    pub replayed: usize,
+    /// The calls the conductor rejected, by their position in the replayed sequence.
+    pub rejected: Vec<(usize, ConductorError)>,
+    /// The position of the call the replay stopped at, if it stopped early.
+    pub stopped_at: Option<usize>,
+}
+
+/// Re-execute a recorded sequence of zome calls in order, for rebuilding the state derived from
+/// them after a reset, when the full records for a source chain graft aren't available.
+///
+/// Calls are made to the cells of the app the websocket is authenticated for, by the role name
+/// they were recorded with. Only calls which are idempotent, or which the fresh cell hasn't seen
+/// yet, should be replayed, since the client cannot tell whether a call was already applied.
+///
+/// Errors that did not come from the conductor, such as a lost connection, end the replay with an
+/// error. Call outputs are discarded.
+pub async fn replay_zome_calls(
+    app_ws: &AppWebsocket,
+    calls: impl IntoIterator<Item = RecordedZomeCall>,
+    on_error: ReplayErrorPolicy,
+) -> Result<ReplayReport> {
+    let mut report = ReplayReport::default();
+    for (index, call) in calls.into_iter().enumerate() {
+        let result = app_ws
+            .call_zome(
+                ZomeCallTarget::RoleName(call.role_name),
+                call.zome_name,
+                call.fn_name,
+                call.payload,
+            )
+            .await;
+
+        match result {
+            Ok(_) => report.replayed += 1,
+            Err(err) => {
+                let Some(conductor_error) = err.conductor_error() else {
+                    return Err(err.into());
+                };
+
+                report.rejected.push((index, conductor_error));
+                if on_error == ReplayErrorPolicy::Stop {
+                    report.stopped_at = Some(index);
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok(report)
+}
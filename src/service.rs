@@ -0,0 +1,194 @@
+//! Readiness and liveness reporting to service managers.
+//!
+//! A [ServiceMonitor] checks the conductor through an [AdminWebsocket] and reports the outcome to
+//! [ServiceHook]s. [SdNotify] tells systemd that the service is ready and alive, and [HealthState]
+//! keeps the latest status for a health endpoint of the node. Packaging a node with these hooks
+//! replaces scripts that wait for the conductor by parsing its logs.
+
+use crate::AdminWebsocket;
+use anyhow::Result;
+use holochain_conductor_api::AppInfoStatus;
+use holochain_types::app::InstalledAppId;
+use parking_lot::RwLock;
+use std::{sync::Arc, time::Duration};
+
+/// The status of the conductor as observed by a [ServiceMonitor].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ServiceStatus {
+    /// No check has completed yet.
+    Starting,
+    /// The conductor responds and all required apps are running.
+    Ready,
+    /// The conductor does not respond, or a required app is not running.
+    Unhealthy(String),
+}
+
+/// Receives the status of the conductor after every check of a [ServiceMonitor].
+pub trait ServiceHook: Send + Sync {
+    fn report(&self, status: &ServiceStatus);
+}
+
+/// Checks the conductor periodically and reports its status to [ServiceHook]s.
+pub struct ServiceMonitor {
+    hooks: Vec<Box<dyn ServiceHook>>,
+    required_apps: Vec<InstalledAppId>,
+    interval: Duration,
+}
+
+impl ServiceMonitor {
+    /// Check the conductor every `interval`.
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            hooks: Vec::new(),
+            required_apps: Vec::new(),
+            interval,
+        }
+    }
+
+    pub fn with_hook(mut self, hook: impl ServiceHook + 'static) -> Self {
+        self.hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Only report the conductor as ready while these apps are installed and running.
+    pub fn with_required_apps(mut self, required_apps: Vec<InstalledAppId>) -> Self {
+        self.required_apps = required_apps;
+        self
+    }
+
+    /// Check the conductor once and report the status to the hooks.
+    pub async fn check_once(&self, admin_ws: &AdminWebsocket) -> ServiceStatus {
+        let status = match admin_ws.list_apps(None).await {
+            Ok(apps) => {
+                let not_running: Vec<&InstalledAppId> = self
+                    .required_apps
+                    .iter()
+                    .filter(|app_id| {
+                        !apps.iter().any(|app| {
+                            app.installed_app_id == **app_id
+                                && matches!(app.status, AppInfoStatus::Running)
+                        })
+                    })
+                    .collect();
+                if not_running.is_empty() {
+                    ServiceStatus::Ready
+                } else {
+                    ServiceStatus::Unhealthy(format!("Apps not running: {not_running:?}"))
+                }
+            }
+            Err(err) => ServiceStatus::Unhealthy(format!("Conductor not responding: {err}")),
+        };
+        for hook in &self.hooks {
+            hook.report(&status);
+        }
+
+        status
+    }
+
+    /// Check the conductor every interval until the returned future is dropped.
+    pub async fn run(&self, admin_ws: &AdminWebsocket) -> Result<()> {
+        loop {
+            self.check_once(admin_ws).await;
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+}
+
+/// The latest [ServiceStatus], for serving from a health endpoint.
+///
+/// Clones share the same status.
+#[derive(Clone, Debug)]
+pub struct HealthState {
+    status: Arc<RwLock<ServiceStatus>>,
+}
+
+impl HealthState {
+    pub fn new() -> Self {
+        Self {
+            status: Arc::new(RwLock::new(ServiceStatus::Starting)),
+        }
+    }
+
+    pub fn status(&self) -> ServiceStatus {
+        self.status.read().clone()
+    }
+
+    pub fn is_ready(&self) -> bool {
+        *self.status.read() == ServiceStatus::Ready
+    }
+}
+
+impl Default for HealthState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ServiceHook for HealthState {
+    fn report(&self, status: &ServiceStatus) {
+        *self.status.write() = status.clone();
+    }
+}
+
+/// Reports the status to systemd with the `sd_notify` protocol.
+///
+/// Ready checks send `READY=1` and, for services with `WatchdogSec` set, `WATCHDOG=1`. Failed
+/// checks update the status text shown by `systemctl status` and do not feed the watchdog, so
+/// systemd restarts the service once the conductor has been unhealthy for longer than the
+/// watchdog timeout.
+#[cfg(unix)]
+#[derive(Debug)]
+pub struct SdNotify {
+    socket: std::os::unix::net::UnixDatagram,
+    path: String,
+}
+
+#[cfg(unix)]
+impl SdNotify {
+    /// Connect to the socket given by systemd in `NOTIFY_SOCKET`, or `None` if the process was not
+    /// started by systemd with `Type=notify`.
+    pub fn from_env() -> Result<Option<Self>> {
+        match std::env::var("NOTIFY_SOCKET") {
+            Ok(path) => Ok(Some(Self::new(path)?)),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Send notifications to the socket at `path`. Paths starting with `@` are abstract sockets.
+    pub fn new(path: impl Into<String>) -> Result<Self> {
+        Ok(Self {
+            socket: std::os::unix::net::UnixDatagram::unbound()?,
+            path: path.into(),
+        })
+    }
+
+    /// Send a raw notification, such as `"STOPPING=1"`.
+    pub fn notify(&self, state: &str) -> Result<()> {
+        match self.path.strip_prefix('@') {
+            #[cfg(target_os = "linux")]
+            Some(name) => {
+                use std::os::linux::net::SocketAddrExt;
+                let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+                self.socket.send_to_addr(state.as_bytes(), &addr)?;
+            }
+            _ => {
+                self.socket.send_to(state.as_bytes(), &self.path)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+impl ServiceHook for SdNotify {
+    fn report(&self, status: &ServiceStatus) {
+        let state = match status {
+            ServiceStatus::Starting => "STATUS=Waiting for the conductor".to_string(),
+            ServiceStatus::Ready => "READY=1\nWATCHDOG=1\nSTATUS=Conductor ready".to_string(),
+            ServiceStatus::Unhealthy(reason) => format!("STATUS={reason}"),
+        };
+        // The service manager may have gone away, in which case there is nobody to tell.
+        let _ = self.notify(&state);
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{
+    kv_store::{prefixed_key, KvStore},
+    AppWebsocket,
+};
+use anyhow::{anyhow, Result};
+use futures::StreamExt;
+use holochain_types::{
+    prelude::{decode, encode},
+    signal::Signal,
+};
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+
+/// A record of received signals in a [KvStore], so that they can be read again after a restart.
+///
+/// Each signal is stored under the next sequence number. All keys start with the namespace given
+/// to [SignalJournal::new], so a journal can share a [KvStore] with other data.
+pub struct SignalJournal<S> {
+    kv: S,
+    namespace: Vec<u8>,
+    // Makes taking the next sequence number and storing the signal one step.
+    append_lock: tokio::sync::Mutex<()>,
+}
+
+impl<S: KvStore> SignalJournal<S> {
+    pub fn new(kv: S, namespace: impl Into<String>) -> Self {
+        Self {
+            kv,
+            namespace: format!("{}/", namespace.into()).into_bytes(),
+            append_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    pub fn kv_store(&self) -> &S {
+        &self.kv
+    }
+
+    /// Add a signal to the end of the journal and return its sequence number.
+    pub async fn append(&self, signal: &Signal) -> Result<u64> {
+        let _lock = self.append_lock.lock().await;
+        let next_seq_key = prefixed_key(&self.namespace, b"next_seq");
+        let seq = match self.kv.get(&next_seq_key).await? {
+            Some(bytes) => u64::from_be_bytes(
+                bytes
+                    .try_into()
+                    .map_err(|_| anyhow!("Invalid sequence number in the signal journal"))?,
+            ),
+            None => 0,
+        };
+        self.kv.put(&self.signal_key(seq), encode(signal)?).await?;
+        self.kv
+            .put(&next_seq_key, (seq + 1).to_be_bytes().to_vec())
+            .await?;
+
+        Ok(seq)
+    }
+
+    /// The signals with a sequence number of at least `from`, oldest first.
+    pub async fn read_from(&self, from: u64) -> Result<Vec<(u64, Signal)>> {
+        let prefix = prefixed_key(&self.namespace, b"signal/");
+        self.kv
+            .scan_prefix(&prefix)
+            .await?
+            .into_iter()
+            .map(|(key, value)| {
+                let seq = key[prefix.len()..]
+                    .try_into()
+                    .map(u64::from_be_bytes)
+                    .map_err(|_| anyhow!("Invalid key in the signal journal"))?;
+                Ok((seq, value))
+            })
+            .filter(|entry| !matches!(entry, Ok((seq, _)) if *seq < from))
+            .map(|entry| {
+                let (seq, value) = entry?;
+                Ok((seq, decode(&value)?))
+            })
+            .collect()
+    }
+
+    /// Remove the signals with a sequence number below `before`, once they have been handled.
+    pub async fn truncate(&self, before: u64) -> Result<()> {
+        for (seq, _) in self.read_from(0).await? {
+            if seq >= before {
+                break;
+            }
+            self.kv.delete(&self.signal_key(seq)).await?;
+        }
+
+        Ok(())
+    }
+
+    // Big-endian, so that the journal is scanned in sequence order.
+    fn signal_key(&self, seq: u64) -> Vec<u8> {
+        prefixed_key(
+            &self.namespace,
+            &prefixed_key(b"signal/", &seq.to_be_bytes()),
+        )
+    }
+}
+
+impl AppWebsocket {
+    /// Append every signal received on this connection to a [SignalJournal].
+    ///
+    /// The signals are written by a background task, which stops when the returned handle is
+    /// aborted or when writing to the journal fails.
+    pub async fn journal_signals<S: KvStore + 'static>(
+        &self,
+        journal: Arc<SignalJournal<S>>,
+    ) -> Result<JoinHandle<Result<()>>> {
+        let mut signals = self.signal_stream().await?;
+        Ok(tokio::spawn(async move {
+            while let Some(signal) = signals.next().await {
+                journal.append(&signal).await?;
+            }
+            Ok(())
+        }))
+    }
+}
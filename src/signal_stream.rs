@@ -0,0 +1,353 @@
+use crate::AppWebsocket;
+use anyhow::Result;
+use futures::Stream;
+use holochain_types::signal::{Signal, SystemSignal};
+use holochain_zome_types::prelude::{CellId, ZomeName};
+use parking_lot::Mutex;
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Waker},
+};
+use tokio::sync::{broadcast, mpsc};
+
+/// A handle to signal handlers registered on an [AppWebsocket], see
+/// [AppWebsocket::signal_subscription].
+///
+/// The handlers are removed when the handle is dropped, or with
+/// [SignalSubscription::unsubscribe] to wait until they have been removed. The handle keeps the
+/// connection open until then.
+pub struct SignalSubscription {
+    app_ws: AppWebsocket,
+    handler_ids: Vec<String>,
+}
+
+impl SignalSubscription {
+    /// Remove another handler of the same connection together with the subscribed ones.
+    pub(crate) fn add_handler(&mut self, handler_id: String) {
+        self.handler_ids.push(handler_id);
+    }
+
+    /// Remove the handlers and release everything they hold, such as buffered signals.
+    pub async fn unsubscribe(mut self) {
+        for handler_id in std::mem::take(&mut self.handler_ids) {
+            self.app_ws.off_signal(&handler_id).await;
+        }
+    }
+}
+
+impl Drop for SignalSubscription {
+    fn drop(&mut self) {
+        if self.handler_ids.is_empty() {
+            return;
+        }
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let app_ws = self.app_ws.clone();
+            let handler_ids = std::mem::take(&mut self.handler_ids);
+            runtime.spawn(async move {
+                for handler_id in handler_ids {
+                    app_ws.off_signal(&handler_id).await;
+                }
+            });
+        }
+    }
+}
+
+/// A stream of the signals received by an [AppWebsocket], see [AppWebsocket::signal_stream].
+///
+/// The signal handler behind the stream is removed when the stream is dropped, or with
+/// [SignalStream::unsubscribe]. The stream keeps the connection open until then.
+pub struct SignalStream<T = Signal> {
+    signals: mpsc::UnboundedReceiver<T>,
+    subscription: SignalSubscription,
+}
+
+impl<T> SignalStream<T> {
+    /// Remove the signal handler behind the stream and drop the signals that were not read.
+    pub async fn unsubscribe(self) {
+        self.subscription.unsubscribe().await;
+    }
+}
+
+impl<T> Stream for SignalStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.signals.poll_recv(cx)
+    }
+}
+
+/// The number of signals a receiver of [AppWebsocket::subscribe_signals] can fall behind by
+/// before it misses signals.
+pub const SIGNAL_BROADCAST_CAPACITY: usize = 1024;
+
+/// An item of a [BufferedSignalStream].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SignalEvent {
+    Signal(Signal),
+    /// Signals may have been missed at this point of the stream, so state that is kept current
+    /// with signals should be queried again.
+    Gap(SignalGap),
+}
+
+/// Why signals may have been missed, see [SignalEvent::Gap].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignalGap {
+    /// The buffer was full, so this many signals were dropped.
+    BufferFull { dropped: u64 },
+    /// The connection to the conductor was lost. Signals sent while the client is not connected
+    /// are not delivered later.
+    Disconnected,
+}
+
+/// A stream of the signals received by an [AppWebsocket] with a bounded buffer, see
+/// [AppWebsocket::buffered_signal_stream].
+///
+/// Like [SignalStream], the handlers behind the stream are removed when it is dropped, or with
+/// [BufferedSignalStream::unsubscribe].
+pub struct BufferedSignalStream {
+    buffer: Arc<Mutex<SignalBuffer>>,
+    subscription: SignalSubscription,
+}
+
+impl BufferedSignalStream {
+    /// Remove the handlers behind the stream and free the buffer.
+    pub async fn unsubscribe(self) {
+        self.subscription.unsubscribe().await;
+    }
+}
+
+impl Stream for BufferedSignalStream {
+    type Item = SignalEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<SignalEvent>> {
+        let mut buffer = self.buffer.lock();
+        match buffer.events.pop_front() {
+            Some(event) => {
+                if let SignalEvent::Signal(_) = event {
+                    buffer.signals -= 1;
+                }
+                Poll::Ready(Some(event))
+            }
+            None => {
+                buffer.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+struct SignalBuffer {
+    events: VecDeque<SignalEvent>,
+    /// The number of [SignalEvent::Signal]s in `events`.
+    signals: usize,
+    capacity: usize,
+    waker: Option<Waker>,
+}
+
+impl SignalBuffer {
+    fn push_signal(&mut self, signal: Signal) {
+        if self.signals < self.capacity {
+            self.events.push_back(SignalEvent::Signal(signal));
+            self.signals += 1;
+        } else if let Some(SignalEvent::Gap(SignalGap::BufferFull { dropped })) =
+            self.events.back_mut()
+        {
+            *dropped += 1;
+        } else {
+            self.events
+                .push_back(SignalEvent::Gap(SignalGap::BufferFull { dropped: 1 }));
+        }
+        self.wake();
+    }
+
+    fn push_gap(&mut self, gap: SignalGap) {
+        if self.events.back() != Some(&SignalEvent::Gap(gap)) {
+            self.events.push_back(SignalEvent::Gap(gap));
+        }
+        self.wake();
+    }
+
+    fn wake(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl AppWebsocket {
+    /// Register a signal handler like [AppWebsocket::on_signal], and get a handle which removes
+    /// it when dropped instead of an id.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(app_ws: holochain_client::AppWebsocket) -> anyhow::Result<()> {
+    /// let subscription = app_ws
+    ///     .signal_subscription(|signal| println!("{signal:?}"))
+    ///     .await?;
+    /// // ...
+    /// subscription.unsubscribe().await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn signal_subscription<F: Fn(Signal) + 'static + Sync + Send>(
+        &self,
+        handler: F,
+    ) -> Result<SignalSubscription> {
+        let handler_id = self.on_signal(handler).await?;
+
+        Ok(SignalSubscription {
+            app_ws: self.clone(),
+            handler_ids: vec![handler_id],
+        })
+    }
+
+    /// Get a stream of the signals received on this connection, as an alternative to
+    /// [AppWebsocket::on_signal] for async code.
+    ///
+    /// The stream yields the same signals that a handler registered with
+    /// [AppWebsocket::on_signal] receives, in the order they were received. Signals are buffered
+    /// until they are read, so keep reading from the stream for as long as it is alive.
+    ///
+    /// ```rust,no_run
+    /// # async fn example(app_ws: holochain_client::AppWebsocket) -> anyhow::Result<()> {
+    /// use futures::StreamExt;
+    /// let mut signals = app_ws.signal_stream().await?;
+    /// while let Some(signal) = signals.next().await {
+    ///     println!("{signal:?}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn signal_stream(&self) -> Result<SignalStream> {
+        self.filtered_signal_stream(Some).await
+    }
+
+    /// Get a stream of the app signals emitted by one cell, and optionally by one zome of it.
+    ///
+    /// This is for apps with several DNAs, where each consumer only handles the signals of some
+    /// cells. System signals are not included. The stream otherwise behaves like
+    /// [AppWebsocket::signal_stream].
+    pub async fn signal_stream_filtered(
+        &self,
+        cell_id: CellId,
+        zome_name: Option<ZomeName>,
+    ) -> Result<SignalStream> {
+        self.filtered_signal_stream(move |signal| {
+            let matches = match &signal {
+                Signal::App {
+                    cell_id: signal_cell_id,
+                    zome_name: signal_zome_name,
+                    ..
+                } => {
+                    *signal_cell_id == cell_id
+                        && match &zome_name {
+                            Some(zome_name) => signal_zome_name == zome_name,
+                            None => true,
+                        }
+                }
+                Signal::System(_) => false,
+            };
+            matches.then_some(signal)
+        })
+        .await
+    }
+
+    /// Get a stream of the system signals of this connection, which the conductor sends about the
+    /// outcome of countersigning sessions.
+    ///
+    /// The stream otherwise behaves like [AppWebsocket::signal_stream].
+    pub async fn system_signal_stream(&self) -> Result<SignalStream<SystemSignal>> {
+        self.filtered_signal_stream(|signal| match signal {
+            Signal::System(signal) => Some(signal),
+            Signal::App { .. } => None,
+        })
+        .await
+    }
+
+    async fn filtered_signal_stream<T: Send + 'static>(
+        &self,
+        filter_map: impl Fn(Signal) -> Option<T> + 'static + Sync + Send,
+    ) -> Result<SignalStream<T>> {
+        let (tx, signals) = mpsc::unbounded_channel();
+        let handler_id = self
+            .on_signal(move |signal| {
+                if let Some(signal) = filter_map(signal) {
+                    // The stream may have been dropped before its handler was removed.
+                    let _ = tx.send(signal);
+                }
+            })
+            .await?;
+
+        Ok(SignalStream {
+            signals,
+            subscription: SignalSubscription {
+                app_ws: self.clone(),
+                handler_ids: vec![handler_id],
+            },
+        })
+    }
+
+    /// Get a stream of the signals received on this connection which buffers at most `capacity`
+    /// signals, and marks where signals may have been missed.
+    ///
+    /// When the buffer is full, further signals are dropped until the stream is read, and a
+    /// [SignalEvent::Gap] takes their place in the stream. A gap is also added when the connection
+    /// to the conductor is lost, since signals sent in the meantime are never delivered. Consumers
+    /// that keep state current with signals should query the state again after a gap.
+    pub async fn buffered_signal_stream(&self, capacity: usize) -> Result<BufferedSignalStream> {
+        let buffer = Arc::new(Mutex::new(SignalBuffer {
+            events: VecDeque::new(),
+            signals: 0,
+            capacity,
+            waker: None,
+        }));
+        let signal_handler_id = self
+            .on_signal({
+                let buffer = buffer.clone();
+                move |signal| buffer.lock().push_signal(signal)
+            })
+            .await?;
+        let disconnected_handler_id = self
+            .on_disconnected({
+                let buffer = buffer.clone();
+                move |()| buffer.lock().push_gap(SignalGap::Disconnected)
+            })
+            .await?;
+
+        Ok(BufferedSignalStream {
+            buffer,
+            subscription: SignalSubscription {
+                app_ws: self.clone(),
+                handler_ids: vec![signal_handler_id, disconnected_handler_id],
+            },
+        })
+    }
+
+    /// Subscribe to the signals received on this connection through a broadcast channel.
+    ///
+    /// Every receiver gets every signal, so independent consumers such as a UI layer and a
+    /// background indexer can each subscribe. All subscriptions of this connection and its clones
+    /// share one signal handler. A receiver that falls more than [SIGNAL_BROADCAST_CAPACITY]
+    /// signals behind gets [broadcast::error::RecvError::Lagged] with the number of signals it
+    /// missed, and then continues with the oldest signal still held.
+    pub async fn subscribe_signals(&self) -> Result<broadcast::Receiver<Signal>> {
+        let sender = self
+            .signal_broadcast
+            .get_or_try_init(|| async {
+                let (sender, _) = broadcast::channel(SIGNAL_BROADCAST_CAPACITY);
+                self.on_signal({
+                    let sender = sender.clone();
+                    move |signal| {
+                        // There may be no subscribers at the moment.
+                        let _ = sender.send(signal);
+                    }
+                })
+                .await?;
+                anyhow::Ok(sender)
+            })
+            .await?;
+
+        Ok(sender.subscribe())
+    }
+}
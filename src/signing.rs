@@ -1,7 +1,8 @@
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use ed25519_dalek::VerifyingKey;
 use holo_hash::AgentPubKey;
 use holochain_conductor_api::ZomeCallParamsSigned;
 use holochain_zome_types::{
@@ -12,6 +13,8 @@ use holochain_zome_types::{
 };
 
 pub(crate) mod client_signing;
+pub(crate) mod composite_signing;
+pub(crate) mod detached_signing;
 
 #[cfg(feature = "lair_signing")]
 pub(crate) mod lair_signing;
@@ -32,19 +35,68 @@ pub trait AgentSigner {
     fn get_cap_secret(&self, cell_id: &CellId) -> Option<CapSecret>;
 }
 
+/// A zome call serialized for signing.
+///
+/// The conductor verifies the signature against `data_to_sign`, which is a hash of the serialized
+/// call, and then deserializes the call from `bytes`.
+#[derive(Clone, Debug)]
+pub struct SerializedZomeCall {
+    pub bytes: Vec<u8>,
+    pub data_to_sign: Arc<[u8]>,
+}
+
+impl SerializedZomeCall {
+    pub fn new(params: &ZomeCallParams) -> Result<Self> {
+        let (bytes, bytes_hash) = params.serialize_and_hash()?;
+        Ok(Self {
+            bytes,
+            data_to_sign: bytes_hash.into(),
+        })
+    }
+
+    /// Combine the serialized call with a signature over [SerializedZomeCall::data_to_sign].
+    pub fn into_signed(self, signature: Signature) -> ZomeCallParamsSigned {
+        ZomeCallParamsSigned {
+            bytes: ExternIO(self.bytes),
+            signature,
+        }
+    }
+}
+
 /// Signs an unsigned zome call using the provided signing implementation
-pub(crate) async fn sign_zome_call(
+pub async fn sign_zome_call(
     params: ZomeCallParams,
     signer: Arc<dyn AgentSigner + Send + Sync>,
 ) -> Result<ZomeCallParamsSigned> {
     let pub_key = params.provenance.clone();
-    let (bytes, bytes_hash) = params.serialize_and_hash()?;
+    let serialized = SerializedZomeCall::new(&params)?;
     let signature = signer
-        .sign(&params.cell_id, pub_key, bytes_hash.into())
+        .sign(&params.cell_id, pub_key, serialized.data_to_sign.clone())
         .await?;
 
-    Ok(ZomeCallParamsSigned {
-        bytes: ExternIO(bytes),
-        signature,
-    })
+    Ok(serialized.into_signed(signature))
+}
+
+/// Verify the signature of a signed zome call against its provenance, as the conductor does, and
+/// return the decoded call.
+///
+/// This is useful to check calls signed by a custom signing pipeline, such as an air-gapped
+/// signer, before sending them with
+/// [AppWebsocket::signed_call_zome](crate::AppWebsocket::signed_call_zome).
+pub fn verify_signed_zome_call(signed: &ZomeCallParamsSigned) -> Result<ZomeCallParams> {
+    let params: ZomeCallParams = signed.bytes.decode()?;
+    let serialized = SerializedZomeCall::new(&params)?;
+    if serialized.bytes != signed.bytes.0 {
+        return Err(anyhow!("Zome call is not serialized canonically"));
+    }
+
+    let public_key = VerifyingKey::from_bytes(params.provenance.get_raw_32().try_into()?)?;
+    public_key
+        .verify_strict(
+            &serialized.data_to_sign,
+            &ed25519_dalek::Signature::from_bytes(&signed.signature.0),
+        )
+        .map_err(|_| anyhow!("Invalid zome call signature"))?;
+
+    Ok(params)
 }
@@ -1,11 +1,14 @@
 use super::AgentSigner;
+use crate::kv_store::{prefixed_key, KvStore};
 use async_trait::async_trait;
 use ed25519_dalek::Signer;
 use holo_hash::AgentPubKey;
+use holochain_types::prelude::{decode, encode};
 use holochain_zome_types::{
     capability::CapSecret, cell::CellId, dependencies::holochain_integrity_types::Signature,
 };
 use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, sync::Arc};
 
 pub struct SigningCredentials {
@@ -23,20 +26,150 @@ impl std::fmt::Debug for SigningCredentials {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+/// The name of the profile used by [ClientAgentSigner::new].
+pub const DEFAULT_SIGNER_PROFILE: &str = "default";
+
+/// Signs zome calls with credentials held in memory by the client.
+///
+/// Credentials are stored per named profile, such as "ui" or "background-jobs", so that different
+/// parts of an application can sign with separate credentials for the same cell. Each
+/// [ClientAgentSigner] signs with the credentials of one profile. Use
+/// [ClientAgentSigner::profile] to get a signer for another profile, which shares the credential
+/// store with this one, and [AppWebsocket::with_signer](crate::AppWebsocket::with_signer) to pick
+/// the signer for a call.
+#[derive(Debug, Clone)]
 pub struct ClientAgentSigner {
-    credentials: Arc<RwLock<HashMap<CellId, SigningCredentials>>>,
+    credentials: Arc<RwLock<HashMap<String, HashMap<CellId, SigningCredentials>>>>,
+    profile: String,
 }
 
 impl ClientAgentSigner {
     pub fn new() -> Self {
         Self {
             credentials: Arc::new(RwLock::new(HashMap::new())),
+            profile: DEFAULT_SIGNER_PROFILE.to_string(),
+        }
+    }
+
+    /// Get a signer for the named profile, which shares the credential store with this signer.
+    pub fn profile(&self, name: impl Into<String>) -> Self {
+        Self {
+            credentials: self.credentials.clone(),
+            profile: name.into(),
         }
     }
 
+    /// The name of the profile this signer signs with.
+    pub fn profile_name(&self) -> &str {
+        &self.profile
+    }
+
+    /// The names of all profiles that have credentials.
+    pub fn profiles(&self) -> Vec<String> {
+        self.credentials.read().keys().cloned().collect()
+    }
+
+    /// Add credentials for a cell to the profile of this signer.
     pub fn add_credentials(&self, cell_id: CellId, credentials: SigningCredentials) {
-        self.credentials.write().insert(cell_id, credentials);
+        self.credentials
+            .write()
+            .entry(self.profile.clone())
+            .or_default()
+            .insert(cell_id, credentials);
+    }
+
+    /// Remove the credentials for a cell from the profile of this signer and return them, for
+    /// example to replace credentials whose grant was lost.
+    pub fn remove_credentials(&self, cell_id: &CellId) -> Option<SigningCredentials> {
+        self.credentials
+            .write()
+            .get_mut(&self.profile)?
+            .remove(cell_id)
+    }
+
+    /// Write the credentials of all profiles to a [KvStore], so that they can be loaded again with
+    /// [ClientAgentSigner::load_credentials] after a restart.
+    ///
+    /// The private keys are stored as they are, so the store must be kept as secret as the keys.
+    pub async fn save_credentials(&self, kv: &impl KvStore) -> anyhow::Result<()> {
+        let entries = self
+            .credentials
+            .read()
+            .iter()
+            .flat_map(|(profile, credentials)| {
+                credentials
+                    .iter()
+                    .map(|(cell_id, credentials)| -> anyhow::Result<_> {
+                        let stored = StoredCredentials {
+                            profile: profile.clone(),
+                            cell_id: cell_id.clone(),
+                            signing_agent_key: credentials.signing_agent_key.clone(),
+                            secret_key: credentials.keypair.to_bytes(),
+                            cap_secret: credentials.cap_secret,
+                        };
+                        let key = encode(&(&stored.profile, &stored.cell_id))?;
+                        Ok((prefixed_key(CREDENTIALS_KEY_PREFIX, &key), encode(&stored)?))
+                    })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        for (key, value) in entries {
+            kv.put(&key, value).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Add the credentials stored with [ClientAgentSigner::save_credentials] to their profiles
+    /// and return how many there were.
+    pub async fn load_credentials(&self, kv: &impl KvStore) -> anyhow::Result<usize> {
+        let entries = kv.scan_prefix(CREDENTIALS_KEY_PREFIX).await?;
+        let count = entries.len();
+        for (_, value) in entries {
+            let stored: StoredCredentials = decode(&value)?;
+            self.credentials
+                .write()
+                .entry(stored.profile)
+                .or_default()
+                .insert(
+                    stored.cell_id,
+                    SigningCredentials {
+                        signing_agent_key: stored.signing_agent_key,
+                        keypair: ed25519_dalek::SigningKey::from_bytes(&stored.secret_key),
+                        cap_secret: stored.cap_secret,
+                    },
+                );
+        }
+
+        Ok(count)
+    }
+
+    fn with_credentials<R>(
+        &self,
+        cell_id: &CellId,
+        f: impl FnOnce(&SigningCredentials) -> R,
+    ) -> Option<R> {
+        self.credentials
+            .read()
+            .get(&self.profile)
+            .and_then(|credentials| credentials.get(cell_id))
+            .map(f)
+    }
+}
+
+const CREDENTIALS_KEY_PREFIX: &[u8] = b"credentials/";
+
+#[derive(Serialize, Deserialize)]
+struct StoredCredentials {
+    profile: String,
+    cell_id: CellId,
+    signing_agent_key: AgentPubKey,
+    secret_key: [u8; 32],
+    cap_secret: CapSecret,
+}
+
+impl Default for ClientAgentSigner {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -48,23 +181,26 @@ impl AgentSigner for ClientAgentSigner {
         _provenance: AgentPubKey,
         data_to_sign: Arc<[u8]>,
     ) -> Result<Signature, anyhow::Error> {
-        let credentials_lock = self.credentials.read();
-        let credentials = credentials_lock
-            .get(cell_id)
-            .ok_or_else(|| anyhow::anyhow!("No credentials found for cell: {:?}", cell_id))?;
-        let signature = credentials.keypair.try_sign(&data_to_sign)?;
+        let signature = self
+            .with_credentials(cell_id, |credentials| {
+                credentials.keypair.try_sign(&data_to_sign)
+            })
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No credentials found for cell {:?} in profile {}",
+                    cell_id,
+                    self.profile
+                )
+            })??;
         Ok(Signature(signature.to_bytes()))
     }
 
     fn get_provenance(&self, cell_id: &CellId) -> Option<AgentPubKey> {
-        self.credentials
-            .read()
-            .get(cell_id)
-            .map(|c| c.signing_agent_key.clone())
+        self.with_credentials(cell_id, |c| c.signing_agent_key.clone())
     }
 
     fn get_cap_secret(&self, cell_id: &CellId) -> Option<CapSecret> {
-        self.credentials.read().get(cell_id).map(|c| c.cap_secret)
+        self.with_credentials(cell_id, |c| c.cap_secret)
     }
 }
 
@@ -0,0 +1,69 @@
+use super::AgentSigner;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use holo_hash::AgentPubKey;
+use holochain_zome_types::{
+    capability::CapSecret, cell::CellId, dependencies::holochain_integrity_types::Signature,
+};
+use std::sync::Arc;
+
+/// Combines several signers, picking a signer per cell.
+///
+/// Signers are tried in the order they were added, and a cell is signed for by the first signer
+/// that has credentials for it. For example, to sign for cells with keys in Lair where they are
+/// registered and with client credentials otherwise, add a [LairAgentSigner](crate::LairAgentSigner)
+/// followed by a [ClientAgentSigner](crate::ClientAgentSigner).
+#[derive(Clone, Default)]
+pub struct CompositeSigner {
+    signers: Vec<Arc<dyn AgentSigner + Send + Sync>>,
+}
+
+impl CompositeSigner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a signer which is used for the cells that the previously added signers have no
+    /// credentials for.
+    pub fn with_signer(mut self, signer: Arc<dyn AgentSigner + Send + Sync>) -> Self {
+        self.signers.push(signer);
+        self
+    }
+
+    fn signer_for(&self, cell_id: &CellId) -> Option<&Arc<dyn AgentSigner + Send + Sync>> {
+        self.signers
+            .iter()
+            .find(|signer| signer.get_provenance(cell_id).is_some())
+    }
+}
+
+#[async_trait]
+impl AgentSigner for CompositeSigner {
+    async fn sign(
+        &self,
+        cell_id: &CellId,
+        provenance: AgentPubKey,
+        data_to_sign: Arc<[u8]>,
+    ) -> Result<Signature> {
+        self.signer_for(cell_id)
+            .ok_or_else(|| anyhow!("No signer has credentials for cell {:?}", cell_id))?
+            .sign(cell_id, provenance, data_to_sign)
+            .await
+    }
+
+    fn get_provenance(&self, cell_id: &CellId) -> Option<AgentPubKey> {
+        self.signer_for(cell_id)
+            .and_then(|signer| signer.get_provenance(cell_id))
+    }
+
+    fn get_cap_secret(&self, cell_id: &CellId) -> Option<CapSecret> {
+        self.signer_for(cell_id)
+            .and_then(|signer| signer.get_cap_secret(cell_id))
+    }
+}
+
+impl From<CompositeSigner> for Arc<dyn AgentSigner + Send + Sync> {
+    fn from(signer: CompositeSigner) -> Self {
+        Arc::new(signer)
+    }
+}
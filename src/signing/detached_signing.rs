@@ -0,0 +1,93 @@
+use super::{verify_signed_zome_call, SerializedZomeCall};
+use anyhow::{anyhow, Result};
+use holo_hash::AgentPubKey;
+use holochain_conductor_api::ZomeCallParamsSigned;
+use holochain_zome_types::{
+    cell::CellId, dependencies::holochain_integrity_types::Signature, zome_io::ZomeCallParams,
+};
+use std::sync::Arc;
+
+/// The data that an external signer has to sign for one zome call of a [DetachedSigningBatch].
+#[derive(Clone, Debug)]
+pub struct SigningRequest {
+    /// The agent whose key must produce the signature.
+    pub provenance: AgentPubKey,
+    pub cell_id: CellId,
+    /// The exact bytes to sign.
+    pub data_to_sign: Arc<[u8]>,
+}
+
+/// A batch of zome calls which are signed elsewhere, for setups where the signing keys never live
+/// on the networked host.
+///
+/// Build the calls with [AppWebsocket::build_zome_call](crate::AppWebsocket::build_zome_call),
+/// hand the [SigningRequest]s to the air-gapped signer and combine the detached signatures it
+/// produces with [DetachedSigningBatch::into_signed]. The signed calls can then be sent with
+/// [AppWebsocket::signed_call_zomes](crate::AppWebsocket::signed_call_zomes).
+///
+/// Zome calls expire a few minutes after they were built, so the round trip to the signer has to
+/// complete within that time.
+#[derive(Clone, Debug)]
+pub struct DetachedSigningBatch {
+    calls: Vec<(SigningRequest, SerializedZomeCall)>,
+}
+
+impl DetachedSigningBatch {
+    pub fn new(calls: impl IntoIterator<Item = ZomeCallParams>) -> Result<Self> {
+        let calls = calls
+            .into_iter()
+            .map(|params| {
+                let serialized = SerializedZomeCall::new(&params)?;
+                let request = SigningRequest {
+                    provenance: params.provenance,
+                    cell_id: params.cell_id,
+                    data_to_sign: serialized.data_to_sign.clone(),
+                };
+                Ok((request, serialized))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { calls })
+    }
+
+    /// The data to sign, in the order of the calls.
+    pub fn signing_requests(&self) -> Vec<SigningRequest> {
+        self.calls
+            .iter()
+            .map(|(request, _)| request.clone())
+            .collect()
+    }
+
+    /// Combine the calls with their detached signatures, given in the order of the calls.
+    ///
+    /// Every signature is verified against the provenance of its call, so that a wrong or
+    /// misordered signature is reported here instead of by the conductor.
+    pub fn into_signed(self, signatures: Vec<Signature>) -> Result<Vec<ZomeCallParamsSigned>> {
+        if signatures.len() != self.calls.len() {
+            return Err(anyhow!(
+                "Expected {} signatures but got {}",
+                self.calls.len(),
+                signatures.len()
+            ));
+        }
+
+        self.calls
+            .into_iter()
+            .zip(signatures)
+            .enumerate()
+            .map(|(index, ((_, serialized), signature))| {
+                let signed = serialized.into_signed(signature);
+                verify_signed_zome_call(&signed)
+                    .map_err(|err| err.context(format!("Signature {index} is invalid")))?;
+                Ok(signed)
+            })
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+}
@@ -0,0 +1,108 @@
+use parking_lot::Mutex;
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+
+/// Request statistics of a connection, per operation.
+///
+/// Operations are named after the request type on the wire, such as `install_app` or `call_zome`.
+/// Byte counts are the sizes of the data that requests and responses carry, such as the inputs and
+/// outputs of zome calls, which are known without encoding the messages again. Operations that
+/// carry no such data count zero bytes.
+#[derive(Clone, Debug, Default)]
+pub struct ConnectionStats {
+    pub operations: BTreeMap<String, OperationStats>,
+}
+
+impl ConnectionStats {
+    /// The statistics of all operations combined.
+    pub fn total(&self) -> OperationStats {
+        self.operations
+            .values()
+            .fold(OperationStats::default(), |mut total, op| {
+                total.count += op.count;
+                total.errors += op.errors;
+                total.bytes_sent += op.bytes_sent;
+                total.bytes_received += op.bytes_received;
+                total.total_latency += op.total_latency;
+                total.max_latency = total.max_latency.max(op.max_latency);
+                total
+            })
+    }
+}
+
+/// Request statistics for one operation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OperationStats {
+    pub count: u64,
+    /// Requests that failed, either because the conductor returned an error or because no response
+    /// was received.
+    pub errors: u64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub total_latency: Duration,
+    pub max_latency: Duration,
+}
+
+impl OperationStats {
+    pub fn mean_latency(&self) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_nanos((self.total_latency.as_nanos() / self.count as u128) as u64)
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct StatsRecorder {
+    operations: Mutex<BTreeMap<String, OperationStats>>,
+}
+
+/// A request that is in flight, see [StatsRecorder::start].
+pub(crate) struct PendingRequest {
+    operation: String,
+    bytes_sent: u64,
+    started_at: Instant,
+}
+
+impl PendingRequest {
+    /// The request type on the wire.
+    pub(crate) fn operation(&self) -> &str {
+        &self.operation
+    }
+}
+
+impl StatsRecorder {
+    /// Start timing a request, see [operation_name](crate::wire::operation_name) and
+    /// [PayloadSize](crate::wire::PayloadSize).
+    pub(crate) fn start(&self, operation: String, bytes_sent: u64) -> PendingRequest {
+        PendingRequest {
+            operation,
+            bytes_sent,
+            started_at: Instant::now(),
+        }
+    }
+
+    /// Record a completed request. `bytes_received` is zero if no response was received.
+    pub(crate) fn finish(&self, pending: PendingRequest, bytes_received: u64, is_error: bool) {
+        let latency = pending.started_at.elapsed();
+
+        let mut operations = self.operations.lock();
+        let op = operations.entry(pending.operation).or_default();
+        op.count += 1;
+        if is_error {
+            op.errors += 1;
+        }
+        op.bytes_sent += pending.bytes_sent;
+        op.bytes_received += bytes_received;
+        op.total_latency += latency;
+        op.max_latency = op.max_latency.max(latency);
+    }
+
+    pub(crate) fn snapshot(&self) -> ConnectionStats {
+        ConnectionStats {
+            operations: self.operations.lock().clone(),
+        }
+    }
+}
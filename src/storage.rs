@@ -0,0 +1,74 @@
+use crate::{AdminWebsocket, ConductorApiResult};
+use holo_hash::DnaHash;
+use holochain_conductor_api::{DnaStorageInfo, StorageBlob};
+use holochain_types::app::InstalledAppId;
+
+/// The storage used by an installed app, see [AdminWebsocket::storage_info_per_app].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AppStorageInfo {
+    pub installed_app_id: InstalledAppId,
+    /// The DNAs the app's storage is made up of.
+    pub dna_hashes: Vec<DnaHash>,
+    pub authored_data_size: usize,
+    pub authored_data_size_on_disk: usize,
+    pub dht_data_size: usize,
+    pub dht_data_size_on_disk: usize,
+    pub cache_data_size: usize,
+    pub cache_data_size_on_disk: usize,
+    /// Whether the app shares some of its DNAs with other apps. The storage of a shared DNA is
+    /// counted in full for every app that uses it.
+    pub shares_dnas: bool,
+}
+
+impl AppStorageInfo {
+    /// The total size on disk of the app's authored, DHT and cache data.
+    pub fn total_size_on_disk(&self) -> usize {
+        self.authored_data_size_on_disk + self.dht_data_size_on_disk + self.cache_data_size_on_disk
+    }
+
+    fn add(&mut self, dna: &DnaStorageInfo) {
+        self.dna_hashes.push(dna.dna_hash.clone());
+        self.authored_data_size += dna.authored_data_size;
+        self.authored_data_size_on_disk += dna.authored_data_size_on_disk;
+        self.dht_data_size += dna.dht_data_size;
+        self.dht_data_size_on_disk += dna.dht_data_size_on_disk;
+        self.cache_data_size += dna.cache_data_size;
+        self.cache_data_size_on_disk += dna.cache_data_size_on_disk;
+        self.shares_dnas |= dna.used_by.len() > 1;
+    }
+}
+
+impl AdminWebsocket {
+    /// Get the storage used by each installed app, by joining [AdminWebsocket::storage_info]
+    /// with the installed apps.
+    ///
+    /// Apps are listed in the order of [AdminWebsocket::list_apps], including apps which don't
+    /// use any storage yet.
+    pub async fn storage_info_per_app(&self) -> ConductorApiResult<Vec<AppStorageInfo>> {
+        let mut apps: Vec<AppStorageInfo> = self
+            .list_apps(None)
+            .await?
+            .into_iter()
+            .map(|app| AppStorageInfo {
+                installed_app_id: app.installed_app_id,
+                ..Default::default()
+            })
+            .collect();
+
+        for blob in self.storage_info().await?.blobs {
+            #[allow(unreachable_patterns)]
+            let dna = match blob {
+                StorageBlob::Dna(dna) => dna,
+                _ => continue,
+            };
+            for app in apps
+                .iter_mut()
+                .filter(|app| dna.used_by.contains(&app.installed_app_id))
+            {
+                app.add(&dna);
+            }
+        }
+
+        Ok(apps)
+    }
+}
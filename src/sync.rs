@@ -0,0 +1,430 @@
+//! Push/pull synchronisation between a local store and zome functions, for local-first apps.
+//!
+//! The app keeps its data in a [LocalStore] and records local edits in the store's queue with
+//! [LocalStore::enqueue], whether or not it is connected to a conductor. A [SyncEngine] pulls the
+//! records that changed on the network since the last pull and pushes the queued edits through zome
+//! functions that follow this convention:
+//!
+//! - the create and update functions take a record and return the record as it was committed;
+//! - the list function takes a [ListSince] and returns the records changed since that time.
+//!
+//! When a pulled record has a queued local edit, the [ConflictHandler] decides which one wins.
+//!
+//! [KvLocalStore] implements [LocalStore] on top of any [KvStore].
+
+use crate::{
+    kv_store::{prefixed_key, KvStore},
+    AppWebsocket, ConductorApiError, ConductorError, ZomeCallTarget,
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use holochain_types::prelude::{decode, encode};
+use holochain_zome_types::prelude::{ExternIO, FunctionName, Timestamp, ZomeName};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{fmt::Debug, hash::Hash, marker::PhantomData, time::Duration};
+
+/// The input to the list function of a [SyncEndpoints].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ListSince {
+    /// Only list records changed at or after this time, or all records if `None`.
+    pub since: Option<Timestamp>,
+}
+
+/// A local edit waiting to be pushed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LocalChange<R> {
+    Create(R),
+    Update(R),
+}
+
+impl<R> LocalChange<R> {
+    pub fn record(&self) -> &R {
+        match self {
+            LocalChange::Create(record) | LocalChange::Update(record) => record,
+        }
+    }
+}
+
+/// A [LocalChange] in the queue of a [LocalStore], with its position in the queue.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueuedChange<R> {
+    pub seq: u64,
+    pub change: LocalChange<R>,
+}
+
+/// Storage for the local copy of the records and the queue of local edits.
+#[async_trait]
+pub trait LocalStore: Send + Sync {
+    type Id: Eq + Hash + Clone + Send + Sync;
+    type Record: Serialize + DeserializeOwned + Debug + Clone + Send + Sync;
+
+    /// The id that identifies a record across local edits and remote changes.
+    fn record_id(record: &Self::Record) -> Self::Id;
+
+    /// Add a local edit to the end of the queue and return its sequence number.
+    async fn enqueue(&self, change: LocalChange<Self::Record>) -> Result<u64>;
+
+    /// The queued local edits, oldest first.
+    async fn queued_changes(&self) -> Result<Vec<QueuedChange<Self::Record>>>;
+
+    /// Remove a local edit from the queue once it has been pushed or superseded.
+    async fn dequeue(&self, seq: u64) -> Result<()>;
+
+    /// Store records as the current local state, replacing any records with the same id.
+    async fn save(&self, records: Vec<Self::Record>) -> Result<()>;
+
+    /// When the last successful pull started, or `None` if nothing has been pulled yet.
+    async fn last_pulled_at(&self) -> Result<Option<Timestamp>>;
+
+    async fn set_last_pulled_at(&self, pulled_at: Timestamp) -> Result<()>;
+}
+
+/// A record which knows its own id, so that a [KvLocalStore] can store it.
+pub trait SyncRecord: Serialize + DeserializeOwned + Debug + Clone + Send + Sync {
+    type Id: Serialize + Eq + Hash + Clone + Send + Sync;
+
+    fn id(&self) -> Self::Id;
+}
+
+/// A [LocalStore] which keeps the records and the queue of local edits in a [KvStore].
+///
+/// All keys start with the namespace given to [KvLocalStore::new], so several stores can share
+/// one [KvStore]. Records and keys are encoded with MessagePack.
+pub struct KvLocalStore<S, R> {
+    kv: S,
+    namespace: Vec<u8>,
+    // Makes taking the next sequence number and storing the edit one step.
+    enqueue_lock: tokio::sync::Mutex<()>,
+    _record: PhantomData<fn() -> R>,
+}
+
+impl<S: KvStore, R: SyncRecord> KvLocalStore<S, R> {
+    pub fn new(kv: S, namespace: impl Into<String>) -> Self {
+        Self {
+            kv,
+            namespace: format!("{}/", namespace.into()).into_bytes(),
+            enqueue_lock: tokio::sync::Mutex::new(()),
+            _record: PhantomData,
+        }
+    }
+
+    pub fn kv_store(&self) -> &S {
+        &self.kv
+    }
+
+    /// The local copy of the records.
+    pub async fn records(&self) -> Result<Vec<R>> {
+        self.kv
+            .scan_prefix(&self.key(b"record/"))
+            .await?
+            .into_iter()
+            .map(|(_, value)| Ok(decode(&value)?))
+            .collect()
+    }
+
+    fn key(&self, rest: &[u8]) -> Vec<u8> {
+        prefixed_key(&self.namespace, rest)
+    }
+
+    fn record_key(&self, id: &R::Id) -> Result<Vec<u8>> {
+        Ok(self.key(&prefixed_key(b"record/", &encode(id)?)))
+    }
+
+    // Big-endian, so that the queue is scanned in sequence order.
+    fn queue_key(&self, seq: u64) -> Vec<u8> {
+        self.key(&prefixed_key(b"queue/", &seq.to_be_bytes()))
+    }
+}
+
+#[async_trait]
+impl<S: KvStore, R: SyncRecord> LocalStore for KvLocalStore<S, R> {
+    type Id = R::Id;
+    type Record = R;
+
+    fn record_id(record: &R) -> R::Id {
+        record.id()
+    }
+
+    async fn enqueue(&self, change: LocalChange<R>) -> Result<u64> {
+        let _lock = self.enqueue_lock.lock().await;
+        let next_seq_key = self.key(b"next_seq");
+        let seq = match self.kv.get(&next_seq_key).await? {
+            Some(bytes) => u64::from_be_bytes(
+                bytes
+                    .try_into()
+                    .map_err(|_| anyhow!("Invalid sequence number in the offline queue"))?,
+            ),
+            None => 0,
+        };
+        self.kv.put(&self.queue_key(seq), encode(&change)?).await?;
+        self.kv
+            .put(&next_seq_key, (seq + 1).to_be_bytes().to_vec())
+            .await?;
+
+        Ok(seq)
+    }
+
+    async fn queued_changes(&self) -> Result<Vec<QueuedChange<R>>> {
+        let prefix = self.key(b"queue/");
+        self.kv
+            .scan_prefix(&prefix)
+            .await?
+            .into_iter()
+            .map(|(key, value)| {
+                let seq = key[prefix.len()..]
+                    .try_into()
+                    .map(u64::from_be_bytes)
+                    .map_err(|_| anyhow!("Invalid key in the offline queue"))?;
+                Ok(QueuedChange {
+                    seq,
+                    change: decode(&value)?,
+                })
+            })
+            .collect()
+    }
+
+    async fn dequeue(&self, seq: u64) -> Result<()> {
+        self.kv.delete(&self.queue_key(seq)).await
+    }
+
+    async fn save(&self, records: Vec<R>) -> Result<()> {
+        for record in records {
+            self.kv
+                .put(&self.record_key(&record.id())?, encode(&record)?)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn last_pulled_at(&self) -> Result<Option<Timestamp>> {
+        match self.kv.get(&self.key(b"last_pulled_at")).await? {
+            Some(bytes) => Ok(Some(decode(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn set_last_pulled_at(&self, pulled_at: Timestamp) -> Result<()> {
+        self.kv
+            .put(&self.key(b"last_pulled_at"), encode(&pulled_at)?)
+            .await
+    }
+}
+
+/// How to resolve a pulled record that has a queued local edit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Resolution<R> {
+    /// Keep the local edit, which will overwrite the remote record when it is pushed.
+    KeepLocal,
+    /// Discard the local edit and keep the remote record.
+    KeepRemote,
+    /// Replace the local edit with an update to the given record.
+    Merge(R),
+}
+
+/// What to do with a local edit that the conductor rejected when it was pushed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rejection {
+    /// Keep the edit in the queue and push it again on the next sync.
+    Retry,
+    /// Remove the edit from the queue.
+    Discard,
+}
+
+/// Hooks for resolving conflicts between local edits and the network.
+///
+/// By default, local edits win and rejected edits are retried.
+pub trait ConflictHandler<R>: Send + Sync {
+    fn resolve(&self, _local: &LocalChange<R>, _remote: &R) -> Resolution<R> {
+        Resolution::KeepLocal
+    }
+
+    fn rejected(&self, _change: &LocalChange<R>, _error: &ConductorError) -> Rejection {
+        Rejection::Retry
+    }
+}
+
+/// The default [ConflictHandler], which keeps local edits and retries rejected ones.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct KeepLocal;
+
+impl<R> ConflictHandler<R> for KeepLocal {}
+
+/// The zome functions a [SyncEngine] pushes to and pulls from.
+#[derive(Clone, Debug)]
+pub struct SyncEndpoints {
+    pub target: ZomeCallTarget,
+    pub zome_name: ZomeName,
+    pub create_fn: FunctionName,
+    pub update_fn: FunctionName,
+    pub list_since_fn: FunctionName,
+}
+
+/// The outcome of a [SyncEngine::sync_once].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    pub pushed: usize,
+    pub pulled: usize,
+    pub conflicts: usize,
+    pub rejected: usize,
+}
+
+/// Synchronises a [LocalStore] with zome functions.
+pub struct SyncEngine<S: LocalStore> {
+    app_ws: AppWebsocket,
+    store: S,
+    endpoints: SyncEndpoints,
+    conflict_handler: Box<dyn ConflictHandler<S::Record>>,
+}
+
+impl<S: LocalStore> SyncEngine<S> {
+    pub fn new(app_ws: AppWebsocket, store: S, endpoints: SyncEndpoints) -> Self {
+        Self {
+            app_ws,
+            store,
+            endpoints,
+            conflict_handler: Box::new(KeepLocal),
+        }
+    }
+
+    pub fn with_conflict_handler(
+        mut self,
+        conflict_handler: impl ConflictHandler<S::Record> + 'static,
+    ) -> Self {
+        self.conflict_handler = Box::new(conflict_handler);
+        self
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Pull remote changes and then push the queued local edits.
+    ///
+    /// Pulling first means conflicts are resolved before local edits overwrite remote records.
+    pub async fn sync_once(&self) -> Result<SyncReport> {
+        let mut report = SyncReport::default();
+        self.pull(&mut report).await?;
+        self.push(&mut report).await?;
+
+        Ok(report)
+    }
+
+    /// Run [SyncEngine::sync_once] every `interval` until it fails.
+    ///
+    /// Local edits can be queued in the store while this runs.
+    pub async fn run(&self, interval: Duration) -> Result<()> {
+        loop {
+            self.sync_once().await?;
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    async fn pull(&self, report: &mut SyncReport) -> Result<()> {
+        // Use the time the pull started as the watermark, so changes made during the pull are
+        // listed again by the next one.
+        let started_at = Timestamp::now();
+        let since = self.store.last_pulled_at().await?;
+        let remote: Vec<S::Record> = self
+            .call(
+                self.endpoints.list_since_fn.clone(),
+                ExternIO::encode(ListSince { since })?,
+            )
+            .await?;
+        report.pulled = remote.len();
+
+        let mut queued = self.store.queued_changes().await?;
+        let mut to_save = Vec::with_capacity(remote.len());
+        for remote_record in remote {
+            let id = S::record_id(&remote_record);
+            let Some(position) = queued
+                .iter()
+                .position(|queued| S::record_id(queued.change.record()) == id)
+            else {
+                to_save.push(remote_record);
+                continue;
+            };
+
+            report.conflicts += 1;
+            let local = &queued[position];
+            match self.conflict_handler.resolve(&local.change, &remote_record) {
+                Resolution::KeepLocal => {}
+                Resolution::KeepRemote => {
+                    self.store.dequeue(local.seq).await?;
+                    queued.remove(position);
+                    to_save.push(remote_record);
+                }
+                Resolution::Merge(merged) => {
+                    self.store.dequeue(local.seq).await?;
+                    queued.remove(position);
+                    self.store
+                        .enqueue(LocalChange::Update(merged.clone()))
+                        .await?;
+                    to_save.push(merged);
+                }
+            }
+        }
+
+        self.store.save(to_save).await?;
+        self.store.set_last_pulled_at(started_at).await?;
+
+        Ok(())
+    }
+
+    async fn push(&self, report: &mut SyncReport) -> Result<()> {
+        for queued in self.store.queued_changes().await? {
+            let fn_name = match &queued.change {
+                LocalChange::Create(_) => self.endpoints.create_fn.clone(),
+                LocalChange::Update(_) => self.endpoints.update_fn.clone(),
+            };
+            let payload = ExternIO::encode(queued.change.record())?;
+
+            match self.call::<S::Record>(fn_name, payload).await {
+                Ok(committed) => {
+                    self.store.save(vec![committed]).await?;
+                    self.store.dequeue(queued.seq).await?;
+                    report.pushed += 1;
+                }
+                Err(err) => {
+                    // Errors that did not come from the conductor, such as a lost connection,
+                    // leave the queue as it is for the next sync.
+                    let Some(conductor_error) = err
+                        .downcast_ref::<ConductorApiError>()
+                        .and_then(|err| err.conductor_error())
+                    else {
+                        return Err(err);
+                    };
+
+                    report.rejected += 1;
+                    if self
+                        .conflict_handler
+                        .rejected(&queued.change, &conductor_error)
+                        == Rejection::Discard
+                    {
+                        self.store.dequeue(queued.seq).await?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn call<T: DeserializeOwned + Debug>(
+        &self,
+        fn_name: FunctionName,
+        payload: ExternIO,
+    ) -> Result<T> {
+        let response = self
+            .app_ws
+            .call_zome(
+                self.endpoints.target.clone(),
+                self.endpoints.zome_name.clone(),
+                fn_name,
+                payload,
+            )
+            .await?;
+
+        Ok(response.decode()?)
+    }
+}
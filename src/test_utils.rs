@@ -0,0 +1,14 @@
+//! Helpers for writing integration tests against a running conductor.
+//!
+//! Enabled with the `test_utils` feature.
+
+mod credentials;
+mod network;
+mod scope;
+mod snapshot;
+mod sweet;
+
+pub use credentials::{lose_signing_grants, ungranted_credentials};
+pub use network::TestNetwork;
+pub use scope::TestScope;
+pub use snapshot::{AppSnapshot, ConductorSnapshot, RestoredConductor};
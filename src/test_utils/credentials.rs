@@ -0,0 +1,106 @@
+use crate::{admin_websocket::is_enabled, AdminWebsocket, SigningCredentials};
+use anyhow::{anyhow, Result};
+use holo_hash::AgentPubKey;
+use holochain::sweettest::{SweetConductor, SweetLocalRendezvous};
+use holochain_conductor_api::{AppInfo, CellInfo};
+use holochain_types::app::{AppBundleSource, InstallAppPayload, InstalledAppId};
+use holochain_zome_types::{capability::CAP_SECRET_BYTES, prelude::CellId};
+use rand::{rngs::OsRng, RngCore};
+use std::collections::HashSet;
+
+/// Simulate the loss of the capability grants behind signing credentials, as happens when a
+/// conductor is reset and its grants are not restored.
+///
+/// `conductor` is shut down and replaced with a conductor started from fresh state, which shares
+/// the keystore of the original conductor and so has the same agent keys. The apps in `sources`
+/// are installed again from their bundle for the same agent, with the default settings of the
+/// bundle, and enabled if they were enabled before. Their cells have the same ids but fresh source
+/// chains without any capability grants, so zome calls signed with credentials authorized before
+/// the reset fail with
+/// [ConductorErrorKind::ZomeCallUnauthorized](crate::ConductorErrorKind::ZomeCallUnauthorized)
+/// until new credentials are authorized. Other apps are not installed again.
+///
+/// The interfaces of the original conductor close with it, so clients have to connect to the
+/// replacement.
+pub async fn lose_signing_grants(
+    conductor: &mut SweetConductor,
+    sources: impl IntoIterator<Item = (InstalledAppId, AppBundleSource)>,
+) -> Result<()> {
+    let apps = AdminWebsocket::from_sweet_conductor(conductor)
+        .await?
+        .list_apps(None)
+        .await
+        .map_err(|err| anyhow!("Error listing apps: {err:?}"))?;
+
+    let mut config = conductor.get_config().clone();
+    config.data_root_path = None;
+    let keystore = conductor.keystore();
+    conductor.shutdown().await;
+    *conductor = SweetConductor::create_with_defaults(
+        config,
+        Some(keystore),
+        Some(SweetLocalRendezvous::new().await),
+    )
+    .await;
+
+    let admin_ws = AdminWebsocket::from_sweet_conductor(conductor).await?;
+    for (installed_app_id, source) in sources {
+        let app = apps
+            .iter()
+            .find(|app| app.installed_app_id == installed_app_id)
+            .ok_or_else(|| anyhow!("App {installed_app_id} is not installed"))?;
+        let reinstalled = admin_ws
+            .install_app(InstallAppPayload {
+                source,
+                agent_key: Some(app.agent_pub_key.clone()),
+                installed_app_id: Some(installed_app_id.clone()),
+                network_seed: None,
+                roles_settings: None,
+                ignore_genesis_failure: false,
+                allow_throwaway_random_agent_key: false,
+            })
+            .await
+            .map_err(|err| anyhow!("Error installing app {installed_app_id}: {err:?}"))?;
+        if provisioned_cell_ids(&reinstalled) != provisioned_cell_ids(app) {
+            return Err(anyhow!(
+                "The cells of app {installed_app_id} changed, it was not installed with the default \
+                 settings of its bundle"
+            ));
+        }
+        if is_enabled(&app.status) {
+            admin_ws
+                .enable_app(installed_app_id.clone())
+                .await
+                .map_err(|err| anyhow!("Error enabling app {installed_app_id}: {err:?}"))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn provisioned_cell_ids(app_info: &AppInfo) -> HashSet<CellId> {
+    app_info
+        .cell_info
+        .values()
+        .flatten()
+        .filter_map(|cell_info| match cell_info {
+            CellInfo::Provisioned(cell) => Some(cell.cell_id.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Generate signing credentials which no conductor has a grant for.
+pub fn ungranted_credentials() -> SigningCredentials {
+    let mut csprng = OsRng;
+    let keypair = ed25519_dalek::SigningKey::generate(&mut csprng);
+    let signing_agent_key = AgentPubKey::from_raw_32(keypair.verifying_key().as_bytes().to_vec());
+    let mut cap_secret = [0; CAP_SECRET_BYTES];
+    csprng.fill_bytes(&mut cap_secret);
+
+    SigningCredentials {
+        signing_agent_key,
+        keypair,
+        cap_secret: cap_secret.into(),
+    }
+}
@@ -0,0 +1,206 @@
+use crate::{AdminWebsocket, ConductorApiResult};
+use anyhow::{anyhow, Result};
+use holochain::{
+    conductor::ConductorHandle,
+    prelude::{Block, BlockTarget, CellBlockReason, CellId, InclusiveTimestampInterval, Timestamp},
+    sweettest::SweetConductor,
+};
+use holochain_conductor_api::CellInfo;
+use holochain_types::app::InstalledAppId;
+
+/// Controls which of a set of test conductors can reach each other, for scripting partition
+/// scenarios.
+///
+/// Each node is a conductor and the app under test on that conductor. A partition splits the
+/// nodes into two sides which stay online but stop gossiping and publishing to each other: every
+/// conductor blocks the cells of the nodes on the other side, so its network refuses to exchange
+/// data with their agents. Nodes on the same side keep talking to each other.
+///
+/// Nodes can also be taken offline entirely, by disabling their app, which removes their cells
+/// from the network. Bringing a node back online enables the app again and exchanges peer info so
+/// the nodes find each other without waiting for bootstrapping.
+pub struct TestNetwork {
+    nodes: Vec<TestNode>,
+    /// The blocks each node applied, with the node whose cell is blocked.
+    blocks: Vec<(usize, usize, Block)>,
+}
+
+struct TestNode {
+    conductor: ConductorHandle,
+    admin_ws: AdminWebsocket,
+    installed_app_id: InstalledAppId,
+    online: bool,
+}
+
+impl TestNetwork {
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            blocks: Vec::new(),
+        }
+    }
+
+    /// Add a conductor with an installed and enabled app to the network.
+    ///
+    /// The node keeps a handle to the running conductor, so a conductor which is restarted has to
+    /// be added again. Returns the index used to refer to the node.
+    pub fn add_node(
+        &mut self,
+        conductor: &SweetConductor,
+        admin_ws: AdminWebsocket,
+        installed_app_id: InstalledAppId,
+    ) -> usize {
+        self.nodes.push(TestNode {
+            conductor: conductor.raw_handle(),
+            admin_ws,
+            installed_app_id,
+            online: true,
+        });
+        self.nodes.len() - 1
+    }
+
+    pub fn admin_ws(&self, node: usize) -> &AdminWebsocket {
+        &self.nodes[node].admin_ws
+    }
+
+    pub fn is_online(&self, node: usize) -> bool {
+        self.nodes[node].online
+    }
+
+    /// Whether there is a partition between the two nodes.
+    pub fn is_partitioned(&self, a: usize, b: usize) -> bool {
+        self.blocks
+            .iter()
+            .any(|(node, other, _)| *node == a && *other == b)
+    }
+
+    /// Make every online node aware of the agents on every other online node.
+    pub async fn exchange_peer_info(&self) -> ConductorApiResult<()> {
+        let online = self.nodes.iter().filter(|n| n.online).collect::<Vec<_>>();
+
+        let mut agent_infos = Vec::with_capacity(online.len());
+        for node in &online {
+            agent_infos.push(node.admin_ws.agent_info(None).await?);
+        }
+
+        for (i, node) in online.iter().enumerate() {
+            let others = agent_infos
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .flat_map(|(_, infos)| infos.iter().cloned())
+                .collect::<Vec<_>>();
+            if !others.is_empty() {
+                node.admin_ws.add_agent_info(others).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Disconnect a node from all others by disabling its app.
+    pub async fn take_offline(&mut self, node: usize) -> ConductorApiResult<()> {
+        let node = &mut self.nodes[node];
+        node.admin_ws
+            .disable_app(node.installed_app_id.clone())
+            .await?;
+        node.online = false;
+
+        Ok(())
+    }
+
+    /// Reconnect a node by enabling its app and exchanging peer info with the online nodes.
+    pub async fn bring_online(&mut self, node: usize) -> ConductorApiResult<()> {
+        let node = &mut self.nodes[node];
+        node.admin_ws
+            .enable_app(node.installed_app_id.clone())
+            .await?;
+        node.online = true;
+
+        self.exchange_peer_info().await
+    }
+
+    /// Stop gossip and publishing between the nodes in `side_a` and the nodes in `side_b`.
+    ///
+    /// All nodes stay online. A node may be partitioned from several sets of nodes at once.
+    pub async fn partition(&mut self, side_a: &[usize], side_b: &[usize]) -> Result<()> {
+        if let Some(node) = side_a.iter().find(|node| side_b.contains(node)) {
+            return Err(anyhow!("Node {node} is on both sides of the partition"));
+        }
+
+        let mut cell_ids = Vec::with_capacity(self.nodes.len());
+        for node in 0..self.nodes.len() {
+            cell_ids.push(self.cell_ids(node).await?);
+        }
+
+        let interval = InclusiveTimestampInterval::try_new(Timestamp::now(), Timestamp::MAX)
+            .map_err(|err| anyhow!("Invalid block interval: {err:?}"))?;
+        for (this_side, other_side) in [(side_a, side_b), (side_b, side_a)] {
+            for &node in this_side {
+                for &other in other_side {
+                    for cell_id in &cell_ids[other] {
+                        let block = Block::new(
+                            BlockTarget::Cell(cell_id.clone(), CellBlockReason::App(Vec::new())),
+                            interval.clone(),
+                        );
+                        self.nodes[node]
+                            .conductor
+                            .block(block.clone())
+                            .await
+                            .map_err(|err| anyhow!("Error blocking on node {node}: {err:?}"))?;
+                        self.blocks.push((node, other, block));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Remove every partition and bring every offline node back online.
+    pub async fn heal(&mut self) -> Result<()> {
+        for (node, _, block) in std::mem::take(&mut self.blocks) {
+            self.nodes[node]
+                .conductor
+                .unblock(block)
+                .await
+                .map_err(|err| anyhow!("Error unblocking on node {node}: {err:?}"))?;
+        }
+
+        for node in 0..self.nodes.len() {
+            if !self.nodes[node].online {
+                self.bring_online(node).await?;
+            }
+        }
+
+        Ok(self.exchange_peer_info().await?)
+    }
+
+    async fn cell_ids(&self, node: usize) -> Result<Vec<CellId>> {
+        let node = &self.nodes[node];
+        let app_info = node
+            .admin_ws
+            .list_apps(None)
+            .await?
+            .into_iter()
+            .find(|app_info| app_info.installed_app_id == node.installed_app_id)
+            .ok_or_else(|| anyhow!("App {} is not installed", node.installed_app_id))?;
+
+        Ok(app_info
+            .cell_info
+            .values()
+            .flatten()
+            .filter_map(|cell_info| match cell_info {
+                CellInfo::Provisioned(cell) => Some(cell.cell_id.clone()),
+                CellInfo::Cloned(cell) => Some(cell.cell_id.clone()),
+                _ => None,
+            })
+            .collect())
+    }
+}
+
+impl Default for TestNetwork {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{AdminWebsocket, ConductorApiError, ConductorApiResult};
+use anyhow::{anyhow, Result};
+use holochain_conductor_api::AppInfo;
+use holochain_types::{
+    app::{InstallAppPayload, InstalledAppId},
+    websocket::AllowedOrigins,
+};
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Isolates the resources a test creates on a shared conductor.
+///
+/// Tests that run in parallel against one conductor collide when they use fixed app ids or ports.
+/// A scope hands out app ids that are unique to the scope, lets the OS choose ports, and keeps track
+/// of everything it installed so it can be removed again when the test is done.
+///
+/// Call [TestScope::cleanup] at the end of a test. A scope that is dropped without being cleaned
+/// up leaves its apps installed and prints a warning.
+///
+/// App interfaces cannot be detached through the admin API, so the interfaces a scope attaches are
+/// bound to one of its apps. Once cleanup has uninstalled that app, the interface stays open but
+/// accepts no connections.
+pub struct TestScope {
+    admin_ws: Option<AdminWebsocket>,
+    prefix: String,
+    counter: AtomicUsize,
+    installed_app_ids: Mutex<Vec<InstalledAppId>>,
+    app_interface_ports: Mutex<Vec<u16>>,
+}
+
+impl TestScope {
+    /// Create a scope which manages resources through the given admin connection.
+    pub fn new(admin_ws: AdminWebsocket) -> Self {
+        Self {
+            admin_ws: Some(admin_ws),
+            prefix: format!("test-{:08x}", rand::random::<u32>()),
+            counter: AtomicUsize::new(0),
+            installed_app_ids: Mutex::new(Vec::new()),
+            app_interface_ports: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn admin_ws(&self) -> &AdminWebsocket {
+        self.admin_ws
+            .as_ref()
+            .expect("Admin websocket is only taken on cleanup")
+    }
+
+    /// Generate an app id that is unique to this scope, such as `test-1a2b3c4d-app-0`.
+    pub fn app_id(&self, name: &str) -> InstalledAppId {
+        let n = self.counter.fetch_add(1, Ordering::Relaxed);
+        format!("{}-{name}-{n}", self.prefix)
+    }
+
+    /// Install an app and record it for cleanup.
+    ///
+    /// If the payload does not set an installed app id, a unique one is generated.
+    pub async fn install_app(&self, mut payload: InstallAppPayload) -> ConductorApiResult<AppInfo> {
+        let installed_app_id = payload
+            .installed_app_id
+            .get_or_insert_with(|| self.app_id("app"))
+            .clone();
+        let app_info = self.admin_ws().install_app(payload).await?;
+        self.installed_app_ids.lock().push(installed_app_id);
+
+        Ok(app_info)
+    }
+
+    /// Attach an app interface for an app installed through this scope, on a port chosen by the OS.
+    ///
+    /// Returns the port the conductor bound the interface to.
+    pub async fn attach_app_interface(
+        &self,
+        allowed_origins: AllowedOrigins,
+        installed_app_id: InstalledAppId,
+    ) -> Result<u16> {
+        if !self.installed_app_ids.lock().contains(&installed_app_id) {
+            return Err(anyhow!(
+                "App {installed_app_id} was not installed through this scope"
+            ));
+        }
+        let port = self
+            .admin_ws()
+            .attach_app_interface(0, allowed_origins, Some(installed_app_id))
+            .await?;
+        self.app_interface_ports.lock().push(port);
+
+        Ok(port)
+    }
+
+    pub fn installed_app_ids(&self) -> Vec<InstalledAppId> {
+        self.installed_app_ids.lock().clone()
+    }
+
+    pub fn app_interface_ports(&self) -> Vec<u16> {
+        self.app_interface_ports.lock().clone()
+    }
+
+    /// Uninstall every app installed through this scope, which also closes the interfaces attached
+    /// through it to new connections.
+    ///
+    /// Every app is attempted, and the errors for the apps which could not be uninstalled are
+    /// returned together.
+    pub async fn cleanup(mut self) -> Result<()> {
+        let admin_ws = self.admin_ws.take().expect("Cleanup is only run once");
+        let installed_app_ids = std::mem::take(&mut *self.installed_app_ids.lock());
+        self.app_interface_ports.lock().clear();
+
+        let errors = uninstall_all(&admin_ws, installed_app_ids).await;
+        if errors.is_empty() {
+            return Ok(());
+        }
+
+        let errors = errors
+            .into_iter()
+            .map(|(installed_app_id, err)| format!("{installed_app_id}: {err:?}"))
+            .collect::<Vec<_>>();
+        Err(anyhow!("Error uninstalling apps: {}", errors.join(", ")))
+    }
+}
+
+impl Drop for TestScope {
+    fn drop(&mut self) {
+        if self.admin_ws.is_none() {
+            return;
+        }
+        let installed_app_ids = self.installed_app_ids.lock();
+        if !installed_app_ids.is_empty() {
+            eprintln!(
+                "TestScope {} was dropped without cleanup, leaving apps installed: {}",
+                self.prefix,
+                installed_app_ids.join(", ")
+            );
+        }
+    }
+}
+
+async fn uninstall_all(
+    admin_ws: &AdminWebsocket,
+    installed_app_ids: Vec<InstalledAppId>,
+) -> Vec<(InstalledAppId, ConductorApiError)> {
+    let mut errors = Vec::new();
+    for installed_app_id in installed_app_ids {
+        if let Err(err) = admin_ws
+            .uninstall_app(installed_app_id.clone(), false)
+            .await
+        {
+            errors.push((installed_app_id, err));
+        }
+    }
+
+    errors
+}
@@ -0,0 +1,174 @@
+use crate::{admin_websocket::is_enabled, AdminWebsocket};
+use anyhow::{anyhow, Context, Result};
+use holochain::{
+    conductor::config::{ConductorConfig, KeystoreConfig},
+    prelude::MetaLairClient,
+    sweettest::{SweetConductor, SweetLocalRendezvous},
+};
+use holochain_types::{app::InstalledAppId, dna::AgentPubKey};
+use std::{
+    ops::{Deref, DerefMut},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tempfile::TempDir;
+
+/// A snapshot of the state of a conductor.
+///
+/// Installing and enabling the same fixture app dominates the run time of most integration
+/// tests. A snapshot is captured once from a provisioned conductor and each test then starts a
+/// conductor from a copy of it, so that nothing is installed, enabled or run through genesis again.
+///
+/// The snapshot is a copy of the conductor's data directory and, for an in-process Lair keystore
+/// kept outside of it, of the keystore directory. The in-memory test keystore of a default
+/// [SweetConductor] cannot be copied, so conductors restored from a snapshot of one share the
+/// keystore of the original conductor.
+#[derive(Clone)]
+pub struct ConductorSnapshot {
+    config: ConductorConfig,
+    data: Arc<TempDir>,
+    lair_root: Option<PathBuf>,
+    keystore: Option<MetaLairClient>,
+    apps: Vec<AppSnapshot>,
+}
+
+/// An app installed on the conductor when a [ConductorSnapshot] was captured.
+#[derive(Clone, Debug)]
+pub struct AppSnapshot {
+    pub installed_app_id: InstalledAppId,
+    pub agent_key: AgentPubKey,
+    pub enabled: bool,
+}
+
+impl ConductorSnapshot {
+    /// Capture the state of `conductor`.
+    ///
+    /// The conductor is shut down while its directories are copied and started again afterwards,
+    /// which moves its admin interfaces to new ports. Connect to it again after capturing.
+    pub async fn capture(conductor: &mut SweetConductor) -> Result<Self> {
+        let apps = AdminWebsocket::from_sweet_conductor(conductor)
+            .await?
+            .list_apps(None)
+            .await
+            .map_err(|err| anyhow!("Error listing apps: {err:?}"))?
+            .into_iter()
+            .map(|app_info| AppSnapshot {
+                installed_app_id: app_info.installed_app_id,
+                agent_key: app_info.agent_pub_key,
+                enabled: is_enabled(&app_info.status),
+            })
+            .collect();
+
+        let config = conductor.get_config().clone();
+        let data_root: &Path = config
+            .data_root_path
+            .as_ref()
+            .ok_or_else(|| anyhow!("The conductor has no data directory"))?
+            .as_ref();
+        let (keystore, lair_root) = match &config.keystore {
+            KeystoreConfig::DangerTestKeystore => (Some(conductor.keystore()), None),
+            KeystoreConfig::LairServerInProc { lair_root } => (
+                None,
+                lair_root
+                    .as_ref()
+                    .map(|path| AsRef::<Path>::as_ref(path).to_path_buf()),
+            ),
+            KeystoreConfig::LairServer { .. } => {
+                return Err(anyhow!(
+                    "The keystore of an external Lair server cannot be copied"
+                ))
+            }
+        };
+
+        let data = TempDir::new()?;
+        conductor.shutdown().await;
+        let copied =
+            copy_dir(data_root, &data.path().join("data")).and_then(|()| match &lair_root {
+                Some(lair_root) => copy_dir(lair_root, &data.path().join("lair")),
+                None => Ok(()),
+            });
+        conductor.startup().await;
+        copied.context("Error copying the conductor's directories")?;
+
+        Ok(Self {
+            config,
+            data: Arc::new(data),
+            lair_root,
+            keystore,
+            apps,
+        })
+    }
+
+    pub fn apps(&self) -> &[AppSnapshot] {
+        &self.apps
+    }
+
+    /// Start a new conductor from a copy of this snapshot.
+    ///
+    /// Every restored conductor gets its own copy, so conductors restored from the same snapshot
+    /// do not affect each other or the snapshot.
+    pub async fn restore(&self) -> Result<RestoredConductor> {
+        let dir = TempDir::new()?;
+        let data_root = dir.path().join("data");
+        copy_dir(&self.data.path().join("data"), &data_root)
+            .context("Error copying the snapshot's data directory")?;
+
+        let mut config = self.config.clone();
+        config.data_root_path = Some(data_root.into());
+        if self.lair_root.is_some() {
+            let lair_root = dir.path().join("lair");
+            copy_dir(&self.data.path().join("lair"), &lair_root)
+                .context("Error copying the snapshot's keystore directory")?;
+            config.keystore = KeystoreConfig::LairServerInProc {
+                lair_root: Some(lair_root.into()),
+            };
+        }
+
+        let conductor = SweetConductor::create_with_defaults(
+            config,
+            self.keystore.clone(),
+            Some(SweetLocalRendezvous::new().await),
+        )
+        .await;
+
+        Ok(RestoredConductor {
+            conductor,
+            _dir: dir,
+        })
+    }
+}
+
+/// A conductor started from a [ConductorSnapshot], which removes its copy of the snapshot when it
+/// is dropped.
+pub struct RestoredConductor {
+    conductor: SweetConductor,
+    _dir: TempDir,
+}
+
+impl Deref for RestoredConductor {
+    type Target = SweetConductor;
+
+    fn deref(&self) -> &Self::Target {
+        &self.conductor
+    }
+}
+
+impl DerefMut for RestoredConductor {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.conductor
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use crate::{AdminWebsocket, AgentSigner, AppWebsocket};
+use anyhow::{anyhow, Result};
+use holochain::sweettest::SweetConductor;
+use holochain_types::{app::InstalledAppId, websocket::AllowedOrigins};
+use std::{net::Ipv4Addr, sync::Arc, time::Duration};
+
+const APP_INTERFACE_READY_TIMEOUT: Duration = Duration::from_secs(10);
+
+impl AdminWebsocket {
+    /// Connect to the admin interface of a [SweetConductor].
+    pub async fn from_sweet_conductor(conductor: &SweetConductor) -> Result<Self> {
+        let admin_port = conductor
+            .get_arbitrary_admin_websocket_port()
+            .ok_or_else(|| anyhow!("The conductor has no admin interface"))?;
+        AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port)).await
+    }
+}
+
+impl AppWebsocket {
+    /// Connect to an app installed on a [SweetConductor].
+    ///
+    /// This attaches a new app interface bound to the app, which accepts connections from any
+    /// origin, and authenticates with a freshly issued token. The app must be enabled.
+    pub async fn from_sweet_conductor(
+        conductor: &SweetConductor,
+        installed_app_id: InstalledAppId,
+        signer: Arc<dyn AgentSigner + Send + Sync>,
+    ) -> Result<Self> {
+        AdminWebsocket::from_sweet_conductor(conductor)
+            .await?
+            .attach_and_connect(
+                Ipv4Addr::LOCALHOST,
+                AllowedOrigins::Any,
+                installed_app_id,
+                signer,
+                APP_INTERFACE_READY_TIMEOUT,
+            )
+            .await
+    }
+}
@@ -0,0 +1,317 @@
+use anyhow::{Context, Result};
+use rand::{rngs::OsRng, RngCore};
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    time::Instant,
+};
+use tokio_rustls::{
+    rustls::{pki_types::ServerName, ClientConfig, RootCertStore},
+    TlsConnector,
+};
+
+/// How long a tunnel waits for the websocket to connect to it and send its handshake.
+const TUNNEL_ACCEPT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The header with which the websocket proves to the tunnel that it is the connection the tunnel
+/// was opened for. The tunnel removes it before the handshake is forwarded.
+pub(crate) const TUNNEL_TOKEN_HEADER: &str = "X-Holochain-Client-Tunnel";
+
+/// The maximum size of the handshake request of the websocket.
+const MAX_HANDSHAKE_SIZE: usize = 16 * 1024;
+
+/// How to connect to a conductor behind a TLS-terminating reverse proxy, see
+/// [ConnectOptions::with_tls](crate::ConnectOptions::with_tls).
+///
+/// The websocket is connected through a tunnel which terminates TLS on the client. The tunnel
+/// listens on a random port on the loopback interface for a single connection, which must
+/// present a one-time token generated for it. Any other connection closes the tunnel. The
+/// handshake of the websocket is forwarded with the server name as host and with [path](Self::path)
+/// as the request path, as if the websocket had connected to the proxy directly.
+#[derive(Clone, Debug)]
+pub struct TlsOptions {
+    /// The name the certificate of the server must be valid for, which is also sent with SNI and
+    /// as the host of the websocket handshake.
+    pub server_name: String,
+    pub client_config: Arc<ClientConfig>,
+    /// The path the proxy serves the websocket at, `/` by default.
+    pub path: String,
+}
+
+impl TlsOptions {
+    /// Verify the certificate of the server against the Mozilla root certificates.
+    pub fn new(server_name: impl Into<String>) -> Self {
+        let mut roots = RootCertStore::empty();
+        roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        let client_config = ClientConfig::builder()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+
+        Self {
+            server_name: server_name.into(),
+            client_config: Arc::new(client_config),
+            path: "/".to_string(),
+        }
+    }
+
+    /// Use a custom TLS client configuration, for example to trust a private certificate
+    /// authority.
+    pub fn with_client_config(mut self, client_config: Arc<ClientConfig>) -> Self {
+        self.client_config = client_config;
+        self
+    }
+
+    /// Connect to the websocket at `path` on the proxy, such as `/conductor/admin`.
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into();
+        self
+    }
+}
+
+/// A tunnel opened by [open_tunnel].
+pub(crate) struct Tunnel {
+    /// The local address to connect the websocket to.
+    pub(crate) addr: SocketAddr,
+    /// The value of the [TUNNEL_TOKEN_HEADER] the websocket must send.
+    pub(crate) token: String,
+}
+
+/// Open a TLS connection to `addr` and a tunnel to it for a single websocket connection.
+pub(crate) async fn open_tunnel(addr: SocketAddr, tls: &TlsOptions) -> Result<Tunnel> {
+    let server_name = ServerName::try_from(tls.server_name.clone())
+        .with_context(|| format!("Invalid TLS server name {}", tls.server_name))?;
+    let tcp = TcpStream::connect(addr).await?;
+    let mut stream = TlsConnector::from(tls.client_config.clone())
+        .connect(server_name, tcp)
+        .await
+        .with_context(|| format!("TLS handshake with {addr} failed"))?;
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+    let local_addr = listener.local_addr()?;
+    let mut token = [0; 16];
+    OsRng.fill_bytes(&mut token);
+    let token = token
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+
+    let host = host_header(&tls.server_name, addr.port());
+    let path = tls.path.clone();
+    let expected_token = token.clone();
+    tokio::spawn(async move {
+        let deadline = Instant::now() + TUNNEL_ACCEPT_TIMEOUT;
+        let Ok(Ok((mut local, _))) = tokio::time::timeout_at(deadline, listener.accept()).await
+        else {
+            return;
+        };
+        // The tunnel serves the one websocket connection it was opened for, and closes with it.
+        // Whichever connection comes first must be that one, or the tunnel closes.
+        drop(listener);
+        let Ok(Ok(Some((handshake, rest)))) =
+            tokio::time::timeout_at(deadline, read_handshake(&mut local)).await
+        else {
+            return;
+        };
+        let Some(handshake) = rewrite_handshake(&handshake, &expected_token, &host, &path) else {
+            return;
+        };
+        if stream.write_all(&handshake).await.is_err() || stream.write_all(&rest).await.is_err() {
+            return;
+        }
+        let _ = tokio::io::copy_bidirectional(&mut local, &mut stream).await;
+    });
+
+    Ok(Tunnel {
+        addr: local_addr,
+        token,
+    })
+}
+
+/// Read the head of the HTTP request which opens the websocket, returning the head without the
+/// blank line that ends it and any bytes received after it. Returns `None` if the head is too
+/// large or the connection closes before the end of the head.
+async fn read_handshake<R: AsyncRead + Unpin>(
+    local: &mut R,
+) -> std::io::Result<Option<(Vec<u8>, Vec<u8>)>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0; 1024];
+    loop {
+        if let Some(end) = buf.windows(4).position(|window| window == b"\r\n\r\n") {
+            let rest = buf.split_off(end + 4);
+            buf.truncate(end);
+            return Ok(Some((buf, rest)));
+        }
+        if buf.len() > MAX_HANDSHAKE_SIZE {
+            return Ok(None);
+        }
+        let read = local.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(None);
+        }
+        buf.extend_from_slice(&chunk[..read]);
+    }
+}
+
+/// Replace the host and path of the handshake with those of the proxy and remove the tunnel
+/// token. Returns `None` if the handshake is malformed or doesn't carry the token.
+fn rewrite_handshake(handshake: &[u8], token: &str, host: &str, path: &str) -> Option<Vec<u8>> {
+    let handshake = std::str::from_utf8(handshake).ok()?;
+    let mut lines = handshake.split("\r\n");
+    let (method, target) = lines.next()?.split_once(' ')?;
+    let (_, version) = target.rsplit_once(' ')?;
+
+    let mut rewritten = format!("{method} {path} {version}\r\nHost: {host}\r\n");
+    let mut authorized = false;
+    for line in lines {
+        let (name, value) = line.split_once(':')?;
+        if name.trim().eq_ignore_ascii_case(TUNNEL_TOKEN_HEADER) {
+            authorized = value.trim() == token;
+        } else if !name.trim().eq_ignore_ascii_case("host") {
+            rewritten.push_str(line);
+            rewritten.push_str("\r\n");
+        }
+    }
+    rewritten.push_str("\r\n");
+
+    authorized.then(|| rewritten.into_bytes())
+}
+
+/// The host header for a server, which includes the port unless it is the default port of TLS.
+fn host_header(server_name: &str, port: u16) -> String {
+    let host = if server_name.contains(':') {
+        format!("[{server_name}]")
+    } else {
+        server_name.to_string()
+    };
+    if port == 443 {
+        host
+    } else {
+        format!("{host}:{port}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN: &str = "0123456789abcdef";
+
+    fn handshake(token_header: Option<&str>) -> String {
+        let mut handshake = "GET / HTTP/1.1\r\nHost: 127.0.0.1:40000\r\nUpgrade: websocket\r\n\
+                             Connection: Upgrade\r\nSec-WebSocket-Version: 13\r\n"
+            .to_string();
+        if let Some(token_header) = token_header {
+            handshake.push_str(token_header);
+            handshake.push_str("\r\n");
+        }
+        handshake
+    }
+
+    fn rewrite(handshake: &str, host: &str) -> Option<String> {
+        rewrite_handshake(
+            handshake.trim_end().as_bytes(),
+            TOKEN,
+            host,
+            "/conductor/admin",
+        )
+        .map(|rewritten| String::from_utf8(rewritten).unwrap())
+    }
+
+    #[test]
+    fn rewrites_host_and_path_and_removes_token() {
+        let rewritten = rewrite(
+            &handshake(Some(&format!("{TUNNEL_TOKEN_HEADER}: {TOKEN}"))),
+            "example.org",
+        );
+
+        assert_eq!(
+            rewritten.as_deref(),
+            Some(
+                "GET /conductor/admin HTTP/1.1\r\nHost: example.org\r\nUpgrade: websocket\r\n\
+                 Connection: Upgrade\r\nSec-WebSocket-Version: 13\r\n\r\n"
+            )
+        );
+    }
+
+    #[test]
+    fn token_header_name_is_case_insensitive() {
+        let rewritten = rewrite(
+            &handshake(Some(&format!("x-holochain-client-tunnel:{TOKEN}"))),
+            "example.org",
+        );
+
+        assert!(rewritten.is_some_and(|rewritten| !rewritten.contains(TOKEN)));
+    }
+
+    #[test]
+    fn handshake_without_token_is_rejected() {
+        assert_eq!(rewrite(&handshake(None), "example.org"), None);
+    }
+
+    #[test]
+    fn handshake_with_wrong_token_is_rejected() {
+        let wrong = format!("{TUNNEL_TOKEN_HEADER}: fedcba9876543210");
+        assert_eq!(rewrite(&handshake(Some(&wrong)), "example.org"), None);
+        let empty = format!("{TUNNEL_TOKEN_HEADER}:");
+        assert_eq!(rewrite(&handshake(Some(&empty)), "example.org"), None);
+    }
+
+    #[test]
+    fn malformed_handshake_is_rejected() {
+        let token = format!("{TUNNEL_TOKEN_HEADER}: {TOKEN}");
+        assert_eq!(rewrite(&format!("GET\r\n{token}"), "example.org"), None);
+        assert_eq!(
+            rewrite(
+                &format!("GET / HTTP/1.1\r\nno header\r\n{token}"),
+                "example.org"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn host_header_of_ipv6_address_is_bracketed() {
+        assert_eq!(host_header("::1", 443), "[::1]");
+        assert_eq!(host_header("2001:db8::1", 8443), "[2001:db8::1]:8443");
+
+        let rewritten = rewrite(
+            &handshake(Some(&format!("{TUNNEL_TOKEN_HEADER}: {TOKEN}"))),
+            &host_header("::1", 8443),
+        );
+        assert!(rewritten.is_some_and(|rewritten| rewritten.contains("\r\nHost: [::1]:8443\r\n")));
+    }
+
+    #[test]
+    fn host_header_includes_port_other_than_443() {
+        assert_eq!(host_header("example.org", 443), "example.org");
+        assert_eq!(host_header("example.org", 8443), "example.org:8443");
+        assert_eq!(host_header("127.0.0.1", 80), "127.0.0.1:80");
+    }
+
+    #[tokio::test]
+    async fn read_handshake_returns_bytes_after_head() {
+        let mut received: &[u8] = b"GET / HTTP/1.1\r\nHost: a\r\n\r\nframe";
+
+        let (head, rest) = read_handshake(&mut received).await.unwrap().unwrap();
+
+        assert_eq!(head, b"GET / HTTP/1.1\r\nHost: a");
+        assert_eq!(rest, b"frame");
+    }
+
+    #[tokio::test]
+    async fn read_handshake_rejects_incomplete_or_oversized_head() {
+        let mut incomplete: &[u8] = b"GET / HTTP/1.1\r\nHost: a\r\n";
+        assert!(read_handshake(&mut incomplete).await.unwrap().is_none());
+
+        let oversized = vec![b'a'; MAX_HANDSHAKE_SIZE + 2048];
+        assert!(read_handshake(&mut oversized.as_slice())
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,73 @@
+use crate::{AppWebsocket, SignalDecodeError};
+use anyhow::Result;
+use holochain_types::signal::Signal;
+use holochain_zome_types::prelude::{CellId, ZomeName};
+use serde::de::DeserializeOwned;
+
+/// An app signal with its payload decoded to the signal type of a zome.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypedSignal<T> {
+    pub cell_id: CellId,
+    pub zome_name: ZomeName,
+    pub payload: T,
+}
+
+/// Decoding of the payload of app signals.
+pub trait SignalExt {
+    /// Decode the payload of an app signal.
+    fn into_app_payload<T: DeserializeOwned>(self) -> Result<T, SignalDecodeError>;
+
+    /// Decode the payload of an app signal, keeping the cell and zome that emitted it.
+    fn into_typed<T: DeserializeOwned>(self) -> Result<TypedSignal<T>, SignalDecodeError>;
+}
+
+impl SignalExt for Signal {
+    fn into_app_payload<T: DeserializeOwned>(self) -> Result<T, SignalDecodeError> {
+        self.into_typed().map(|signal| signal.payload)
+    }
+
+    fn into_typed<T: DeserializeOwned>(self) -> Result<TypedSignal<T>, SignalDecodeError> {
+        match self {
+            Signal::App {
+                cell_id,
+                zome_name,
+                signal,
+            } => match signal.into_inner().decode() {
+                Ok(payload) => Ok(TypedSignal {
+                    cell_id,
+                    zome_name,
+                    payload,
+                }),
+                Err(error) => Err(SignalDecodeError::Decode {
+                    cell_id,
+                    zome_name,
+                    error,
+                }),
+            },
+            Signal::System(signal) => Err(SignalDecodeError::NotAppSignal(signal)),
+        }
+    }
+}
+
+impl AppWebsocket {
+    /// Register a handler for the app signals of this connection, decoded to `T`.
+    ///
+    /// Signals whose payload does not decode to `T` are passed to the handler as
+    /// [SignalDecodeError::Decode], which names the cell and zome that emitted them, so a handler
+    /// can tell signals of other zomes apart from malformed ones. System signals are not passed to
+    /// the handler. Failing to register the handler is reported by the returned result instead.
+    ///
+    /// Returns an id to remove the handler with [AppWebsocket::off_signal].
+    pub async fn on_signal_typed<T, F>(&self, handler: F) -> Result<String>
+    where
+        T: DeserializeOwned,
+        F: Fn(Result<TypedSignal<T>, SignalDecodeError>) + 'static + Sync + Send,
+    {
+        self.on_signal(move |signal| {
+            if let Signal::App { .. } = signal {
+                handler(signal.into_typed());
+            }
+        })
+        .await
+    }
+}
@@ -0,0 +1,357 @@
+use serde::{ser, Serialize};
+use std::fmt::Display;
+
+/// The request type of a request on the wire, such as `install_app` or `call_zome`.
+///
+/// The type is read from the tag of the request, without serializing the data it carries.
+pub(crate) fn operation_name<R: Serialize>(request: &R) -> String {
+    request
+        .serialize(TagSerializer)
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+/// The number of bytes of data a request or response carries which is known without encoding it,
+/// such as the input and output of a zome call.
+pub(crate) trait PayloadSize {
+    fn payload_size(&self) -> u64;
+}
+
+#[cfg(feature = "admin")]
+impl PayloadSize for holochain_conductor_api::AdminRequest {
+    fn payload_size(&self) -> u64 {
+        use holochain_conductor_api::AdminRequest;
+        use holochain_types::app::AppBundleSource;
+
+        match self {
+            AdminRequest::InstallApp(payload) => match &payload.source {
+                AppBundleSource::Path(path) => path.as_os_str().len() as u64,
+                _ => 0,
+            },
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(feature = "admin")]
+impl PayloadSize for holochain_conductor_api::AdminResponse {
+    fn payload_size(&self) -> u64 {
+        use holochain_conductor_api::AdminResponse;
+
+        match self {
+            AdminResponse::StateDumped(state) => state.len() as u64,
+            AdminResponse::ConductorStateDumped(state) => state.len() as u64,
+            AdminResponse::NetworkStatsDumped(stats) => stats.len() as u64,
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(feature = "app")]
+impl PayloadSize for holochain_conductor_api::AppRequest {
+    fn payload_size(&self) -> u64 {
+        use holochain_conductor_api::AppRequest;
+
+        match self {
+            AppRequest::CallZome(params) => params.bytes.0.len() as u64,
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(feature = "app")]
+impl PayloadSize for holochain_conductor_api::AppResponse {
+    fn payload_size(&self) -> u64 {
+        use holochain_conductor_api::AppResponse;
+
+        match self {
+            AppResponse::ZomeCalled(output) => output.0.len() as u64,
+            _ => 0,
+        }
+    }
+}
+
+/// Whether a request or response carries a secret, such as a capability secret or an app
+/// authentication token, so that it must not be kept in the debug history.
+pub(crate) trait CarriesSecret {
+    fn carries_secret(&self) -> bool;
+}
+
+#[cfg(feature = "admin")]
+impl CarriesSecret for holochain_conductor_api::AdminRequest {
+    fn carries_secret(&self) -> bool {
+        use holochain_conductor_api::AdminRequest;
+        use holochain_zome_types::capability::CapAccess;
+
+        match self {
+            AdminRequest::GrantZomeCallCapability(payload) => {
+                !matches!(payload.cap_grant.access, CapAccess::Unrestricted)
+            }
+            AdminRequest::RevokeAppAuthenticationToken(_) => true,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(feature = "admin")]
+impl CarriesSecret for holochain_conductor_api::AdminResponse {
+    fn carries_secret(&self) -> bool {
+        use holochain_conductor_api::AdminResponse;
+
+        // State dumps include the private entries of the source chain, such as capability grants.
+        matches!(
+            self,
+            AdminResponse::AppAuthenticationTokenIssued(_)
+                | AdminResponse::StateDumped(_)
+                | AdminResponse::FullStateDumped(_)
+        )
+    }
+}
+
+#[cfg(feature = "app")]
+impl CarriesSecret for holochain_conductor_api::AppRequest {
+    fn carries_secret(&self) -> bool {
+        use holochain_conductor_api::AppRequest;
+        use holochain_zome_types::zome_io::ZomeCallParams;
+
+        match self {
+            // The capability secret is part of the signed bytes of the call.
+            AppRequest::CallZome(params) => params
+                .bytes
+                .decode::<ZomeCallParams>()
+                .map_or(true, |params| params.cap_secret.is_some()),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(feature = "app")]
+impl CarriesSecret for holochain_conductor_api::AppResponse {
+    fn carries_secret(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug)]
+struct NotTagged;
+
+impl Display for NotTagged {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("The value is not a tagged request")
+    }
+}
+
+impl std::error::Error for NotTagged {}
+
+impl ser::Error for NotTagged {
+    fn custom<T: Display>(_msg: T) -> Self {
+        NotTagged
+    }
+}
+
+/// Implements the methods of [ser::Serializer] for the given kinds of values by failing.
+macro_rules! not_tagged {
+    ($($method:ident($($arg:ty),*) -> $ok:ty;)*) => {
+        $(fn $method(self, $(_: $arg),*) -> Result<$ok, NotTagged> {
+            Err(NotTagged)
+        })*
+    };
+}
+
+/// Serializes the `type` field of a tagged enum and skips all other fields.
+struct TagSerializer;
+
+/// Serializes the name of a unit variant or a string, which is the tag of a request.
+struct NameSerializer;
+
+struct TagFields {
+    tag: Option<String>,
+    next_is_tag: bool,
+}
+
+impl ser::SerializeStruct for TagFields {
+    type Ok = String;
+    type Error = NotTagged;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), NotTagged> {
+        if key == "type" {
+            self.tag = Some(value.serialize(NameSerializer)?);
+        }
+        Ok(())
+    }
+
+    fn end(self) -> Result<String, NotTagged> {
+        self.tag.ok_or(NotTagged)
+    }
+}
+
+impl ser::SerializeMap for TagFields {
+    type Ok = String;
+    type Error = NotTagged;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), NotTagged> {
+        self.next_is_tag = key.serialize(NameSerializer).is_ok_and(|key| key == "type");
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), NotTagged> {
+        if std::mem::take(&mut self.next_is_tag) {
+            self.tag = Some(value.serialize(NameSerializer)?);
+        }
+        Ok(())
+    }
+
+    fn end(self) -> Result<String, NotTagged> {
+        self.tag.ok_or(NotTagged)
+    }
+}
+
+impl ser::Serializer for TagSerializer {
+    type Ok = String;
+    type Error = NotTagged;
+    type SerializeSeq = ser::Impossible<String, NotTagged>;
+    type SerializeTuple = ser::Impossible<String, NotTagged>;
+    type SerializeTupleStruct = ser::Impossible<String, NotTagged>;
+    type SerializeTupleVariant = ser::Impossible<String, NotTagged>;
+    type SerializeMap = TagFields;
+    type SerializeStruct = TagFields;
+    type SerializeStructVariant = ser::Impossible<String, NotTagged>;
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<TagFields, NotTagged> {
+        Ok(TagFields {
+            tag: None,
+            next_is_tag: false,
+        })
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<TagFields, NotTagged> {
+        Ok(TagFields {
+            tag: None,
+            next_is_tag: false,
+        })
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<String, NotTagged> {
+        value.serialize(self)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<String, NotTagged> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<String, NotTagged> {
+        Err(NotTagged)
+    }
+
+    not_tagged! {
+        serialize_bool(bool) -> String;
+        serialize_i8(i8) -> String;
+        serialize_i16(i16) -> String;
+        serialize_i32(i32) -> String;
+        serialize_i64(i64) -> String;
+        serialize_u8(u8) -> String;
+        serialize_u16(u16) -> String;
+        serialize_u32(u32) -> String;
+        serialize_u64(u64) -> String;
+        serialize_f32(f32) -> String;
+        serialize_f64(f64) -> String;
+        serialize_char(char) -> String;
+        serialize_str(&str) -> String;
+        serialize_bytes(&[u8]) -> String;
+        serialize_none() -> String;
+        serialize_unit() -> String;
+        serialize_unit_struct(&'static str) -> String;
+        serialize_unit_variant(&'static str, u32, &'static str) -> String;
+        serialize_seq(Option<usize>) -> Self::SerializeSeq;
+        serialize_tuple(usize) -> Self::SerializeTuple;
+        serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+        serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+        serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+    }
+}
+
+impl ser::Serializer for NameSerializer {
+    type Ok = String;
+    type Error = NotTagged;
+    type SerializeSeq = ser::Impossible<String, NotTagged>;
+    type SerializeTuple = ser::Impossible<String, NotTagged>;
+    type SerializeTupleStruct = ser::Impossible<String, NotTagged>;
+    type SerializeTupleVariant = ser::Impossible<String, NotTagged>;
+    type SerializeMap = ser::Impossible<String, NotTagged>;
+    type SerializeStruct = ser::Impossible<String, NotTagged>;
+    type SerializeStructVariant = ser::Impossible<String, NotTagged>;
+
+    fn serialize_str(self, name: &str) -> Result<String, NotTagged> {
+        Ok(name.to_string())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        name: &'static str,
+    ) -> Result<String, NotTagged> {
+        Ok(name.to_string())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        _: &T,
+    ) -> Result<String, NotTagged> {
+        Err(NotTagged)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _: &T) -> Result<String, NotTagged> {
+        Err(NotTagged)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<String, NotTagged> {
+        Err(NotTagged)
+    }
+
+    not_tagged! {
+        serialize_bool(bool) -> String;
+        serialize_i8(i8) -> String;
+        serialize_i16(i16) -> String;
+        serialize_i32(i32) -> String;
+        serialize_i64(i64) -> String;
+        serialize_u8(u8) -> String;
+        serialize_u16(u16) -> String;
+        serialize_u32(u32) -> String;
+        serialize_u64(u64) -> String;
+        serialize_f32(f32) -> String;
+        serialize_f64(f64) -> String;
+        serialize_char(char) -> String;
+        serialize_bytes(&[u8]) -> String;
+        serialize_none() -> String;
+        serialize_unit() -> String;
+        serialize_unit_struct(&'static str) -> String;
+        serialize_seq(Option<usize>) -> Self::SerializeSeq;
+        serialize_tuple(usize) -> Self::SerializeTuple;
+        serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+        serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+        serialize_map(Option<usize>) -> Self::SerializeMap;
+        serialize_struct(&'static str, usize) -> Self::SerializeStruct;
+        serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+    }
+}
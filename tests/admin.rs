@@ -1,17 +1,32 @@
-use holochain::prelude::{DnaModifiersOpt, RoleSettings, Timestamp, YamlProperties};
+use futures::{Stream, StreamExt, TryStreamExt};
+use holochain::prelude::{
+    CellId, CreateCloneCellPayload, DnaBundle, DnaModifiersOpt, DnaSource, RegisterDnaPayload,
+    RoleSettings, Timestamp, YamlProperties,
+};
 use holochain::test_utils::itertools::Itertools;
 use holochain::{prelude::AppBundleSource, sweettest::SweetConductor};
 use holochain_client::{
-    AdminWebsocket, AppWebsocket, AuthorizeSigningCredentialsPayload, ClientAgentSigner,
-    InstallAppPayload, InstalledAppId,
+    compute_dna_hash_from_file,
+    service::{HealthState, SdNotify, ServiceMonitor, ServiceStatus},
+    verify_dna_hash, AdminRole, AdminWebsocket, AppBundleBuilder, AppChange, AppStatusFilter,
+    AppWebsocket, AuthorizeSigningCredentialsPayload, ClientAgentSigner, ConductorApiError,
+    ConductorApiResult, ConnectOptions, ConnectRetry, EnableAppProgress, InstallAppPayload,
+    InstallAppPayloadError, InstalledAppId, ProbeZomeFn, ReadinessCheck, RevertPolicy,
+    ZOME_CALL_SIGNING_KEY_TAG,
+};
+use holochain_conductor_api::{
+    AppInfoStatus, CellInfo, IssueAppAuthenticationTokenPayload, StorageBlob,
 };
-use holochain_conductor_api::{CellInfo, StorageBlob};
 use holochain_types::websocket::AllowedOrigins;
 use holochain_zome_types::prelude::ExternIO;
 use kitsune_p2p_types::fixt::AgentInfoSignedFixturator;
-use std::collections::BTreeSet;
-use std::net::Ipv4Addr;
-use std::{collections::HashMap, path::PathBuf};
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::{Ipv4Addr, TcpListener};
+use std::time::Duration;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 const ROLE_NAME: &str = "foo";
 
@@ -28,6 +43,17 @@ async fn app_interfaces() {
     let app_interfaces = admin_ws.list_app_interfaces().await.unwrap();
 
     assert_eq!(app_interfaces.len(), 0);
+
+    let allowed_origins: AllowedOrigins = "my_cli_app".to_string().into();
+    let app_port = admin_ws
+        .attach_app_interface(0, allowed_origins.clone(), None)
+        .await
+        .unwrap();
+    let app_interfaces = admin_ws.list_app_interfaces().await.unwrap();
+    assert_eq!(app_interfaces.len(), 1);
+    assert_eq!(app_interfaces[0].port, app_port);
+    assert_eq!(app_interfaces[0].allowed_origins, allowed_origins);
+    assert_eq!(app_interfaces[0].installed_app_id, None);
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -144,6 +170,46 @@ async fn storage_info() {
     assert_eq!(1, matched_storage_info.len());
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn storage_info_per_app() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    // Both apps use the same DNA.
+    let app_ids: Vec<InstalledAppId> = vec!["app-1".into(), "app-2".into()];
+    for app_id in &app_ids {
+        admin_ws
+            .install_app(InstallAppPayload {
+                agent_key: None,
+                installed_app_id: Some(app_id.clone()),
+                network_seed: None,
+                roles_settings: None,
+                source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+                ignore_genesis_failure: false,
+                allow_throwaway_random_agent_key: false,
+            })
+            .await
+            .unwrap();
+        admin_ws.enable_app(app_id.clone()).await.unwrap();
+    }
+
+    let apps = admin_ws.storage_info_per_app().await.unwrap();
+    assert_eq!(
+        apps.iter()
+            .map(|app| app.installed_app_id.clone())
+            .collect::<BTreeSet<_>>(),
+        app_ids.into_iter().collect::<BTreeSet<_>>()
+    );
+    for app in &apps {
+        assert_eq!(app.dna_hashes.len(), 1);
+        assert!(app.shares_dnas);
+        assert!(app.total_size_on_disk() > 0);
+    }
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn dump_network_stats() {
     let conductor = SweetConductor::from_standard_config().await;
@@ -170,6 +236,45 @@ async fn dump_network_stats() {
     let network_stats = admin_ws.dump_network_stats().await.unwrap();
 
     assert!(network_stats.contains("\"backend\": \"backendMem\""));
+
+    let network_stats = admin_ws.network_stats().await.unwrap();
+    assert_eq!(network_stats.backend, "backendMem");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dump_network_metrics() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+    let app_id: InstalledAppId = "test-app".into();
+    let app_info = admin_ws
+        .install_app(InstallAppPayload {
+            agent_key: None,
+            installed_app_id: Some(app_id.clone()),
+            network_seed: None,
+            roles_settings: None,
+            source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+            ignore_genesis_failure: false,
+            allow_throwaway_random_agent_key: false,
+        })
+        .await
+        .unwrap();
+    admin_ws.enable_app(app_id.clone()).await.unwrap();
+    let dna_hash = match &app_info.cell_info.get(ROLE_NAME).unwrap()[0] {
+        CellInfo::Provisioned(cell) => cell.cell_id.dna_hash().clone(),
+        _ => panic!("expected provisioned cell"),
+    };
+
+    let all_metrics = admin_ws.dump_network_metrics(None).await.unwrap();
+    assert!(all_metrics.contains(&dna_hash.to_string()));
+
+    let dna_metrics = admin_ws
+        .dump_network_metrics(Some(dna_hash.clone()))
+        .await
+        .unwrap();
+    assert!(dna_metrics.contains(&dna_hash.to_string()));
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -297,6 +402,109 @@ async fn agent_info() {
     assert!(agent_infos.contains(&other_agent));
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn dump_state() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect(format!("127.0.0.1:{}", admin_port))
+        .await
+        .unwrap();
+    let app_id: InstalledAppId = "test-app".into();
+    let app_info = admin_ws
+        .install_app(InstallAppPayload {
+            agent_key: None,
+            installed_app_id: Some(app_id.clone()),
+            roles_settings: None,
+            network_seed: None,
+            source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+            ignore_genesis_failure: false,
+            allow_throwaway_random_agent_key: false,
+        })
+        .await
+        .unwrap();
+    admin_ws.enable_app(app_id).await.unwrap();
+    let cell_id =
+        if let CellInfo::Provisioned(cell) = &app_info.cell_info.get(ROLE_NAME).unwrap()[0] {
+            cell.cell_id.clone()
+        } else {
+            panic!("expected provisioned cell");
+        };
+
+    let state = admin_ws.dump_state(cell_id).await.unwrap();
+    assert!(state.contains("source_chain_dump"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dump_conductor_state() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect(format!("127.0.0.1:{}", admin_port))
+        .await
+        .unwrap();
+    let app_id: InstalledAppId = "test-app".into();
+    admin_ws
+        .install_app(InstallAppPayload {
+            agent_key: None,
+            installed_app_id: Some(app_id.clone()),
+            roles_settings: None,
+            network_seed: None,
+            source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+            ignore_genesis_failure: false,
+            allow_throwaway_random_agent_key: false,
+        })
+        .await
+        .unwrap();
+
+    let state = admin_ws.dump_conductor_state().await.unwrap();
+    assert!(state.contains(&app_id));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dump_full_state_with_cursor() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect(format!("127.0.0.1:{}", admin_port))
+        .await
+        .unwrap();
+    let app_id: InstalledAppId = "test-app".into();
+    let app_info = admin_ws
+        .install_app(InstallAppPayload {
+            agent_key: None,
+            installed_app_id: Some(app_id.clone()),
+            roles_settings: None,
+            network_seed: None,
+            source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+            ignore_genesis_failure: false,
+            allow_throwaway_random_agent_key: false,
+        })
+        .await
+        .unwrap();
+    admin_ws.enable_app(app_id).await.unwrap();
+    let cell_id =
+        if let CellInfo::Provisioned(cell) = &app_info.cell_info.get(ROLE_NAME).unwrap()[0] {
+            cell.cell_id.clone()
+        } else {
+            panic!("expected provisioned cell");
+        };
+
+    let full_state = admin_ws
+        .dump_full_state(cell_id.clone(), None)
+        .await
+        .unwrap();
+    assert!(!full_state.source_chain_dump.records.is_empty());
+
+    // Nothing has been integrated since the first dump.
+    let cursor = full_state.integration_dump.dht_ops_cursor;
+    let next_state = admin_ws
+        .dump_full_state(cell_id, Some(cursor))
+        .await
+        .unwrap();
+    assert!(
+        next_state.integration_dump.integrated.len()
+            <= full_state.integration_dump.integrated.len()
+    );
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn list_cell_ids() {
     let conductor = SweetConductor::from_standard_config().await;
@@ -404,3 +612,1205 @@ async fn install_app_with_roles_settings() {
     );
     assert_eq!(app_role.dna.modifiers.properties, Some(custom_properties));
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn connect_without_retry() {
+    // Find a port that nothing is listening on.
+    let port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+
+    let Err(err) = AdminWebsocket::connect_with_options(
+        (Ipv4Addr::LOCALHOST, port),
+        ConnectOptions::default().with_retry(ConnectRetry::Disabled),
+    )
+    .await
+    else {
+        panic!("connecting to a closed port should fail");
+    };
+
+    let message = err.to_string();
+    assert!(message.contains(&format!("127.0.0.1:{port}")));
+    assert!(message.contains("1 attempt(s)"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn connect_timeout() {
+    // Find a port that nothing is listening on.
+    let port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+
+    // The default retry policy waits a second before retrying, so the timeout elapses first.
+    let Err(err) = AdminWebsocket::connect_with_options(
+        (Ipv4Addr::LOCALHOST, port),
+        ConnectOptions::default().with_connect_timeout(Duration::from_millis(200)),
+    )
+    .await
+    else {
+        panic!("connecting to a closed port should fail");
+    };
+
+    assert!(matches!(
+        err.downcast_ref::<ConductorApiError>(),
+        Some(ConductorApiError::ConnectTimedOut { .. })
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn concurrent_requests_from_shared_references() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    // Clones share the connection.
+    let tasks = (0..10)
+        .map(|i| {
+            let admin_ws = admin_ws.clone();
+            tokio::spawn(async move {
+                if i % 2 == 0 {
+                    admin_ws.list_apps(None).await.map(|_| ())
+                } else {
+                    admin_ws.list_dnas().await.map(|_| ())
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for task in futures::future::join_all(tasks).await {
+        task.unwrap().unwrap();
+    }
+
+    let (_quickstart_admin_ws, app_ws) =
+        holochain_client::quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+            .await
+            .unwrap();
+    let outputs = futures::future::try_join_all((0..10).map(|_| {
+        app_ws.call_zome(
+            ROLE_NAME.to_string().into(),
+            "foo".into(),
+            "foo".into(),
+            ExternIO::encode(()).unwrap(),
+        )
+    }))
+    .await
+    .unwrap();
+    assert_eq!(outputs.len(), 10);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn request_timeout() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect_with_options(
+        (Ipv4Addr::LOCALHOST, admin_port),
+        ConnectOptions::default().with_request_timeout(Duration::ZERO),
+    )
+    .await
+    .unwrap();
+
+    let err = admin_ws.list_apps(None).await.unwrap_err();
+    assert!(matches!(
+        err,
+        ConductorApiError::RequestTimedOut { ref operation, timeout }
+            if operation == "list_apps" && timeout == Duration::ZERO
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn connect_url() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+
+    let admin_ws = AdminWebsocket::connect_url(
+        &format!("ws://127.0.0.1:{admin_port}"),
+        ConnectOptions::default(),
+    )
+    .await
+    .unwrap();
+    admin_ws.list_apps(None).await.unwrap();
+
+    let Err(err) = AdminWebsocket::connect_url(
+        &format!("http://127.0.0.1:{admin_port}"),
+        ConnectOptions::default(),
+    )
+    .await
+    else {
+        panic!("connecting to a URL which is not a websocket URL should fail");
+    };
+    assert!(err.to_string().contains("http://"));
+
+    let Err(err) = AdminWebsocket::connect_url(
+        &format!("ws://127.0.0.1:{admin_port}/conductor"),
+        ConnectOptions::default(),
+    )
+    .await
+    else {
+        panic!("connecting to a ws:// URL with a path should fail");
+    };
+    assert!(err.to_string().contains("only supported with wss://"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn install_app_from_built_bundle() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    let bundle = AppBundleBuilder::new("built-app")
+        .role_from_file("bar", "./fixture/test.dna")
+        .await
+        .unwrap()
+        .build()
+        .await
+        .unwrap();
+
+    let app_id: InstalledAppId = "built-app".into();
+    let app_info = admin_ws
+        .install_app(InstallAppPayload {
+            agent_key: None,
+            installed_app_id: Some(app_id.clone()),
+            network_seed: None,
+            roles_settings: None,
+            source: AppBundleSource::Bundle(bundle),
+            ignore_genesis_failure: false,
+            allow_throwaway_random_agent_key: false,
+        })
+        .await
+        .unwrap();
+    admin_ws.enable_app(app_id).await.unwrap();
+
+    assert!(matches!(
+        app_info.cell_info.get("bar").unwrap()[0],
+        CellInfo::Provisioned(_)
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn register_dna_from_file() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    let dna_hash = admin_ws
+        .register_dna_from_file(
+            "./fixture/test.dna",
+            DnaModifiersOpt::default().with_network_seed("seed 1".to_string()),
+        )
+        .await
+        .unwrap();
+    let other_dna_hash = admin_ws
+        .register_dna_from_file(
+            "./fixture/test.dna",
+            DnaModifiersOpt::default().with_network_seed("seed 2".to_string()),
+        )
+        .await
+        .unwrap();
+    assert_ne!(dna_hash, other_dna_hash);
+
+    let dna_def = admin_ws.get_dna_definition(dna_hash).await.unwrap();
+    assert_eq!(dna_def.modifiers.network_seed, "seed 1");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn register_dna_with_properties() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    let register = |properties: &str| {
+        let properties = YamlProperties::new(serde_yaml::from_str(properties).unwrap());
+        admin_ws.register_dna(RegisterDnaPayload {
+            modifiers: DnaModifiersOpt::default().with_properties(properties),
+            source: DnaSource::Path(PathBuf::from("./fixture/test.dna").canonicalize().unwrap()),
+        })
+    };
+    let dna_hash = register("variant: 1").await.unwrap();
+    let other_dna_hash = register("variant: 2").await.unwrap();
+    assert_ne!(dna_hash, other_dna_hash);
+
+    let dnas = admin_ws.list_dnas().await.unwrap();
+    assert!(dnas.contains(&dna_hash));
+    assert!(dnas.contains(&other_dna_hash));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn request_stats_per_operation() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    admin_ws.list_apps(None).await.unwrap();
+    admin_ws.list_apps(None).await.unwrap();
+    admin_ws.generate_agent_pub_key().await.unwrap();
+    admin_ws
+        .enable_app("no-such-app".to_string())
+        .await
+        .unwrap_err();
+
+    let stats = admin_ws.stats();
+    let list_apps = &stats.operations["list_apps"];
+    assert_eq!(list_apps.count, 2);
+    assert_eq!(list_apps.errors, 0);
+    // Listing apps carries no data whose size is known without encoding the messages.
+    assert_eq!(list_apps.bytes_sent, 0);
+    assert_eq!(list_apps.bytes_received, 0);
+    assert!(list_apps.max_latency >= list_apps.mean_latency());
+    assert_eq!(stats.operations["generate_agent_pub_key"].count, 1);
+    assert_eq!(stats.operations["enable_app"].errors, 1);
+    assert_eq!(stats.total().count, 4);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn clones_share_the_connection() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    let clone = admin_ws.clone();
+    tokio::spawn(async move { clone.list_apps(None).await.map(|_| ()) })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(admin_ws.stats().operations["list_apps"].count, 1);
+
+    // The connection stays open while any clone is alive.
+    let clone = admin_ws.clone();
+    drop(admin_ws);
+    clone.list_apps(None).await.unwrap();
+    assert_eq!(clone.stats().operations["list_apps"].count, 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn export_and_import_grants() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    let app_id: InstalledAppId = "test-app".into();
+    let agent_key = admin_ws.generate_agent_pub_key().await.unwrap();
+    let install = || {
+        admin_ws.install_app(InstallAppPayload {
+            agent_key: Some(agent_key.clone()),
+            installed_app_id: Some(app_id.clone()),
+            network_seed: None,
+            roles_settings: None,
+            source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+            ignore_genesis_failure: false,
+            allow_throwaway_random_agent_key: false,
+        })
+    };
+    let app_info = install().await.unwrap();
+    admin_ws.enable_app(app_id.clone()).await.unwrap();
+    let cell_id = match &app_info.cell_info.get(ROLE_NAME).unwrap()[0] {
+        CellInfo::Provisioned(cell) => cell.cell_id.clone(),
+        _ => panic!("expected provisioned cell"),
+    };
+
+    let signer = ClientAgentSigner::default();
+    let credentials = admin_ws
+        .authorize_signing_credentials(AuthorizeSigningCredentialsPayload {
+            cell_id: cell_id.clone(),
+            functions: None,
+        })
+        .await
+        .unwrap();
+    signer.add_credentials(cell_id.clone(), credentials);
+
+    let export = admin_ws.export_grants();
+    assert_eq!(export.grants.len(), 1);
+    assert_eq!(export.grants[0].cell_id, cell_id);
+    assert_eq!(export.grants[0].cap_grant.tag, "zome-call-signing-key");
+    // The secret is not printed.
+    assert!(!format!("{export:?}").contains("secret"));
+
+    // Rebuild the app for the same agent, which loses the grant.
+    admin_ws.uninstall_app(app_id.clone(), false).await.unwrap();
+    install().await.unwrap();
+    admin_ws.enable_app(app_id.clone()).await.unwrap();
+
+    admin_ws.import_grants(export).await.unwrap();
+
+    let app_ws_port = admin_ws
+        .attach_app_interface(0, AllowedOrigins::Any, None)
+        .await
+        .unwrap();
+    let issued_token = admin_ws
+        .issue_app_auth_token(app_id.clone().into())
+        .await
+        .unwrap();
+    let app_ws = AppWebsocket::connect(
+        (Ipv4Addr::LOCALHOST, app_ws_port),
+        issued_token.token,
+        signer.into(),
+    )
+    .await
+    .unwrap();
+    let response = app_ws
+        .call_zome(
+            cell_id.into(),
+            "foo".into(),
+            "foo".into(),
+            ExternIO::encode(()).unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.decode::<String>().unwrap(), "foo");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn enable_app_with_progress() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    let app_id: InstalledAppId = "test-app".into();
+    admin_ws
+        .install_app(InstallAppPayload {
+            agent_key: None,
+            installed_app_id: Some(app_id.clone()),
+            network_seed: None,
+            roles_settings: None,
+            source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+            ignore_genesis_failure: false,
+            allow_throwaway_random_agent_key: false,
+        })
+        .await
+        .unwrap();
+
+    let progress: Vec<EnableAppProgress> = admin_ws
+        .enable_app_with_progress(app_id.clone(), Duration::from_millis(1))
+        .try_collect()
+        .await
+        .unwrap();
+    let (last, pending) = progress.split_last().unwrap();
+    match last {
+        EnableAppProgress::Enabled(response) => {
+            assert_eq!(response.app.installed_app_id, app_id);
+            assert_eq!(response.app.status, AppInfoStatus::Running);
+        }
+        _ => panic!("expected the app to be enabled"),
+    }
+    assert!(pending
+        .iter()
+        .all(|progress| matches!(progress, EnableAppProgress::Pending { .. })));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn install_app_for_existing_agent() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    let payload = |app_id: &str| InstallAppPayload {
+        agent_key: None,
+        installed_app_id: Some(app_id.into()),
+        network_seed: None,
+        roles_settings: None,
+        source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+        ignore_genesis_failure: false,
+        allow_throwaway_random_agent_key: false,
+    };
+    let first_app = admin_ws.install_app(payload("first-app")).await.unwrap();
+
+    let second_app = admin_ws
+        .install_app_for_existing_agent(payload("second-app"), |agent| {
+            agent.installed_app_ids.contains(&"first-app".to_string())
+        })
+        .await
+        .unwrap();
+    assert_eq!(second_app.agent_pub_key, first_app.agent_pub_key);
+
+    let agents = admin_ws.list_agents().await.unwrap();
+    assert_eq!(agents.len(), 1);
+    assert_eq!(agents[0].agent_pub_key, first_app.agent_pub_key);
+    assert_eq!(agents[0].installed_app_ids.len(), 2);
+
+    let err = admin_ws
+        .install_app_for_existing_agent(payload("third-app"), |_| false)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ConductorApiError::NoMatchingAgent));
+    assert_eq!(admin_ws.list_apps(None).await.unwrap().len(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn export_and_delete_agent_key_are_unsupported() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    let agent_key = admin_ws.generate_agent_pub_key().await.unwrap();
+    let err = admin_ws
+        .export_agent_key(agent_key.clone())
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ConductorApiError::UnsupportedOperation {
+            operation: "export_agent_key",
+            ..
+        }
+    ));
+
+    let err = admin_ws.delete_agent_key(agent_key).await.unwrap_err();
+    assert!(matches!(
+        err,
+        ConductorApiError::UnsupportedOperation {
+            operation: "delete_agent_key",
+            ..
+        }
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn revoke_app_authentication_token() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    let app_id: InstalledAppId = "test-app".into();
+    admin_ws
+        .install_app(InstallAppPayload {
+            agent_key: None,
+            installed_app_id: Some(app_id.clone()),
+            network_seed: None,
+            roles_settings: None,
+            source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+            ignore_genesis_failure: false,
+            allow_throwaway_random_agent_key: false,
+        })
+        .await
+        .unwrap();
+    admin_ws.enable_app(app_id.clone()).await.unwrap();
+    let app_ws_port = admin_ws
+        .attach_app_interface(0, AllowedOrigins::Any, None)
+        .await
+        .unwrap();
+
+    let issued_token = admin_ws
+        .issue_app_auth_token(IssueAppAuthenticationTokenPayload {
+            installed_app_id: app_id.clone(),
+            expiry_seconds: 0,
+            single_use: false,
+        })
+        .await
+        .unwrap();
+    AppWebsocket::connect(
+        (Ipv4Addr::LOCALHOST, app_ws_port),
+        issued_token.token.clone(),
+        ClientAgentSigner::default().into(),
+    )
+    .await
+    .unwrap();
+
+    admin_ws
+        .revoke_app_authentication_token(issued_token.token.clone())
+        .await
+        .unwrap();
+
+    // The token can't be used to authenticate again.
+    let result = AppWebsocket::connect(
+        (Ipv4Addr::LOCALHOST, app_ws_port),
+        issued_token.token,
+        ClientAgentSigner::default().into(),
+    )
+    .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn list_capability_grants() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    let app_id: InstalledAppId = "test-app".into();
+    let app_info = admin_ws
+        .install_app(InstallAppPayload {
+            agent_key: None,
+            installed_app_id: Some(app_id.clone()),
+            network_seed: None,
+            roles_settings: None,
+            source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+            ignore_genesis_failure: false,
+            allow_throwaway_random_agent_key: false,
+        })
+        .await
+        .unwrap();
+    admin_ws.enable_app(app_id.clone()).await.unwrap();
+    let cell_id = match &app_info.cell_info.get(ROLE_NAME).unwrap()[0] {
+        CellInfo::Provisioned(cell) => cell.cell_id.clone(),
+        _ => panic!("expected provisioned cell"),
+    };
+
+    let grants_before = admin_ws
+        .list_capability_grants(cell_id.clone())
+        .await
+        .unwrap();
+    admin_ws
+        .authorize_signing_credentials(AuthorizeSigningCredentialsPayload {
+            cell_id: cell_id.clone(),
+            functions: None,
+        })
+        .await
+        .unwrap();
+
+    let grants = admin_ws
+        .list_capability_grants(cell_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(grants.len(), grants_before.len() + 1);
+    let grant = grants
+        .iter()
+        .find(|grant| grant.cap_grant.tag == ZOME_CALL_SIGNING_KEY_TAG)
+        .unwrap();
+    assert!(grant.is_signing_key_grant());
+    // The secret is not printed.
+    assert!(!format!("{grant:?}").contains("secret"));
+
+    let signing_key_grants = admin_ws
+        .list_signing_key_grants(cell_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(signing_key_grants.len(), 1);
+    assert_eq!(signing_key_grants[0].action_hash, grant.action_hash);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn attach_app_interface_with_origins_and_app_binding() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    let install = |app_id: InstalledAppId| {
+        admin_ws.install_app(InstallAppPayload {
+            agent_key: None,
+            installed_app_id: Some(app_id),
+            network_seed: None,
+            roles_settings: None,
+            source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+            ignore_genesis_failure: false,
+            allow_throwaway_random_agent_key: false,
+        })
+    };
+    let app_id: InstalledAppId = "test-app".into();
+    let other_app_id: InstalledAppId = "other-app".into();
+    install(app_id.clone()).await.unwrap();
+    install(other_app_id.clone()).await.unwrap();
+    admin_ws.enable_app(app_id.clone()).await.unwrap();
+    admin_ws.enable_app(other_app_id.clone()).await.unwrap();
+
+    let allowed_origins: AllowedOrigins = "my_cli_app".to_string().into();
+    let locked_down_port = admin_ws
+        .attach_app_interface(0, allowed_origins.clone(), Some(app_id.clone()))
+        .await
+        .unwrap();
+    let bound_port = admin_ws
+        .attach_app_interface(0, AllowedOrigins::Any, Some(app_id.clone()))
+        .await
+        .unwrap();
+
+    let app_interfaces = admin_ws.list_app_interfaces().await.unwrap();
+    let locked_down = app_interfaces
+        .iter()
+        .find(|interface| interface.port == locked_down_port)
+        .unwrap();
+    assert_eq!(locked_down.allowed_origins, allowed_origins);
+    assert_eq!(locked_down.installed_app_id, Some(app_id.clone()));
+
+    // A client of the bound app can connect to the bound interface.
+    let issued_token = admin_ws
+        .issue_app_auth_token(app_id.clone().into())
+        .await
+        .unwrap();
+    AppWebsocket::connect(
+        (Ipv4Addr::LOCALHOST, bound_port),
+        issued_token.token,
+        ClientAgentSigner::default().into(),
+    )
+    .await
+    .unwrap();
+
+    // A client of another app can't.
+    let issued_token = admin_ws
+        .issue_app_auth_token(other_app_id.clone().into())
+        .await
+        .unwrap();
+    let result = AppWebsocket::connect(
+        (Ipv4Addr::LOCALHOST, bound_port),
+        issued_token.token,
+        ClientAgentSigner::default().into(),
+    )
+    .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn attach_and_connect() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    let app_id: InstalledAppId = "test-app".into();
+    let app_info = admin_ws
+        .install_app(InstallAppPayload {
+            agent_key: None,
+            installed_app_id: Some(app_id.clone()),
+            network_seed: None,
+            roles_settings: None,
+            source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+            ignore_genesis_failure: false,
+            allow_throwaway_random_agent_key: false,
+        })
+        .await
+        .unwrap();
+    admin_ws.enable_app(app_id.clone()).await.unwrap();
+
+    let app_ws = admin_ws
+        .attach_and_connect(
+            Ipv4Addr::LOCALHOST,
+            AllowedOrigins::Any,
+            app_id.clone(),
+            ClientAgentSigner::default().into(),
+            Duration::from_secs(10),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        app_ws.app_info().await.unwrap().unwrap().agent_pub_key,
+        app_info.agent_pub_key
+    );
+
+    let app_interfaces = admin_ws.list_app_interfaces().await.unwrap();
+    assert_eq!(app_interfaces.len(), 1);
+    assert_eq!(app_interfaces[0].installed_app_id, Some(app_id));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn set_apps_enabled() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    let app_ids: Vec<InstalledAppId> = vec!["app-1".into(), "app-2".into()];
+    for app_id in &app_ids {
+        admin_ws
+            .install_app(InstallAppPayload {
+                agent_key: None,
+                installed_app_id: Some(app_id.clone()),
+                network_seed: None,
+                roles_settings: None,
+                source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+                ignore_genesis_failure: false,
+                allow_throwaway_random_agent_key: false,
+            })
+            .await
+            .unwrap();
+    }
+    let missing_app_id: InstalledAppId = "missing".into();
+    let changes: BTreeMap<InstalledAppId, bool> = app_ids
+        .iter()
+        .chain([&missing_app_id])
+        .map(|app_id| (app_id.clone(), true))
+        .collect();
+    let enabled_app_ids = || async {
+        admin_ws
+            .list_apps(Some(AppStatusFilter::Enabled))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|app| app.installed_app_id)
+            .collect::<BTreeSet<_>>()
+    };
+
+    // The missing app is critical, so the other apps are disabled again.
+    let report = admin_ws
+        .set_apps_enabled(
+            changes.clone(),
+            RevertPolicy::IfCriticalFails(BTreeSet::from([missing_app_id.clone()])),
+        )
+        .await
+        .unwrap();
+    assert!(report.results[&missing_app_id].is_err());
+    assert!(report.results[&app_ids[0]].is_ok());
+    assert_eq!(report.reverted.len(), 2);
+    assert!(report.reverted.values().all(Result::is_ok));
+    assert!(enabled_app_ids().await.is_empty());
+
+    // Without reverting, the other apps stay enabled.
+    let report = admin_ws
+        .set_apps_enabled(changes, RevertPolicy::Never)
+        .await
+        .unwrap();
+    assert!(report.results[&missing_app_id].is_err());
+    assert!(report.reverted.is_empty());
+    assert_eq!(
+        enabled_app_ids().await,
+        app_ids.into_iter().collect::<BTreeSet<_>>()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn uninstall_app_with_force() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    let app_id: InstalledAppId = "test-app".into();
+    admin_ws
+        .install_app(InstallAppPayload {
+            agent_key: None,
+            installed_app_id: Some(app_id.clone()),
+            network_seed: None,
+            roles_settings: None,
+            source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+            ignore_genesis_failure: false,
+            allow_throwaway_random_agent_key: false,
+        })
+        .await
+        .unwrap();
+    admin_ws.enable_app(app_id.clone()).await.unwrap();
+
+    admin_ws.uninstall_app(app_id, true).await.unwrap();
+    assert!(admin_ws.list_apps(None).await.unwrap().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn install_app_with_generated_agent_key_ignoring_genesis_failure() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    let app_id: InstalledAppId = "test-app".into();
+    let app_info = admin_ws
+        .install_app(InstallAppPayload {
+            agent_key: None,
+            installed_app_id: Some(app_id.clone()),
+            network_seed: Some("install-seed".to_string()),
+            roles_settings: None,
+            source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+            ignore_genesis_failure: true,
+            allow_throwaway_random_agent_key: false,
+        })
+        .await
+        .unwrap();
+    admin_ws.enable_app(app_id.clone()).await.unwrap();
+
+    // The conductor generated an agent key for the app.
+    let agents = admin_ws.list_agents().await.unwrap();
+    assert_eq!(agents.len(), 1);
+    assert_eq!(agents[0].agent_pub_key, app_info.agent_pub_key);
+    assert_eq!(agents[0].installed_app_ids, vec![app_id]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn install_app_checked() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    let payload = || InstallAppPayload {
+        agent_key: None,
+        installed_app_id: Some("test-app".to_string()),
+        network_seed: None,
+        roles_settings: None,
+        source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+        ignore_genesis_failure: false,
+        allow_throwaway_random_agent_key: false,
+    };
+    let install_error = |payload: InstallAppPayload| {
+        let admin_ws = &admin_ws;
+        async move {
+            match admin_ws.install_app_checked(payload).await.unwrap_err() {
+                ConductorApiError::InvalidInstallAppPayload(err) => err,
+                err => panic!("Unexpected error: {err:?}"),
+            }
+        }
+    };
+
+    let err = install_error(InstallAppPayload {
+        installed_app_id: Some(" test-app".to_string()),
+        ..payload()
+    })
+    .await;
+    assert!(matches!(err, InstallAppPayloadError::InvalidAppId(_)));
+
+    let err = install_error(InstallAppPayload {
+        source: AppBundleSource::Path(PathBuf::from("./fixture/missing.happ")),
+        ..payload()
+    })
+    .await;
+    assert!(matches!(
+        err,
+        InstallAppPayloadError::UnreadableBundle { .. }
+    ));
+
+    let role_settings = RoleSettings::Provisioned {
+        membrane_proof: Default::default(),
+        modifiers: None,
+    };
+    let err = install_error(InstallAppPayload {
+        roles_settings: Some(HashMap::from([("missing".to_string(), role_settings)])),
+        ..payload()
+    })
+    .await;
+    assert_eq!(err, InstallAppPayloadError::UnknownRole("missing".into()));
+
+    // Nothing was sent to the conductor.
+    assert!(admin_ws.list_apps(None).await.unwrap().is_empty());
+
+    admin_ws.install_app_checked(payload()).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn cell_error_watcher() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    let app_id: InstalledAppId = "test-app".into();
+    let app_info = admin_ws
+        .install_app(InstallAppPayload {
+            agent_key: None,
+            installed_app_id: Some(app_id.clone()),
+            network_seed: None,
+            roles_settings: None,
+            source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+            ignore_genesis_failure: false,
+            allow_throwaway_random_agent_key: false,
+        })
+        .await
+        .unwrap();
+    admin_ws.enable_app(app_id.clone()).await.unwrap();
+    let cell_id = match &app_info.cell_info[ROLE_NAME][0] {
+        CellInfo::Provisioned(cell) => cell.cell_id.clone(),
+        _ => panic!("wrong cell type"),
+    };
+
+    // The genesis ops of a healthy cell don't get stuck.
+    let mut watcher = Box::pin(admin_ws.cell_error_watcher(
+        cell_id.clone(),
+        Duration::from_millis(100),
+        Duration::from_secs(60),
+    ));
+    assert!(tokio::time::timeout(Duration::from_secs(1), watcher.next())
+        .await
+        .is_err());
+
+    // Polling a cell that doesn't exist reports an error without ending the stream.
+    let unknown_cell_id = CellId::new(
+        cell_id.dna_hash().clone(),
+        admin_ws.generate_agent_pub_key().await.unwrap(),
+    );
+    let mut watcher = Box::pin(admin_ws.cell_error_watcher(
+        unknown_cell_id,
+        Duration::from_millis(100),
+        Duration::ZERO,
+    ));
+    assert!(watcher.next().await.unwrap().is_err());
+    assert!(watcher.next().await.unwrap().is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn service_monitor_reports_readiness() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (admin_ws, _app_ws) =
+        holochain_client::quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+            .await
+            .unwrap();
+
+    let socket_path = std::env::temp_dir().join(format!("notify-{admin_port}.sock"));
+    let _ = std::fs::remove_file(&socket_path);
+    let notify_socket = std::os::unix::net::UnixDatagram::bind(&socket_path).unwrap();
+    notify_socket
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+
+    let health = HealthState::new();
+    assert_eq!(health.status(), ServiceStatus::Starting);
+    let monitor = ServiceMonitor::new(Duration::from_secs(1))
+        .with_hook(health.clone())
+        .with_hook(SdNotify::new(socket_path.to_str().unwrap()).unwrap())
+        .with_required_apps(vec!["test".to_string()]);
+
+    assert_eq!(monitor.check_once(&admin_ws).await, ServiceStatus::Ready);
+    assert!(health.is_ready());
+    let mut buf = [0; 256];
+    let len = notify_socket.recv(&mut buf).unwrap();
+    assert!(std::str::from_utf8(&buf[..len])
+        .unwrap()
+        .starts_with("READY=1\n"));
+
+    admin_ws.disable_app("test".to_string()).await.unwrap();
+    assert!(matches!(
+        monitor.check_once(&admin_ws).await,
+        ServiceStatus::Unhealthy(_)
+    ));
+    assert!(!health.is_ready());
+
+    std::fs::remove_file(&socket_path).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn admin_role_restricts_operations() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap()
+        .with_role(AdminRole::read_only("monitoring"));
+    assert_eq!(admin_ws.role().name, "monitoring");
+    admin_ws.enable_debug_history(10);
+
+    admin_ws.list_apps(None).await.unwrap();
+    let err = admin_ws.generate_agent_pub_key().await.unwrap_err();
+    assert!(matches!(
+        err,
+        ConductorApiError::OperationNotAllowed { ref operation, ref role }
+            if operation == "generate_agent_pub_key" && role == "monitoring"
+    ));
+    // The refused request was not sent.
+    assert!(!admin_ws
+        .stats()
+        .operations
+        .contains_key("generate_agent_pub_key"));
+    let history = admin_ws.debug_history();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].operation, "list_apps");
+
+    let provisioning_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap()
+        .with_role(AdminRole::restricted(
+            "provisioning",
+            ["generate_agent_pub_key"],
+        ));
+    provisioning_ws.generate_agent_pub_key().await.unwrap();
+    assert!(matches!(
+        provisioning_ws.list_apps(None).await,
+        Err(ConductorApiError::OperationNotAllowed { .. })
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn readiness_probe() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (admin_ws, app_ws) =
+        holochain_client::quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+            .await
+            .unwrap();
+    let cell_id = match &app_ws.app_info().await.unwrap().unwrap().cell_info[ROLE_NAME][0] {
+        CellInfo::Provisioned(cell) => cell.cell_id.clone(),
+        _ => panic!("expected provisioned cell"),
+    };
+    let signer = ClientAgentSigner::default();
+    signer.add_credentials(
+        cell_id.clone(),
+        admin_ws
+            .authorize_signing_credentials(AuthorizeSigningCredentialsPayload {
+                cell_id: cell_id.clone(),
+                functions: None,
+            })
+            .await
+            .unwrap(),
+    );
+    let probe_zome_fn = ProbeZomeFn {
+        target: cell_id.into(),
+        zome_name: "foo".into(),
+        fn_name: "foo".into(),
+        payload: ExternIO::encode(()).unwrap(),
+        signer: signer.into(),
+    };
+    let timeout = Duration::from_secs(10);
+
+    let report = admin_ws
+        .readiness_probe("test".to_string(), Some(probe_zome_fn.clone()), timeout)
+        .await;
+    assert!(report.is_ready(), "{report:?}");
+    assert_eq!(
+        report.passed,
+        vec![
+            ReadinessCheck::Installed,
+            ReadinessCheck::Enabled,
+            ReadinessCheck::InterfaceAttached,
+            ReadinessCheck::Authenticated,
+            ReadinessCheck::ZomeCall,
+        ]
+    );
+
+    let report = admin_ws
+        .readiness_probe("missing".to_string(), None, timeout)
+        .await;
+    assert!(report.passed.is_empty());
+    assert_eq!(report.failure.unwrap().check, ReadinessCheck::Installed);
+
+    admin_ws.disable_app("test".to_string()).await.unwrap();
+    let report = admin_ws
+        .readiness_probe("test".to_string(), Some(probe_zome_fn), timeout)
+        .await;
+    assert_eq!(report.passed, vec![ReadinessCheck::Installed]);
+    assert_eq!(report.failure.unwrap().check, ReadinessCheck::Enabled);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn watch_all_apps_reports_changes() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (admin_ws, app_ws) =
+        holochain_client::quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+            .await
+            .unwrap();
+
+    async fn next_change(
+        changes: &mut (impl Stream<Item = ConductorApiResult<AppChange>> + Unpin),
+    ) -> AppChange {
+        tokio::time::timeout(Duration::from_secs(5), changes.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap()
+    }
+    let mut changes = Box::pin(admin_ws.watch_all_apps(Duration::from_millis(100)));
+
+    // The apps which are already installed are reported first.
+    match next_change(&mut changes).await {
+        AppChange::AppAdded(app_info) => assert_eq!(app_info.installed_app_id, "test"),
+        change => panic!("Unexpected change {change:?}"),
+    }
+
+    let clone_cell = app_ws
+        .create_clone_cell(CreateCloneCellPayload {
+            role_name: ROLE_NAME.to_string(),
+            modifiers: DnaModifiersOpt::none().with_network_seed("watched".into()),
+            membrane_proof: None,
+            name: None,
+        })
+        .await
+        .unwrap();
+    match next_change(&mut changes).await {
+        AppChange::CellAdded {
+            installed_app_id,
+            role_name,
+            cell_info: CellInfo::Cloned(cell),
+        } => {
+            assert_eq!(installed_app_id, "test");
+            assert_eq!(role_name, ROLE_NAME);
+            assert_eq!(cell.cell_id, clone_cell.cell_id);
+        }
+        change => panic!("Unexpected change {change:?}"),
+    }
+
+    app_ws
+        .disable_clone(clone_cell.clone_id.clone())
+        .await
+        .unwrap();
+    match next_change(&mut changes).await {
+        AppChange::CellEnabledChanged {
+            cell_id, enabled, ..
+        } => {
+            assert_eq!(cell_id, clone_cell.cell_id);
+            assert!(!enabled);
+        }
+        change => panic!("Unexpected change {change:?}"),
+    }
+
+    admin_ws.disable_app("test".to_string()).await.unwrap();
+    match next_change(&mut changes).await {
+        AppChange::StatusChanged {
+            installed_app_id,
+            status,
+        } => {
+            assert_eq!(installed_app_id, "test");
+            assert!(matches!(status, AppInfoStatus::Disabled { .. }));
+        }
+        change => panic!("Unexpected change {change:?}"),
+    }
+
+    admin_ws
+        .uninstall_app("test".to_string(), false)
+        .await
+        .unwrap();
+    match next_change(&mut changes).await {
+        AppChange::AppRemoved(installed_app_id) => assert_eq!(installed_app_id, "test"),
+        change => panic!("Unexpected change {change:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn computed_dna_hash_matches_conductor() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    let modifiers = DnaModifiersOpt::default().with_network_seed("computed".to_string());
+    let dna_hash = admin_ws
+        .register_dna_from_file("./fixture/test.dna", modifiers.clone())
+        .await
+        .unwrap();
+
+    let computed = compute_dna_hash_from_file("./fixture/test.dna", modifiers.clone())
+        .await
+        .unwrap();
+    assert_eq!(computed, dna_hash);
+
+    let bundle = DnaBundle::read_from_file(Path::new("./fixture/test.dna"))
+        .await
+        .unwrap();
+    verify_dna_hash(&bundle, modifiers, &dna_hash)
+        .await
+        .unwrap();
+    // The hash depends on the modifiers.
+    verify_dna_hash(
+        &bundle,
+        DnaModifiersOpt::default().with_network_seed("other".to_string()),
+        &dna_hash,
+    )
+    .await
+    .unwrap_err();
+}
@@ -0,0 +1,118 @@
+//! Tracks which conductor API requests are wrapped by a client method.
+//!
+//! Every request variant is listed in a match without a wildcard, so a conductor API release that
+//! adds a request stops this test from compiling until the request is either wrapped or listed as
+//! not wrapped. Each wrapped request names the client method which sends it, and the method is
+//! referenced at compile time, so a renamed or removed method also stops this test from compiling.
+//! The test checks the list of requests which are not wrapped.
+
+use holochain_client::{AdminWebsocket, AppWebsocket};
+use holochain_conductor_api::{AdminRequest, AppRequest};
+
+/// Lists the requests of `$request` with the method of `$client` which sends each of them, or
+/// `None` if there is none. Defines `$list` returning the variant names with their method, and
+/// `$check`, whose match makes sure that every variant is listed.
+///
+/// Variants which only exist with a feature of the conductor API are listed behind that feature.
+/// Without it, they are covered by a wildcard, since another dependency may still enable the
+/// conductor API's feature.
+macro_rules! request_coverage {
+    (
+        fn $list:ident, $check:ident($request:ident) by $client:ident $(unless $feature:literal)? {
+            $($(#[$attr:meta])* $variant:ident => $method:tt,)*
+        }
+    ) => {
+        fn $list() -> Vec<(&'static str, Option<&'static str>)> {
+            let mut methods = Vec::new();
+            $($(#[$attr])* methods.push((stringify!($variant), request_coverage!(@method $client $method)));)*
+            methods
+        }
+
+        #[allow(dead_code, unreachable_patterns)]
+        fn $check(request: &$request) {
+            match request {
+                $($(#[$attr])* $request::$variant { .. } => {})*
+                $(#[cfg(not(feature = $feature))] _ => {})?
+            }
+        }
+    };
+    (@method $client:ident None) => {
+        None
+    };
+    (@method $client:ident $method:ident) => {{
+        let _ = $client::$method;
+        Some(stringify!($method))
+    }};
+}
+
+request_coverage! {
+    fn admin_request_methods, check_admin_requests(AdminRequest) by AdminWebsocket {
+        AddAdminInterfaces => None,
+        RegisterDna => register_dna,
+        GetDnaDefinition => get_dna_definition,
+        UpdateCoordinators => update_coordinators,
+        InstallApp => install_app,
+        UninstallApp => uninstall_app,
+        ListDnas => list_dnas,
+        GenerateAgentPubKey => generate_agent_pub_key,
+        RevokeAgentKey => revoke_agent_key,
+        ListCellIds => list_cell_ids,
+        ListApps => list_apps,
+        EnableApp => enable_app,
+        DisableApp => disable_app,
+        AttachAppInterface => attach_app_interface,
+        ListAppInterfaces => list_app_interfaces,
+        DumpState => dump_state,
+        DumpConductorState => dump_conductor_state,
+        DumpFullState => dump_full_state,
+        DumpNetworkMetrics => dump_network_metrics,
+        DumpNetworkStats => dump_network_stats,
+        AddAgentInfo => add_agent_info,
+        AgentInfo => agent_info,
+        GraftRecords => graft_records,
+        GrantZomeCallCapability => grant_zome_call_capability,
+        DeleteCloneCell => delete_clone_cell,
+        StorageInfo => storage_info,
+        IssueAppAuthenticationToken => issue_app_auth_token,
+        RevokeAppAuthenticationToken => revoke_app_authentication_token,
+        GetCompatibleCells => get_compatible_cells,
+    }
+}
+
+// The countersigning requests only exist with the conductor API's `unstable-countersigning`
+// feature, which this crate's feature of the same name enables.
+request_coverage! {
+    fn app_request_methods, check_app_requests(AppRequest) by AppWebsocket
+        unless "unstable-countersigning"
+    {
+        AppInfo => app_info,
+        CallZome => call_zome,
+        CreateCloneCell => create_clone_cell,
+        DisableCloneCell => disable_clone_cell,
+        EnableCloneCell => enable_clone_cell,
+        NetworkInfo => network_info,
+        ListWasmHostFunctions => list_wasm_host_functions,
+        ProvideMemproofs => provide_memproofs,
+        EnableApp => enable_app,
+        #[cfg(feature = "unstable-countersigning")]
+        GetCountersigningSessionState => get_countersigning_session_state,
+        #[cfg(feature = "unstable-countersigning")]
+        AbandonCountersigningSession => abandon_countersigning_session,
+        #[cfg(feature = "unstable-countersigning")]
+        PublishCountersigningSession => publish_countersigning_session,
+    }
+}
+
+fn unwrapped(methods: Vec<(&'static str, Option<&'static str>)>) -> Vec<&'static str> {
+    methods
+        .into_iter()
+        .filter(|(_, method)| method.is_none())
+        .map(|(variant, _)| variant)
+        .collect()
+}
+
+#[test]
+fn unwrapped_requests() {
+    assert_eq!(unwrapped(admin_request_methods()), ["AddAdminInterfaces"]);
+    assert!(unwrapped(app_request_methods()).is_empty());
+}
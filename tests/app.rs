@@ -1,24 +1,37 @@
+use ed25519_dalek::Signer;
+use futures::{StreamExt, TryStreamExt};
 use holochain::{
-    prelude::{AppBundleSource, NetworkInfoRequestPayload, Signal},
+    prelude::{
+        AppBundleSource, CreateCloneCellPayload, DnaModifiersOpt, NetworkInfoRequestPayload, Signal,
+    },
     sweettest::SweetConductor,
 };
 use holochain_client::{
-    AdminWebsocket, AppWebsocket, AuthorizeSigningCredentialsPayload, ClientAgentSigner,
-    InstallAppPayload, InstalledAppId,
+    quickstart, verify_signed_zome_call, AdminWebsocket, AppWebsocket,
+    AuthorizeSigningCredentialsPayload, ClientAgentSigner, ClientSet, CompositeSigner,
+    ConductorApiError, ConductorErrorKind, DetachedSigningBatch, DisabledAppGuard, HolochainClient,
+    InputValidators, InstallAppPayload, InstalledAppId, Projection, ProjectionQuery,
+    RequestOutcome, SerializedZomeCall, SignalDecodeError, SignalEvent, SignalExt, SignalGap,
+    WebsocketConfig, ZomeCallTarget,
 };
 use holochain_conductor_api::{AppInfoStatus, CellInfo, NetworkInfo};
 use holochain_types::{
     app::{AppBundle, AppManifestV1, DisabledAppReason},
     websocket::AllowedOrigins,
 };
-use holochain_zome_types::dependencies::holochain_integrity_types::ExternIO;
+use holochain_zome_types::capability::GrantedFunctions;
+use holochain_zome_types::dependencies::holochain_integrity_types::{ExternIO, Signature};
+use holochain_zome_types::prelude::Timestamp;
 use kitsune_p2p_types::fetch_pool::FetchPoolInfo;
 use serde::{Deserialize, Serialize};
 use std::net::Ipv4Addr;
 use std::{
-    collections::HashMap,
+    collections::{BTreeSet, HashMap},
     path::PathBuf,
-    sync::{Arc, Barrier},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Barrier,
+    },
 };
 
 #[tokio::test(flavor = "multi_thread")]
@@ -73,7 +86,7 @@ async fn network_info() {
     let network_info = app_ws
         .network_info(NetworkInfoRequestPayload {
             agent_pub_key: agent_key,
-            dnas: vec![dna_hash],
+            dnas: vec![dna_hash.clone()],
             last_time_queried: None,
         })
         .await
@@ -94,6 +107,11 @@ async fn network_info() {
             completed_rounds_since_last_time_queried: 0,
         }
     );
+
+    let app_network_info = app_ws.app_network_info(None).await.unwrap();
+    assert_eq!(app_network_info.len(), 1);
+    assert_eq!(app_network_info[0].0, dna_hash);
+    assert_eq!(app_network_info[0].1.current_number_of_peers, 1);
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -165,7 +183,7 @@ async fn handle_signal() {
     let barrier = Arc::new(Barrier::new(2));
     let barrier_clone = barrier.clone();
 
-    app_ws
+    let handler_id = app_ws
         .on_signal(move |signal| match signal {
             Signal::App { signal, .. } => {
                 let ts: TestString = signal.into_inner().decode().unwrap();
@@ -188,6 +206,9 @@ async fn handle_signal() {
         .unwrap();
 
     barrier.wait();
+
+    assert!(app_ws.off_signal(&handler_id).await);
+    assert!(!app_ws.off_signal(&handler_id).await);
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -311,10 +332,13 @@ async fn deferred_memproof_installation() {
         .unwrap()
         .expect("app info must exist");
     assert_eq!(app_info.status, AppInfoStatus::AwaitingMemproofs);
+    assert!(app_ws.awaiting_memproofs());
 
     app_ws.enable_app().await.unwrap_err();
 
     app_ws.provide_memproofs(HashMap::new()).await.unwrap();
+    app_ws.refresh_app_info().await.unwrap();
+    assert!(!app_ws.awaiting_memproofs());
 
     let app_info = app_ws
         .app_info()
@@ -338,4 +362,1406 @@ async fn deferred_memproof_installation() {
         .unwrap()
         .expect("app info must exist");
     assert_eq!(app_info.status, AppInfoStatus::Running);
+    assert_eq!(
+        app_ws.cached_app_info().app_info.status,
+        AppInfoStatus::Running
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn conductor_error_is_structured() {
+    let conductor = SweetConductor::from_standard_config().await;
+
+    // Connect admin client
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    // Set up the test app
+    let app_id: InstalledAppId = "test-app".into();
+    let app_info = admin_ws
+        .install_app(InstallAppPayload {
+            agent_key: None,
+            installed_app_id: Some(app_id.clone()),
+            network_seed: None,
+            roles_settings: None,
+            source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+            ignore_genesis_failure: false,
+            allow_throwaway_random_agent_key: false,
+        })
+        .await
+        .unwrap();
+    admin_ws.enable_app(app_id.clone()).await.unwrap();
+
+    // Connect app client
+    let app_ws_port = admin_ws
+        .attach_app_interface(0, AllowedOrigins::Any, None)
+        .await
+        .unwrap();
+    let token_issued = admin_ws
+        .issue_app_auth_token(app_id.clone().into())
+        .await
+        .unwrap();
+    let signer = ClientAgentSigner::default();
+    let app_ws = AppWebsocket::connect(
+        (Ipv4Addr::LOCALHOST, app_ws_port),
+        token_issued.token,
+        signer.clone().into(),
+    )
+    .await
+    .unwrap();
+
+    let cell_id = match &app_info.cell_info.get("foo").unwrap()[0] {
+        CellInfo::Provisioned(cell) => cell.cell_id.clone(),
+        _ => panic!("wrong cell type"),
+    };
+    // Only grant access to `foo`, so that calling any other function is unauthorized.
+    let credentials = admin_ws
+        .authorize_signing_credentials(AuthorizeSigningCredentialsPayload {
+            cell_id: cell_id.clone(),
+            functions: Some(GrantedFunctions::Listed(BTreeSet::from([(
+                "foo".into(),
+                "foo".into(),
+            )]))),
+        })
+        .await
+        .unwrap();
+    signer.add_credentials(cell_id.clone(), credentials);
+
+    let err = app_ws
+        .call_zome(
+            cell_id.into(),
+            "foo".into(),
+            "bar".into(),
+            ExternIO::encode(()).unwrap(),
+        )
+        .await
+        .unwrap_err();
+    let conductor_error = err
+        .conductor_error()
+        .expect("error should come from the conductor");
+    assert_eq!(
+        conductor_error.kind,
+        ConductorErrorKind::ZomeCallUnauthorized
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn quickstart_installs_and_connects() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+
+    let (admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let apps = admin_ws.list_apps(None).await.unwrap();
+    assert_eq!(apps.len(), 1);
+    assert_eq!(apps[0].installed_app_id, "test");
+
+    let response = app_ws
+        .call_zome(
+            "foo".to_string().into(),
+            "foo".into(),
+            "foo".into(),
+            ExternIO::encode(()).unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.decode::<String>().unwrap(), "foo");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn holochain_client_app_sessions() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let client = HolochainClient::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    let app_id: InstalledAppId = "test-app".into();
+    client
+        .admin()
+        .install_app(InstallAppPayload {
+            agent_key: None,
+            installed_app_id: Some(app_id.clone()),
+            network_seed: None,
+            roles_settings: None,
+            source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+            ignore_genesis_failure: false,
+            allow_throwaway_random_agent_key: false,
+        })
+        .await
+        .unwrap();
+    client.admin().enable_app(app_id.clone()).await.unwrap();
+
+    let response = client
+        .app(&app_id)
+        .await
+        .unwrap()
+        .call_zome(
+            "foo".to_string().into(),
+            "foo".into(),
+            "foo".into(),
+            ExternIO::encode(()).unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.decode::<String>().unwrap(), "foo");
+
+    // The session is reused, so no further app interfaces are attached.
+    client.app(&app_id).await.unwrap();
+    assert_eq!(client.admin().list_app_interfaces().await.unwrap().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn holochain_client_concurrent_app_requests() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let client = HolochainClient::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    let mut app_ids = Vec::new();
+    for app_id in ["test-app-1", "test-app-2"] {
+        client
+            .admin()
+            .install_app(InstallAppPayload {
+                agent_key: None,
+                installed_app_id: Some(app_id.to_string()),
+                network_seed: None,
+                roles_settings: None,
+                source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+                ignore_genesis_failure: false,
+                allow_throwaway_random_agent_key: false,
+            })
+            .await
+            .unwrap();
+        client.admin().enable_app(app_id.to_string()).await.unwrap();
+        app_ids.push(app_id);
+    }
+
+    // Requests for the same app share one connection, requests for other apps are not blocked.
+    let (first, second, other) = futures::join!(
+        client.app(app_ids[0]),
+        client.app(app_ids[0]),
+        client.app(app_ids[1])
+    );
+    let (first, second, other) = (first.unwrap(), second.unwrap(), other.unwrap());
+    assert_eq!(
+        first.cached_app_info().app_info.installed_app_id,
+        app_ids[0]
+    );
+    assert_eq!(
+        second.cached_app_info().app_info.installed_app_id,
+        app_ids[0]
+    );
+    assert_eq!(
+        other.cached_app_info().app_info.installed_app_id,
+        app_ids[1]
+    );
+    assert_eq!(client.admin().list_app_interfaces().await.unwrap().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn holochain_client_allowed_origins() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let allowed_origins = AllowedOrigins::Origins(["http://localhost:8888".to_string()].into());
+    let client = HolochainClient::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap()
+        .with_allowed_origins(allowed_origins.clone());
+
+    // The connection itself is rejected since the client does not connect from that origin, but
+    // the interface is attached with the configured origins.
+    let _ = client.app("test-app").await;
+    let interfaces = client.admin().list_app_interfaces().await.unwrap();
+    assert_eq!(interfaces.len(), 1);
+    assert_eq!(interfaces[0].allowed_origins, allowed_origins);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn paged_zome_call_streams_all_pages() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let numbers: Vec<u32> = app_ws
+        .paged_zome_call::<u32, u32, u32>(
+            "foo".to_string().into(),
+            "foo".into(),
+            "numbers".into(),
+            7,
+        )
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(numbers, (0..7).collect::<Vec<_>>());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn projection_applies_signals() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let projection = Projection::new(
+        &app_ws,
+        ProjectionQuery {
+            target: "foo".to_string().into(),
+            zome_name: "foo".into(),
+            fn_name: "foo".into(),
+            payload: ExternIO::encode(()).unwrap(),
+        },
+        |initial: TestString| vec![initial.0],
+        |view: &mut Vec<String>, signal: TestString| view.push(signal.0),
+    )
+    .await
+    .unwrap();
+    assert_eq!(projection.snapshot(), vec!["foo".to_string()]);
+
+    app_ws
+        .call_zome(
+            "foo".to_string().into(),
+            "foo".into(),
+            "emitter".into(),
+            ExternIO::encode(()).unwrap(),
+        )
+        .await
+        .unwrap();
+
+    tokio::time::timeout(std::time::Duration::from_secs(10), async {
+        while projection.read(|view| view.len()) < 2 {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(
+        projection.snapshot(),
+        vec!["foo".to_string(), "i am a signal".to_string()]
+    );
+
+    // Rebuilding queries the view again.
+    projection.rebuild(&app_ws).await.unwrap();
+    assert_eq!(projection.snapshot(), vec!["foo".to_string()]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn projection_ignores_signals_of_other_cells() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let client = HolochainClient::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+    let app_id: InstalledAppId = "test-app".into();
+    client
+        .admin()
+        .install_app(InstallAppPayload {
+            agent_key: None,
+            installed_app_id: Some(app_id.clone()),
+            network_seed: None,
+            roles_settings: None,
+            source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+            ignore_genesis_failure: false,
+            allow_throwaway_random_agent_key: false,
+        })
+        .await
+        .unwrap();
+    client.admin().enable_app(app_id.clone()).await.unwrap();
+    let clone_cell = client
+        .app(&app_id)
+        .await
+        .unwrap()
+        .create_clone_cell(CreateCloneCellPayload {
+            role_name: "foo".to_string(),
+            modifiers: DnaModifiersOpt::none().with_network_seed("clone".into()),
+            membrane_proof: None,
+            name: None,
+        })
+        .await
+        .unwrap();
+    // A new session authorizes signing for the clone cell too.
+    client.close_app(&app_id).await;
+    let app_ws = client.app(&app_id).await.unwrap();
+
+    let projection = Projection::new(
+        &app_ws,
+        ProjectionQuery {
+            target: "foo".to_string().into(),
+            zome_name: "foo".into(),
+            fn_name: "foo".into(),
+            payload: ExternIO::encode(()).unwrap(),
+        },
+        |initial: TestString| vec![initial.0],
+        |view: &mut Vec<String>, signal: TestString| view.push(signal.0),
+    )
+    .await
+    .unwrap();
+
+    // Signals arrive in order, so the signal of the clone cell has been handled once the signal
+    // of the provisioned cell is applied.
+    for target in [
+        clone_cell.clone_id.into(),
+        ZomeCallTarget::RoleName("foo".to_string()),
+    ] {
+        app_ws
+            .call_zome(
+                target,
+                "foo".into(),
+                "emitter".into(),
+                ExternIO::encode(()).unwrap(),
+            )
+            .await
+            .unwrap();
+    }
+    tokio::time::timeout(std::time::Duration::from_secs(10), async {
+        while projection.read(|view| view.len()) < 2 {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(
+        projection.snapshot(),
+        vec!["foo".to_string(), "i am a signal".to_string()]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn request_stats_count_zome_call_data() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let payload = ExternIO::encode(TestString("a".repeat(1_000))).unwrap();
+    let output = app_ws
+        .call_zome(
+            "foo".to_string().into(),
+            "foo".into(),
+            "echo".into(),
+            payload.clone(),
+        )
+        .await
+        .unwrap();
+
+    let stats = app_ws.stats();
+    let call_zome = &stats.operations["call_zome"];
+    assert_eq!(call_zome.count, 1);
+    // The serialized call contains the payload as well as the target and the signing details.
+    assert!(call_zome.bytes_sent > payload.0.len() as u64);
+    assert_eq!(call_zome.bytes_received, output.0.len() as u64);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn pause_and_resume_signals() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let (signal_tx, signal_rx) = std::sync::mpsc::channel();
+    let signal_tx = std::sync::Mutex::new(signal_tx);
+    app_ws
+        .on_signal(move |signal| {
+            signal_tx.lock().unwrap().send(signal).unwrap();
+        })
+        .await
+        .unwrap();
+
+    let emit = || {
+        app_ws.call_zome(
+            "foo".to_string().into(),
+            "foo".into(),
+            "emitter".into(),
+            ExternIO::encode(()).unwrap(),
+        )
+    };
+
+    app_ws.pause_signals();
+    assert!(app_ws.signals_paused());
+    emit().await.unwrap();
+    assert!(signal_rx
+        .recv_timeout(std::time::Duration::from_millis(500))
+        .is_err());
+
+    app_ws.resume_signals();
+    emit().await.unwrap();
+    signal_rx
+        .recv_timeout(std::time::Duration::from_secs(10))
+        .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn call_zome_stream_preserves_order() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let inputs = (0..20).map(|i| i.to_string()).collect::<Vec<_>>();
+    let payloads = futures::stream::iter(
+        inputs
+            .clone()
+            .into_iter()
+            .map(|input| ExternIO::encode(TestString(input)).unwrap()),
+    );
+    let outputs: Vec<ExternIO> = app_ws
+        .call_zome_stream(
+            "foo".to_string().into(),
+            "foo".into(),
+            "echo".into(),
+            payloads,
+            4,
+        )
+        .try_collect()
+        .await
+        .unwrap();
+    let outputs = outputs
+        .into_iter()
+        .map(|output| output.decode::<TestString>().unwrap().0)
+        .collect::<Vec<_>>();
+    assert_eq!(outputs, inputs);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn signer_profiles() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    let app_id: InstalledAppId = "test-app".into();
+    let app_info = admin_ws
+        .install_app(InstallAppPayload {
+            agent_key: None,
+            installed_app_id: Some(app_id.clone()),
+            network_seed: None,
+            roles_settings: None,
+            source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+            ignore_genesis_failure: false,
+            allow_throwaway_random_agent_key: false,
+        })
+        .await
+        .unwrap();
+    admin_ws.enable_app(app_id.clone()).await.unwrap();
+    let cell_id = match &app_info.cell_info.get("foo").unwrap()[0] {
+        CellInfo::Provisioned(cell) => cell.cell_id.clone(),
+        _ => panic!("wrong cell type"),
+    };
+
+    // The UI may call any function, background jobs may only call `bar`.
+    let ui_signer = ClientAgentSigner::default().profile("ui");
+    let jobs_signer = ui_signer.profile("background-jobs");
+    let ui_credentials = admin_ws
+        .authorize_signing_credentials(AuthorizeSigningCredentialsPayload {
+            cell_id: cell_id.clone(),
+            functions: None,
+        })
+        .await
+        .unwrap();
+    ui_signer.add_credentials(cell_id.clone(), ui_credentials);
+    let jobs_credentials = admin_ws
+        .authorize_signing_credentials(AuthorizeSigningCredentialsPayload {
+            cell_id: cell_id.clone(),
+            functions: Some(GrantedFunctions::Listed(BTreeSet::from([(
+                "foo".into(),
+                "bar".into(),
+            )]))),
+        })
+        .await
+        .unwrap();
+    jobs_signer.add_credentials(cell_id.clone(), jobs_credentials);
+    let mut profiles = ui_signer.profiles();
+    profiles.sort();
+    assert_eq!(profiles, vec!["background-jobs", "ui"]);
+
+    let app_ws_port = admin_ws
+        .attach_app_interface(0, AllowedOrigins::Any, None)
+        .await
+        .unwrap();
+    let token_issued = admin_ws
+        .issue_app_auth_token(app_id.clone().into())
+        .await
+        .unwrap();
+    let app_ws = AppWebsocket::connect(
+        (Ipv4Addr::LOCALHOST, app_ws_port),
+        token_issued.token,
+        ui_signer.clone().into(),
+    )
+    .await
+    .unwrap();
+    let call_foo = |app_ws: AppWebsocket| {
+        let cell_id = cell_id.clone();
+        async move {
+            app_ws
+                .call_zome(
+                    cell_id.into(),
+                    "foo".into(),
+                    "foo".into(),
+                    ExternIO::encode(()).unwrap(),
+                )
+                .await
+        }
+    };
+
+    call_foo(app_ws.clone()).await.unwrap();
+    let err = call_foo(app_ws.with_signer(jobs_signer.into()))
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.conductor_error().unwrap().kind,
+        ConductorErrorKind::ZomeCallUnauthorized
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn client_set_shuts_down_tasks_and_connections() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+
+    let (admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let mut clients = ClientSet::new();
+    clients.set_admin(admin_ws);
+    clients.add_app(app_ws.clone());
+    let task_state = Arc::new(());
+    clients.spawn({
+        let task_state = task_state.clone();
+        async move {
+            let _task_state = task_state;
+            std::future::pending::<()>().await;
+        }
+    });
+    assert_eq!(Arc::strong_count(&task_state), 2);
+
+    clients.shutdown().await;
+
+    // The task has been stopped and signals are no longer delivered.
+    assert_eq!(Arc::strong_count(&task_state), 1);
+    assert!(app_ws.signals_paused());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn composite_signer_falls_back_per_cell() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+    let cell_id = match &app_ws
+        .cached_app_info()
+        .app_info
+        .cell_info
+        .get("foo")
+        .unwrap()[0]
+    {
+        CellInfo::Provisioned(cell) => cell.cell_id.clone(),
+        _ => panic!("wrong cell type"),
+    };
+
+    // The primary signer has no credentials for the cell, so the fallback signs.
+    let primary = ClientAgentSigner::default();
+    let fallback = ClientAgentSigner::default();
+    let credentials = admin_ws
+        .authorize_signing_credentials(AuthorizeSigningCredentialsPayload {
+            cell_id: cell_id.clone(),
+            functions: None,
+        })
+        .await
+        .unwrap();
+    fallback.add_credentials(cell_id.clone(), credentials);
+    let signer = CompositeSigner::new()
+        .with_signer(primary.clone().into())
+        .with_signer(fallback.into());
+
+    let call_foo = |app_ws: AppWebsocket| {
+        let cell_id = cell_id.clone();
+        async move {
+            app_ws
+                .call_zome(
+                    cell_id.into(),
+                    "foo".into(),
+                    "foo".into(),
+                    ExternIO::encode(()).unwrap(),
+                )
+                .await
+        }
+    };
+    let response = call_foo(app_ws.with_signer(signer.into())).await.unwrap();
+    assert_eq!(response.decode::<String>().unwrap(), "foo");
+
+    // Without a signer that has credentials, the call can't be signed.
+    let signer = CompositeSigner::new().with_signer(primary.into());
+    let err = call_foo(app_ws.with_signer(signer.into()))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ConductorApiError::SignZomeCallError(_)));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn call_zome_with_deadline() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let called_at = Timestamp::now();
+    let outcome = app_ws
+        .call_zome_with_deadline(
+            "foo".to_string().into(),
+            "foo".into(),
+            "foo".into(),
+            ExternIO::encode(()).unwrap(),
+            std::time::Duration::from_secs(10),
+        )
+        .await
+        .unwrap();
+    assert_eq!(outcome.output.decode::<String>().unwrap(), "foo");
+    assert!(outcome.expires_at > called_at);
+    assert!(outcome.expires_at.as_micros() <= Timestamp::now().as_micros() + 10_000_000);
+
+    let err = app_ws
+        .call_zome_with_deadline(
+            "foo".to_string().into(),
+            "foo".into(),
+            "foo".into(),
+            ExternIO::encode(()).unwrap(),
+            std::time::Duration::ZERO,
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ConductorApiError::ZomeCallDeadlineExceeded { .. }
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn validate_zome_call_inputs() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+    let app_ws = app_ws.with_input_validators(
+        InputValidators::new()
+            .strict()
+            .with_type::<TestString>("foo", "echo"),
+    );
+
+    let output = app_ws
+        .call_zome_json(
+            "foo".to_string().into(),
+            "foo".into(),
+            "echo".into(),
+            serde_json::json!("hello"),
+        )
+        .await
+        .unwrap();
+    assert_eq!(output.decode::<TestString>().unwrap().0, "hello");
+
+    let err = app_ws
+        .call_zome_json(
+            "foo".to_string().into(),
+            "foo".into(),
+            "echo".into(),
+            serde_json::json!({ "value": 1 }),
+        )
+        .await
+        .unwrap_err();
+    let ConductorApiError::InvalidZomeCallInput {
+        fn_name, errors, ..
+    } = err
+    else {
+        panic!("expected an invalid input error, got {err:?}");
+    };
+    assert_eq!(fn_name, "echo".into());
+    assert_eq!(errors.len(), 1);
+
+    // Strict validators reject calls to functions without a validator.
+    let err = app_ws
+        .call_zome(
+            "foo".to_string().into(),
+            "foo".into(),
+            "foo".into(),
+            ExternIO::encode(()).unwrap(),
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ConductorApiError::InvalidZomeCallInput { .. }
+    ));
+}
+
+#[cfg(feature = "schema")]
+#[test]
+fn validate_inputs_against_json_schema() {
+    use holochain_client::JsonSchemaValidator;
+
+    let schema = serde_json::json!({
+        "type": "object",
+        "properties": {
+            "title": { "type": "string" },
+            "tags": { "type": "array", "items": { "type": "string" } }
+        },
+        "required": ["title"]
+    });
+    let validators = InputValidators::new().with_validator(
+        "foo",
+        "create_note",
+        JsonSchemaValidator::new(&schema).unwrap(),
+    );
+    let validate = |input: serde_json::Value| {
+        validators.validate(
+            &"foo".into(),
+            &"create_note".into(),
+            &ExternIO::encode(input).unwrap(),
+        )
+    };
+
+    validate(serde_json::json!({ "title": "note", "tags": ["a"] })).unwrap();
+
+    let err = validate(serde_json::json!({ "title": 1, "tags": ["a", 2] })).unwrap_err();
+    let ConductorApiError::InvalidZomeCallInput {
+        fn_name, errors, ..
+    } = err
+    else {
+        panic!("expected an invalid input error, got {err:?}");
+    };
+    assert_eq!(fn_name, "create_note".into());
+    assert_eq!(errors.len(), 2);
+    assert!(errors.iter().any(|error| error.starts_with("/title: ")));
+    assert!(errors.iter().any(|error| error.starts_with("/tags/1: ")));
+
+    // Problems with the input as a whole have no path.
+    let err = validate(serde_json::json!({ "tags": [] })).unwrap_err();
+    let ConductorApiError::InvalidZomeCallInput { errors, .. } = err else {
+        panic!("expected an invalid input error, got {err:?}");
+    };
+    assert_eq!(errors.len(), 1);
+    assert!(!errors[0].starts_with('/'));
+
+    assert!(JsonSchemaValidator::new(&serde_json::json!({ "type": 1 })).is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn call_zome_with_timeout() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let output = app_ws
+        .call_zome_with_timeout(
+            "foo".to_string().into(),
+            "foo".into(),
+            "foo".into(),
+            ExternIO::encode(()).unwrap(),
+            std::time::Duration::from_secs(10),
+        )
+        .await
+        .unwrap();
+    assert_eq!(output.decode::<String>().unwrap(), "foo");
+
+    let err = app_ws
+        .call_zome_with_timeout(
+            "foo".to_string().into(),
+            "foo".into(),
+            "foo".into(),
+            ExternIO::encode(()).unwrap(),
+            std::time::Duration::ZERO,
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ConductorApiError::RequestTimedOut { ref operation, .. } if operation == "call_zome"
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn connection_info() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let admin_info = admin_ws.connection_info();
+    assert_eq!(admin_info.addr, (Ipv4Addr::LOCALHOST, admin_port).into());
+    assert_eq!(admin_info.installed_app_id, None);
+
+    let app_info = app_ws.connection_info();
+    let app_port = admin_ws.list_app_interfaces().await.unwrap()[0].port;
+    assert_eq!(app_info.addr, (Ipv4Addr::LOCALHOST, app_port).into());
+    assert_eq!(app_info.installed_app_id, Some("test".to_string()));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn debug_history() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    // Disabled by default.
+    app_ws.app_info().await.unwrap();
+    assert!(app_ws.debug_history().is_empty());
+
+    app_ws.enable_debug_history(2);
+    app_ws.app_info().await.unwrap();
+    let call = |fn_name: &'static str| {
+        app_ws.call_zome(
+            "foo".to_string().into(),
+            "foo".into(),
+            fn_name.into(),
+            ExternIO::encode(()).unwrap(),
+        )
+    };
+    call("foo").await.unwrap();
+    call("missing_fn").await.unwrap_err();
+
+    let history = app_ws.debug_history();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].operation, "call_zome");
+    assert_eq!(history[0].outcome, RequestOutcome::Success);
+    assert_eq!(history[1].operation, "call_zome");
+    assert_eq!(history[1].outcome, RequestOutcome::ConductorError);
+    assert!(history[1].response.is_some());
+    // The calls carry the capability secret of the signing credentials.
+    assert_eq!(history[0].request, "<redacted>");
+
+    admin_ws.enable_debug_history(10);
+    admin_ws.list_apps(None).await.unwrap();
+    admin_ws
+        .issue_app_auth_token("test".to_string().into())
+        .await
+        .unwrap();
+    let history = admin_ws.debug_history();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].operation, "list_apps");
+    assert_ne!(history[0].response.as_deref(), Some("<redacted>"));
+    assert_eq!(history[1].operation, "issue_app_authentication_token");
+    assert_eq!(history[1].response.as_deref(), Some("<redacted>"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn disabled_app_guard() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+    let call = |app_ws: AppWebsocket| async move {
+        app_ws
+            .call_zome(
+                "foo".to_string().into(),
+                "foo".into(),
+                "foo".into(),
+                ExternIO::encode(()).unwrap(),
+            )
+            .await
+    };
+
+    admin_ws.disable_app("test".into()).await.unwrap();
+    app_ws.refresh_app_info().await.unwrap();
+    assert!(matches!(
+        call(app_ws.clone()).await,
+        Err(ConductorApiError::AppDisabled {
+            reason: DisabledAppReason::User
+        })
+    ));
+
+    // The cached app info is stale once the app is enabled again.
+    admin_ws.enable_app("test".into()).await.unwrap();
+    let cached_only = app_ws.with_disabled_app_guard(DisabledAppGuard::Cached);
+    assert!(matches!(
+        call(cached_only.clone()).await,
+        Err(ConductorApiError::AppDisabled { .. })
+    ));
+    // Confirming the status refreshes the cache, which is shared between clones.
+    call(app_ws.clone()).await.unwrap();
+    call(cached_only).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn build_and_verify_signed_zome_call() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let mut params = app_ws
+        .build_zome_call(
+            "foo".to_string().into(),
+            "foo".into(),
+            "foo".into(),
+            ExternIO::encode(()).unwrap(),
+        )
+        .unwrap();
+    assert_eq!(params.provenance, app_ws.my_pub_key);
+
+    // Sign the call outside of the client, as an external signer would.
+    let credentials = admin_ws
+        .authorize_signing_credentials(AuthorizeSigningCredentialsPayload {
+            cell_id: params.cell_id.clone(),
+            functions: None,
+        })
+        .await
+        .unwrap();
+    params.provenance = credentials.signing_agent_key.clone();
+    params.cap_secret = Some(credentials.cap_secret);
+    let serialized = SerializedZomeCall::new(&params).unwrap();
+    let signature = credentials.keypair.sign(&serialized.data_to_sign);
+    let signed = serialized.into_signed(Signature(signature.to_bytes()));
+
+    let verified = verify_signed_zome_call(&signed).unwrap();
+    assert_eq!(verified.provenance, params.provenance);
+    assert_eq!(verified.nonce, params.nonce);
+    let mut tampered = signed.clone();
+    tampered.signature.0[0] ^= 1;
+    assert!(verify_signed_zome_call(&tampered).is_err());
+
+    let response = app_ws.signed_call_zome(signed).await.unwrap();
+    assert_eq!(response.decode::<String>().unwrap(), "foo");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn detached_signing_batch() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let cell_id = app_ws
+        .build_zome_call(
+            "foo".to_string().into(),
+            "foo".into(),
+            "foo".into(),
+            ExternIO::encode(()).unwrap(),
+        )
+        .unwrap()
+        .cell_id;
+    // The keys of the air-gapped signer.
+    let credentials = admin_ws
+        .authorize_signing_credentials(AuthorizeSigningCredentialsPayload {
+            cell_id: cell_id.clone(),
+            functions: None,
+        })
+        .await
+        .unwrap();
+
+    let calls = (0..3).map(|_| {
+        let mut params = app_ws
+            .build_zome_call(
+                cell_id.clone().into(),
+                "foo".into(),
+                "foo".into(),
+                ExternIO::encode(()).unwrap(),
+            )
+            .unwrap();
+        params.provenance = credentials.signing_agent_key.clone();
+        params.cap_secret = Some(credentials.cap_secret);
+        params
+    });
+    let batch = DetachedSigningBatch::new(calls).unwrap();
+    assert_eq!(batch.len(), 3);
+
+    let signatures: Vec<Signature> = batch
+        .signing_requests()
+        .iter()
+        .map(|request| {
+            assert_eq!(request.provenance, credentials.signing_agent_key);
+            Signature(credentials.keypair.sign(&request.data_to_sign).to_bytes())
+        })
+        .collect();
+
+    let mut misordered = signatures.clone();
+    misordered.swap(0, 1);
+    assert!(batch.clone().into_signed(misordered).is_err());
+    assert!(batch.clone().into_signed(signatures[..2].to_vec()).is_err());
+
+    let signed_calls = batch.into_signed(signatures).unwrap();
+    for result in app_ws.signed_call_zomes(signed_calls).await {
+        assert_eq!(result.unwrap().decode::<String>().unwrap(), "foo");
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn app_info_of_authenticated_app() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let app_info = app_ws.app_info().await.unwrap().unwrap();
+    assert_eq!(app_info.installed_app_id, "test");
+    assert_eq!(app_info.agent_pub_key, app_ws.my_pub_key);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn discover_installed_app_id() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+    assert_eq!(app_ws.installed_app_id(), "test");
+
+    let other_app_id: InstalledAppId = "other-app".into();
+    admin_ws
+        .install_app(InstallAppPayload {
+            agent_key: None,
+            installed_app_id: Some(other_app_id.clone()),
+            network_seed: None,
+            roles_settings: None,
+            source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+            ignore_genesis_failure: false,
+            allow_throwaway_random_agent_key: false,
+        })
+        .await
+        .unwrap();
+    admin_ws.enable_app(other_app_id.clone()).await.unwrap();
+
+    // A client given only a port and a token finds out which app it serves.
+    let port = admin_ws
+        .attach_app_interface(0, AllowedOrigins::Any, None)
+        .await
+        .unwrap();
+    let token = admin_ws
+        .issue_app_auth_token(other_app_id.clone().into())
+        .await
+        .unwrap()
+        .token;
+    let other_app_ws = AppWebsocket::connect(
+        (Ipv4Addr::LOCALHOST, port),
+        token,
+        ClientAgentSigner::default().into(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(other_app_ws.installed_app_id(), other_app_id);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn signal_stream() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let mut signals = app_ws.signal_stream().await.unwrap();
+    for _ in 0..2 {
+        app_ws
+            .call_zome(
+                "foo".to_string().into(),
+                "foo".into(),
+                "emitter".into(),
+                ExternIO::encode(()).unwrap(),
+            )
+            .await
+            .unwrap();
+    }
+
+    for _ in 0..2 {
+        let signal = tokio::time::timeout(std::time::Duration::from_secs(5), signals.next())
+            .await
+            .unwrap()
+            .unwrap();
+        match signal {
+            Signal::App { signal, .. } => {
+                let ts: TestString = signal.into_inner().decode().unwrap();
+                assert_eq!(ts.0.as_str(), "i am a signal");
+            }
+            _ => panic!("Invalid signal"),
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn typed_signals() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let (typed_tx, typed_rx) = std::sync::mpsc::channel();
+    app_ws
+        .on_signal_typed::<TestString, _>(move |signal| typed_tx.send(signal).unwrap())
+        .await
+        .unwrap();
+    let (mistyped_tx, mistyped_rx) = std::sync::mpsc::channel();
+    app_ws
+        .on_signal_typed::<u32, _>(move |signal| mistyped_tx.send(signal).unwrap())
+        .await
+        .unwrap();
+    let mut signals = app_ws.signal_stream().await.unwrap();
+
+    app_ws
+        .call_zome(
+            "foo".to_string().into(),
+            "foo".into(),
+            "emitter".into(),
+            ExternIO::encode(()).unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let timeout = std::time::Duration::from_secs(5);
+    let typed = typed_rx.recv_timeout(timeout).unwrap().unwrap();
+    assert_eq!(typed.zome_name, "foo".into());
+    assert_eq!(typed.payload.0, "i am a signal");
+
+    match mistyped_rx.recv_timeout(timeout).unwrap() {
+        Err(SignalDecodeError::Decode { zome_name, .. }) => assert_eq!(zome_name, "foo".into()),
+        other => panic!("Expected a decode error, got {other:?}"),
+    }
+
+    let signal = tokio::time::timeout(timeout, signals.next())
+        .await
+        .unwrap()
+        .unwrap();
+    let payload: TestString = signal.into_app_payload().unwrap();
+    assert_eq!(payload.0, "i am a signal");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn system_signal_stream() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let mut system_signals = app_ws.system_signal_stream().await.unwrap();
+    let mut signals = app_ws.signal_stream().await.unwrap();
+    app_ws
+        .call_zome(
+            "foo".to_string().into(),
+            "foo".into(),
+            "emitter".into(),
+            ExternIO::encode(()).unwrap(),
+        )
+        .await
+        .unwrap();
+
+    tokio::time::timeout(std::time::Duration::from_secs(5), signals.next())
+        .await
+        .unwrap()
+        .unwrap();
+    // App signals are not system signals.
+    assert!(
+        tokio::time::timeout(std::time::Duration::from_millis(500), system_signals.next())
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn buffered_signal_stream_marks_gaps() {
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+    let emit = || {
+        app_ws.call_zome(
+            "foo".to_string().into(),
+            "foo".into(),
+            "emitter".into(),
+            ExternIO::encode(()).unwrap(),
+        )
+    };
+    let timeout = std::time::Duration::from_secs(10);
+
+    let mut signals = app_ws.buffered_signal_stream(1).await.unwrap();
+    for _ in 0..3 {
+        emit().await.unwrap();
+    }
+    // Let all signals arrive before reading any of them.
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    let event = tokio::time::timeout(timeout, signals.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(event, SignalEvent::Signal(Signal::App { .. })));
+    let event = tokio::time::timeout(timeout, signals.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        event,
+        SignalEvent::Gap(SignalGap::BufferFull { dropped: 2 })
+    );
+
+    // Reading made room in the buffer again.
+    emit().await.unwrap();
+    let event = tokio::time::timeout(timeout, signals.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(event, SignalEvent::Signal(Signal::App { .. })));
+
+    conductor.shutdown().await;
+    let event = tokio::time::timeout(timeout, signals.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(event, SignalEvent::Gap(SignalGap::Disconnected));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn broadcast_signals_to_subscribers() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let mut ui = app_ws.subscribe_signals().await.unwrap();
+    let mut indexer = app_ws.clone().subscribe_signals().await.unwrap();
+
+    app_ws
+        .call_zome(
+            "foo".to_string().into(),
+            "foo".into(),
+            "emitter".into(),
+            ExternIO::encode(()).unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let timeout = std::time::Duration::from_secs(5);
+    let ui_signal = tokio::time::timeout(timeout, ui.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    let indexer_signal = tokio::time::timeout(timeout, indexer.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(ui_signal, indexer_signal);
+    assert!(matches!(ui_signal, Signal::App { .. }));
+
+    // Each subscriber gets each signal once.
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    assert!(ui.try_recv().is_err());
+    assert!(indexer.try_recv().is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn unsubscribe_signal_subscription() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let received = Arc::new(AtomicUsize::new(0));
+    let subscription = app_ws
+        .signal_subscription({
+            let received = received.clone();
+            move |_| {
+                received.fetch_add(1, Ordering::SeqCst);
+            }
+        })
+        .await
+        .unwrap();
+    let stream = app_ws.buffered_signal_stream(8).await.unwrap();
+
+    let emit = || async {
+        app_ws
+            .call_zome(
+                "foo".to_string().into(),
+                "foo".into(),
+                "emitter".into(),
+                ExternIO::encode(()).unwrap(),
+            )
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    };
+
+    emit().await;
+    assert_eq!(received.load(Ordering::SeqCst), 1);
+
+    subscription.unsubscribe().await;
+    stream.unsubscribe().await;
+
+    emit().await;
+    assert_eq!(received.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn connect_with_websocket_config() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (admin_ws, _app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+    let websocket_config = Arc::new(WebsocketConfig {
+        default_request_timeout: std::time::Duration::from_secs(120),
+        ..WebsocketConfig::CLIENT_DEFAULT
+    });
+
+    let app_port = admin_ws.list_app_interfaces().await.unwrap()[0].port;
+    admin_ws
+        .issue_app_auth_token("test".to_string().into())
+        .await
+        .unwrap();
+    let app_ws = AppWebsocket::connect_with_config(
+        (Ipv4Addr::LOCALHOST, app_port),
+        issued.token,
+        ClientAgentSigner::default().into(),
+        websocket_config.clone(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(app_ws.installed_app_id(), "test");
+
+    // The config of a client applies to the app connections it makes.
+    let client =
+        HolochainClient::connect_with_config((Ipv4Addr::LOCALHOST, admin_port), websocket_config)
+            .await
+            .unwrap();
+    client
+        .app("test")
+        .await
+        .unwrap()
+        .call_zome(
+            "foo".to_string().into(),
+            "foo".into(),
+            "foo".into(),
+            ExternIO::encode(()).unwrap(),
+        )
+        .await
+        .unwrap();
 }
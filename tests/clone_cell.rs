@@ -1,16 +1,20 @@
 use std::net::Ipv4Addr;
 use std::path::PathBuf;
+use std::time::Duration;
 
+use futures::StreamExt;
 use holochain::{
-    prelude::{DeleteCloneCellPayload, DisableCloneCellPayload, EnableCloneCellPayload},
+    prelude::{DeleteCloneCellPayload, DisableCloneCellPayload, EnableCloneCellPayload, Signal},
     sweettest::SweetConductor,
 };
 use holochain_client::{
-    AdminWebsocket, AppWebsocket, AuthorizeSigningCredentialsPayload, ClientAgentSigner,
-    ConductorApiError, InstallAppPayload,
+    check_modifiers, origin_time_before_now, quickstart, AdminWebsocket, AppBundleBuilder,
+    AppWebsocket, AuthorizeSigningCredentialsPayload, ClientAgentSigner, CloneRef, CloneRefError,
+    ConductorApiError, HolochainClient, InstallAppPayload, ModifiersWarning,
 };
 use holochain_types::prelude::{
-    AppBundleSource, CloneCellId, CloneId, CreateCloneCellPayload, DnaModifiersOpt, InstalledAppId,
+    AppBundleSource, AppRoleDnaManifest, AppRoleManifest, CellProvisioning, CloneCellId, CloneId,
+    CreateCloneCellPayload, DnaBundle, DnaModifiersOpt, InstalledAppId, Timestamp,
 };
 use holochain_types::websocket::AllowedOrigins;
 use holochain_zome_types::{dependencies::holochain_integrity_types::ExternIO, prelude::RoleName};
@@ -206,7 +210,7 @@ pub async fn app_info_refresh() {
         .issue_app_auth_token(app_id.clone().into())
         .await
         .unwrap();
-    let mut app_agent_ws = AppWebsocket::connect(
+    let app_agent_ws = AppWebsocket::connect(
         (Ipv4Addr::LOCALHOST, app_api_port),
         token_issued.token,
         signer.clone().into(),
@@ -251,7 +255,19 @@ pub async fn app_info_refresh() {
     }
 
     // Refresh the app info, which means the app agent will now know about the clone cell
+    let cached_before_refresh = app_agent_ws.cached_app_info();
     app_agent_ws.refresh_app_info().await.unwrap();
+    let cached_after_refresh = app_agent_ws.cached_app_info();
+    assert!(cached_after_refresh.fetched_at > cached_before_refresh.fetched_at);
+    assert_eq!(
+        cached_after_refresh
+            .app_info
+            .cell_info
+            .get(&role_name)
+            .unwrap()
+            .len(),
+        2
+    );
 
     // Call the zome function on the clone cell again, expecting success
     app_agent_ws
@@ -264,3 +280,418 @@ pub async fn app_info_refresh() {
         .await
         .unwrap();
 }
+
+#[tokio::test(flavor = "multi_thread")]
+pub async fn clone_limit_is_checked_client_side() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let client = HolochainClient::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+
+    // The fixture role allows 10 clones.
+    let role_name: RoleName = "foo".into();
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+    assert_eq!(app_ws.clones_remaining(&role_name), Some(10));
+    app_ws
+        .create_clone_cell(CreateCloneCellPayload {
+            role_name: role_name.clone(),
+            modifiers: DnaModifiersOpt::none().with_network_seed("seed".into()),
+            membrane_proof: None,
+            name: None,
+        })
+        .await
+        .unwrap();
+    app_ws.refresh_app_info().await.unwrap();
+    assert_eq!(app_ws.clones_remaining(&role_name), Some(9));
+    assert_eq!(app_ws.clones_remaining(&"missing".into()), None);
+
+    // Roles added with the builder don't allow clones.
+    let bundle = AppBundleBuilder::new("no-clones")
+        .role_from_file("bar", "./fixture/test.dna")
+        .await
+        .unwrap()
+        .build()
+        .await
+        .unwrap();
+    let app_id: InstalledAppId = "no-clones".into();
+    client
+        .admin()
+        .install_app(InstallAppPayload {
+            agent_key: None,
+            installed_app_id: Some(app_id.clone()),
+            network_seed: None,
+            roles_settings: None,
+            source: AppBundleSource::Bundle(bundle),
+            ignore_genesis_failure: false,
+            allow_throwaway_random_agent_key: false,
+        })
+        .await
+        .unwrap();
+    client.admin().enable_app(app_id.clone()).await.unwrap();
+    let app_ws = client.app(&app_id).await.unwrap();
+
+    let role_name: RoleName = "bar".into();
+    assert_eq!(app_ws.clones_remaining(&role_name), Some(0));
+    let err = app_ws
+        .create_clone_cell(CreateCloneCellPayload {
+            role_name,
+            modifiers: DnaModifiersOpt::none().with_network_seed("seed".into()),
+            membrane_proof: None,
+            name: None,
+        })
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ConductorApiError::CloneLimitReached { limit: 0 }
+    ));
+}
+
+/// Install an app with a single role "bar" which allows one clone cell.
+async fn install_single_clone_app(client: &HolochainClient, app_id: &InstalledAppId) {
+    let role = AppRoleManifest {
+        name: "bar".into(),
+        provisioning: Some(CellProvisioning::Create { deferred: false }),
+        dna: AppRoleDnaManifest {
+            location: None,
+            modifiers: DnaModifiersOpt::none(),
+            installed_hash: None,
+            clone_limit: 1,
+        },
+    };
+    let dna = DnaBundle::read_from_file(&PathBuf::from("./fixture/test.dna"))
+        .await
+        .unwrap();
+    let bundle = AppBundleBuilder::new("single-clone")
+        .role_with_manifest(role, dna)
+        .build()
+        .await
+        .unwrap();
+    client
+        .admin()
+        .install_app(InstallAppPayload {
+            agent_key: None,
+            installed_app_id: Some(app_id.clone()),
+            network_seed: None,
+            roles_settings: None,
+            source: AppBundleSource::Bundle(bundle),
+            ignore_genesis_failure: false,
+            allow_throwaway_random_agent_key: false,
+        })
+        .await
+        .unwrap();
+    client.admin().enable_app(app_id.clone()).await.unwrap();
+}
+
+fn clone_payload(seed: &str) -> CreateCloneCellPayload {
+    CreateCloneCellPayload {
+        role_name: "bar".into(),
+        modifiers: DnaModifiersOpt::none().with_network_seed(seed.into()),
+        membrane_proof: None,
+        name: None,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+pub async fn clone_limit_is_checked_by_the_conductor() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let client = HolochainClient::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+    let app_id: InstalledAppId = "single-clone".into();
+    install_single_clone_app(&client, &app_id).await;
+
+    // Two connections, so that the second one's cached app info doesn't know about the clone the
+    // first one creates.
+    let app_ws = client.app(&app_id).await.unwrap();
+    client.close_app(&app_id).await;
+    let stale_app_ws = client.app(&app_id).await.unwrap();
+
+    app_ws.create_clone_cell(clone_payload("1")).await.unwrap();
+    assert_eq!(stale_app_ws.clones_remaining(&"bar".into()), Some(1));
+
+    // The request is sent and rejected by the conductor.
+    let err = stale_app_ws
+        .create_clone_cell(clone_payload("2"))
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, ConductorApiError::CloneLimitReached { limit: 1 }),
+        "{err:?}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+pub async fn disabled_clone_cells_do_not_count_towards_the_clone_limit() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let client = HolochainClient::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+    let app_id: InstalledAppId = "single-clone".into();
+    install_single_clone_app(&client, &app_id).await;
+    let app_ws = client.app(&app_id).await.unwrap();
+    let role_name: RoleName = "bar".into();
+
+    let clone_cell = app_ws.create_clone_cell(clone_payload("1")).await.unwrap();
+    app_ws.refresh_app_info().await.unwrap();
+    assert_eq!(app_ws.clones_remaining(&role_name), Some(0));
+
+    app_ws
+        .disable_clone_cell(DisableCloneCellPayload {
+            clone_cell_id: CloneCellId::CloneId(clone_cell.clone_id),
+        })
+        .await
+        .unwrap();
+    app_ws.refresh_app_info().await.unwrap();
+    assert_eq!(app_ws.clones_remaining(&role_name), Some(1));
+
+    // The conductor agrees that the disabled clone cell leaves room for another one.
+    app_ws.create_clone_cell(clone_payload("2")).await.unwrap();
+    app_ws.refresh_app_info().await.unwrap();
+    assert_eq!(app_ws.clones_remaining(&role_name), Some(0));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+pub async fn clone_cell_origin_time() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let origin_time = origin_time_before_now(Duration::from_secs(60));
+    assert!(origin_time < Timestamp::now());
+    let clone_cell = app_ws
+        .create_clone_cell(CreateCloneCellPayload {
+            role_name: "foo".into(),
+            modifiers: DnaModifiersOpt::none()
+                .with_network_seed("seed".into())
+                .with_origin_time(origin_time),
+            membrane_proof: None,
+            name: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(clone_cell.dna_modifiers.origin_time, origin_time);
+    assert!(check_modifiers(&clone_cell.dna_modifiers).is_empty());
+
+    let mut modifiers = clone_cell.dna_modifiers.clone();
+    modifiers.origin_time = Timestamp::from_micros(Timestamp::now().as_micros() + 3_600_000_000);
+    assert_eq!(
+        check_modifiers(&modifiers),
+        vec![ModifiersWarning::OriginTimeInFuture {
+            origin_time: modifiers.origin_time
+        }]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+pub async fn clone_refs() {
+    assert_eq!(
+        "foo.0".parse::<CloneRef>().unwrap(),
+        CloneRef::CloneId(CloneId::new(&"foo".into(), 0))
+    );
+    for invalid in ["foo", "foo.", ".0", "foo.-1", "foo.x"] {
+        assert_eq!(
+            invalid.parse::<CloneRef>().unwrap_err(),
+            CloneRefError::InvalidCloneId(invalid.to_string())
+        );
+    }
+
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let clone_cell = app_ws
+        .create_clone_cell(CreateCloneCellPayload {
+            role_name: "foo".into(),
+            modifiers: DnaModifiersOpt::none().with_network_seed("seed".into()),
+            membrane_proof: None,
+            name: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        CloneCellId::from(CloneRef::from(&clone_cell)),
+        CloneCellId::CloneId(clone_cell.clone_id.clone())
+    );
+    assert_eq!(
+        CloneRef::from(&clone_cell.cell_id),
+        CloneRef::DnaHash(clone_cell.cell_id.dna_hash().clone())
+    );
+
+    let clone_ref: CloneRef = "foo.0".parse().unwrap();
+    assert!(matches!(
+        app_ws.clone_cell_id(clone_ref.clone()),
+        Err(ConductorApiError::CellNotFound)
+    ));
+    app_ws.refresh_app_info().await.unwrap();
+    assert_eq!(
+        app_ws.clone_cell_id(clone_ref.clone()).unwrap(),
+        clone_cell.cell_id
+    );
+
+    app_ws.disable_clone(&clone_cell).await.unwrap();
+    let enabled_cell = app_ws.enable_clone(clone_ref).await.unwrap();
+    assert_eq!(enabled_cell, clone_cell);
+    app_ws
+        .disable_clone(clone_cell.cell_id.clone())
+        .await
+        .unwrap();
+    admin_ws
+        .delete_clone("test".into(), &clone_cell.cell_id)
+        .await
+        .unwrap();
+    assert!(app_ws.enable_clone(&clone_cell).await.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+pub async fn list_clone_cells() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+    let role_name: RoleName = "foo".into();
+    assert!(app_ws
+        .list_clone_cells(&role_name)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let mut clone_cells = Vec::new();
+    for network_seed in ["seed-1", "seed-2"] {
+        let clone_cell = app_ws
+            .create_clone_cell(CreateCloneCellPayload {
+                role_name: role_name.clone(),
+                modifiers: DnaModifiersOpt::none().with_network_seed(network_seed.into()),
+                membrane_proof: None,
+                name: None,
+            })
+            .await
+            .unwrap();
+        clone_cells.push(clone_cell);
+    }
+    app_ws.disable_clone(&clone_cells[0]).await.unwrap();
+
+    let listed = app_ws.list_clone_cells(&role_name).await.unwrap();
+    assert_eq!(listed.len(), 2);
+    let enabled: Vec<_> = clone_cells
+        .iter()
+        .map(|clone_cell| {
+            listed
+                .iter()
+                .find(|listed| listed.clone_id == clone_cell.clone_id)
+                .unwrap()
+                .enabled
+        })
+        .collect();
+    assert_eq!(enabled, vec![false, true]);
+    assert!(app_ws
+        .list_clone_cells(&"unknown".into())
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+pub async fn debounced_app_info_refresh() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+    let app_info_requests = || app_ws.stats().operations["app_info"].count;
+
+    for network_seed in ["seed-1", "seed-2", "seed-3"] {
+        app_ws
+            .create_clone_cell(CreateCloneCellPayload {
+                role_name: "foo".into(),
+                modifiers: DnaModifiersOpt::none().with_network_seed(network_seed.into()),
+                membrane_proof: None,
+                name: None,
+            })
+            .await
+            .unwrap();
+    }
+
+    let requests_before = app_info_requests();
+    let refreshed =
+        futures::future::join_all((0..3).map(|_| app_ws.refresh_app_info_debounced())).await;
+    assert_eq!(app_info_requests(), requests_before + 1);
+    for cached in refreshed {
+        assert_eq!(cached.unwrap().app_info.cell_info["foo"].len(), 4);
+    }
+
+    // Forcing a refresh always makes a request.
+    app_ws.refresh_app_info().await.unwrap();
+    assert_eq!(app_info_requests(), requests_before + 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+pub async fn signal_stream_filtered_by_cell() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let clone_cell = app_ws
+        .create_clone_cell(CreateCloneCellPayload {
+            role_name: "foo".into(),
+            modifiers: DnaModifiersOpt::none().with_network_seed("seed".into()),
+            membrane_proof: None,
+            name: None,
+        })
+        .await
+        .unwrap();
+    app_ws.refresh_app_info().await.unwrap();
+
+    let mut clone_signals = app_ws
+        .signal_stream_filtered(clone_cell.cell_id.clone(), Some("foo".into()))
+        .await
+        .unwrap();
+    let mut other_zome_signals = app_ws
+        .signal_stream_filtered(clone_cell.cell_id.clone(), Some("bar".into()))
+        .await
+        .unwrap();
+
+    for target in ["foo".to_string().into(), clone_cell.cell_id.clone().into()] {
+        app_ws
+            .call_zome(
+                target,
+                "foo".into(),
+                "emitter".into(),
+                ExternIO::encode(()).unwrap(),
+            )
+            .await
+            .unwrap();
+    }
+
+    let signal = tokio::time::timeout(Duration::from_secs(5), clone_signals.next())
+        .await
+        .unwrap()
+        .unwrap();
+    match signal {
+        Signal::App { cell_id, .. } => assert_eq!(cell_id, clone_cell.cell_id),
+        _ => panic!("Invalid signal"),
+    }
+    // Only the signal of the clone cell passed the filter.
+    assert!(
+        tokio::time::timeout(Duration::from_millis(500), clone_signals.next())
+            .await
+            .is_err()
+    );
+    assert!(
+        tokio::time::timeout(Duration::from_millis(500), other_zome_signals.next())
+            .await
+            .is_err()
+    );
+}
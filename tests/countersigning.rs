@@ -0,0 +1,49 @@
+use holochain::sweettest::SweetConductor;
+use holochain_client::{quickstart, AppWebsocket};
+use holochain_conductor_api::CellInfo;
+use holochain_zome_types::prelude::CellId;
+use std::net::Ipv4Addr;
+
+fn provisioned_cell_id(app_ws: &AppWebsocket) -> CellId {
+    match &app_ws.cached_app_info().app_info.cell_info["foo"][0] {
+        CellInfo::Provisioned(cell) => cell.cell_id.clone(),
+        _ => panic!("wrong cell type"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn countersigning_session_state() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+    let cell_id = provisioned_cell_id(&app_ws);
+
+    // No session has been started.
+    let state = app_ws
+        .get_countersigning_session_state(cell_id)
+        .await
+        .unwrap();
+    assert!(state.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn recover_without_countersigning_session() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+    let cell_id = provisioned_cell_id(&app_ws);
+
+    // There is no unresolved session to recover from.
+    assert!(app_ws
+        .abandon_countersigning_session(cell_id.clone())
+        .await
+        .is_err());
+    assert!(app_ws
+        .publish_countersigning_session(cell_id)
+        .await
+        .is_err());
+}
@@ -0,0 +1,51 @@
+use holochain::sweettest::SweetConductor;
+use holochain_client::{debug::Timeline, quickstart};
+use holochain_conductor_api::AppInfoStatus;
+use holochain_types::prelude::{CreateCloneCellPayload, DnaModifiersOpt};
+use std::{net::Ipv4Addr, time::Duration};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn timeline_records_snapshots() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let timeline = Timeline::new();
+    timeline
+        .around(
+            &app_ws,
+            "create_clone_cell",
+            app_ws.create_clone_cell(CreateCloneCellPayload {
+                role_name: "foo".into(),
+                modifiers: DnaModifiersOpt::none().with_network_seed("seed".into()),
+                membrane_proof: None,
+                name: None,
+            }),
+        )
+        .await
+        .unwrap();
+
+    let entries = timeline.entries();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].label, "create_clone_cell:before");
+    assert_eq!(entries[1].label, "create_clone_cell:after");
+    assert_eq!(entries[0].status, Some(AppInfoStatus::Running));
+    assert_eq!(entries[0].cells.len(), 1);
+    assert_eq!(entries[1].cells.len(), 2);
+    assert_eq!(entries[1].cells[1].kind, "cloned");
+    assert!(entries[1].requests > entries[0].requests);
+
+    let recorder = timeline.record_every(app_ws.clone(), Duration::from_millis(10));
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    drop(recorder);
+    // Let a snapshot that was in progress when the recorder was dropped finish.
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(timeline.entries().len() > 2);
+
+    let mut dump = Vec::new();
+    timeline.write_to(&mut dump).unwrap();
+    let lines = String::from_utf8(dump).unwrap();
+    assert_eq!(lines.lines().count(), timeline.entries().len());
+}
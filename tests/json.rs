@@ -0,0 +1,45 @@
+use holochain::sweettest::SweetConductor;
+use holochain_client::{
+    json::{JsonAppInfo, JsonAppStatus, JsonCellKind},
+    quickstart,
+};
+use serde::Serialize;
+use std::net::Ipv4Addr;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn app_info_as_json() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+    let app_info = app_ws.cached_app_info().app_info;
+
+    let json_app_info = JsonAppInfo::from(&app_info);
+    assert_eq!(json_app_info.status, JsonAppStatus::Running);
+    assert_eq!(json_app_info.cells.len(), 1);
+    assert_eq!(json_app_info.cells[0].kind, JsonCellKind::Provisioned);
+    assert_eq!(json_app_info.cells[0].role_name, "foo");
+
+    // The view can be embedded in another response.
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Response {
+        request_id: u32,
+        #[serde(flatten)]
+        app: JsonAppInfo,
+    }
+    let value = serde_json::to_value(Response {
+        request_id: 1,
+        app: json_app_info,
+    })
+    .unwrap();
+    assert_eq!(value["requestId"], 1);
+    assert_eq!(value["installedAppId"], "test");
+    assert_eq!(value["status"], "running");
+    assert_eq!(value["agentPubKey"], app_info.agent_pub_key.to_string());
+    assert!(value["cells"][0]["cellId"]["dnaHash"]
+        .as_str()
+        .unwrap()
+        .starts_with("uhC0k"));
+}
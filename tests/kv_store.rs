@@ -0,0 +1,235 @@
+use holochain::{
+    prelude::{AgentPubKey, CellId, DnaHash, Signal},
+    sweettest::SweetConductor,
+};
+use holochain_client::{
+    kv_store::{KvStore, MemoryKvStore},
+    quickstart,
+    sync::{KvLocalStore, LocalChange, LocalStore, SyncRecord},
+    AgentSigner, ClientAgentSigner, SignalJournal, SigningCredentials,
+};
+use holochain_zome_types::dependencies::holochain_integrity_types::ExternIO;
+use holochain_zome_types::prelude::Timestamp;
+use serde::{Deserialize, Serialize};
+use std::{net::Ipv4Addr, sync::Arc, time::Duration};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Note {
+    id: u32,
+    text: String,
+}
+
+impl SyncRecord for Note {
+    type Id = u32;
+
+    fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+#[tokio::test]
+async fn memory_kv_store() {
+    let kv = MemoryKvStore::new();
+    kv.put(b"a/2", b"two".to_vec()).await.unwrap();
+    kv.put(b"a/1", b"one".to_vec()).await.unwrap();
+    kv.put(b"b/1", b"other".to_vec()).await.unwrap();
+
+    assert_eq!(kv.get(b"a/1").await.unwrap(), Some(b"one".to_vec()));
+    assert_eq!(
+        kv.scan_prefix(b"a/").await.unwrap(),
+        vec![
+            (b"a/1".to_vec(), b"one".to_vec()),
+            (b"a/2".to_vec(), b"two".to_vec())
+        ]
+    );
+
+    kv.delete(b"a/1").await.unwrap();
+    assert_eq!(kv.get(b"a/1").await.unwrap(), None);
+    assert_eq!(kv.scan_prefix(b"a/").await.unwrap().len(), 1);
+}
+
+#[cfg(feature = "sled")]
+#[tokio::test]
+async fn sled_kv_store_round_trip() {
+    use holochain_client::kv_store::SledKvStore;
+
+    let dir = tempfile::tempdir().unwrap();
+    let kv = SledKvStore::open(dir.path()).unwrap();
+    kv.put(b"a/2", b"two".to_vec()).await.unwrap();
+    kv.put(b"a/1", b"one".to_vec()).await.unwrap();
+    kv.put(b"b/1", b"other".to_vec()).await.unwrap();
+    kv.put(b"a/3", b"three".to_vec()).await.unwrap();
+    kv.delete(b"a/3").await.unwrap();
+    kv.flush().await.unwrap();
+    drop(kv);
+
+    // The entries are read back from disk after reopening the database.
+    let kv = SledKvStore::open(dir.path()).unwrap();
+    assert_eq!(kv.get(b"a/1").await.unwrap(), Some(b"one".to_vec()));
+    assert_eq!(kv.get(b"a/3").await.unwrap(), None);
+    assert_eq!(
+        kv.scan_prefix(b"a/").await.unwrap(),
+        vec![
+            (b"a/1".to_vec(), b"one".to_vec()),
+            (b"a/2".to_vec(), b"two".to_vec())
+        ]
+    );
+}
+
+#[cfg(feature = "sled")]
+#[tokio::test]
+async fn offline_queue_in_sled_kv_store() {
+    use holochain_client::kv_store::SledKvStore;
+
+    let dir = tempfile::tempdir().unwrap();
+    let note = Note {
+        id: 1,
+        text: "queued".to_string(),
+    };
+
+    let kv = SledKvStore::open(dir.path()).unwrap();
+    let store = KvLocalStore::<_, Note>::new(kv.clone(), "notes");
+    let seq = store
+        .enqueue(LocalChange::Create(note.clone()))
+        .await
+        .unwrap();
+    store.save(vec![note.clone()]).await.unwrap();
+    kv.flush().await.unwrap();
+    drop(store);
+    drop(kv);
+
+    let reopened = KvLocalStore::<_, Note>::new(SledKvStore::open(dir.path()).unwrap(), "notes");
+    let queued = reopened.queued_changes().await.unwrap();
+    assert_eq!(queued.len(), 1);
+    assert_eq!(queued[0].seq, seq);
+    assert_eq!(queued[0].change, LocalChange::Create(note.clone()));
+    assert_eq!(reopened.records().await.unwrap(), vec![note]);
+}
+
+#[tokio::test]
+async fn offline_queue_in_kv_store() {
+    let kv = MemoryKvStore::new();
+    let store = KvLocalStore::<_, Note>::new(kv.clone(), "notes");
+    let note = |id, text: &str| Note {
+        id,
+        text: text.to_string(),
+    };
+
+    let first = store
+        .enqueue(LocalChange::Create(note(1, "first")))
+        .await
+        .unwrap();
+    let second = store
+        .enqueue(LocalChange::Update(note(1, "second")))
+        .await
+        .unwrap();
+    store.dequeue(first).await.unwrap();
+    store.save(vec![note(2, "saved")]).await.unwrap();
+    let pulled_at = Timestamp::now();
+    store.set_last_pulled_at(pulled_at).await.unwrap();
+
+    // A store over the same entries, as after a restart, sees the same state.
+    let reopened = KvLocalStore::<_, Note>::new(kv.clone(), "notes");
+    let queued = reopened.queued_changes().await.unwrap();
+    assert_eq!(queued.len(), 1);
+    assert_eq!(queued[0].seq, second);
+    assert_eq!(queued[0].change, LocalChange::Update(note(1, "second")));
+    assert_eq!(reopened.records().await.unwrap(), vec![note(2, "saved")]);
+    assert_eq!(reopened.last_pulled_at().await.unwrap(), Some(pulled_at));
+    assert!(
+        reopened
+            .enqueue(LocalChange::Create(note(3, "third")))
+            .await
+            .unwrap()
+            > second
+    );
+
+    // Stores in other namespaces are separate.
+    let other = KvLocalStore::<_, Note>::new(kv, "other");
+    assert!(other.queued_changes().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn signer_credentials_in_kv_store() {
+    let kv = MemoryKvStore::new();
+    let cell_id = CellId::new(
+        DnaHash::from_raw_32(vec![1; 32]),
+        AgentPubKey::from_raw_32(vec![2; 32]),
+    );
+    let signer = ClientAgentSigner::new().profile("ui");
+    signer.add_credentials(
+        cell_id.clone(),
+        SigningCredentials {
+            signing_agent_key: AgentPubKey::from_raw_32(vec![3; 32]),
+            keypair: ed25519_dalek::SigningKey::from_bytes(&[4; 32]),
+            cap_secret: [5; 64].into(),
+        },
+    );
+    signer.save_credentials(&kv).await.unwrap();
+
+    let restored = ClientAgentSigner::new();
+    assert_eq!(restored.load_credentials(&kv).await.unwrap(), 1);
+    let restored = restored.profile("ui");
+    assert_eq!(
+        restored.get_provenance(&cell_id),
+        signer.get_provenance(&cell_id)
+    );
+    assert_eq!(
+        restored.get_cap_secret(&cell_id),
+        signer.get_cap_secret(&cell_id)
+    );
+
+    let data: Arc<[u8]> = Arc::from(b"data".as_slice());
+    let provenance = signer.get_provenance(&cell_id).unwrap();
+    assert_eq!(
+        restored
+            .sign(&cell_id, provenance.clone(), data.clone())
+            .await
+            .unwrap(),
+        signer.sign(&cell_id, provenance, data).await.unwrap()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn journal_received_signals() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let journal = Arc::new(SignalJournal::new(MemoryKvStore::new(), "signals"));
+    let writer = app_ws.journal_signals(journal.clone()).await.unwrap();
+
+    for _ in 0..2 {
+        app_ws
+            .call_zome(
+                "foo".to_string().into(),
+                "foo".into(),
+                "emitter".into(),
+                ExternIO::encode(()).unwrap(),
+            )
+            .await
+            .unwrap();
+    }
+
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while journal.read_from(0).await.unwrap().len() < 2 {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .unwrap();
+    writer.abort();
+
+    let signals = journal.read_from(0).await.unwrap();
+    assert_eq!(
+        signals.iter().map(|(seq, _)| *seq).collect::<Vec<_>>(),
+        vec![0, 1]
+    );
+    assert!(matches!(signals[0].1, Signal::App { .. }));
+
+    journal.truncate(1).await.unwrap();
+    assert_eq!(journal.read_from(0).await.unwrap().len(), 1);
+    assert_eq!(journal.read_from(2).await.unwrap().len(), 0);
+}
@@ -0,0 +1,30 @@
+//! Builds payloads using only the types re-exported by the prelude.
+
+use holochain_client::prelude::*;
+use std::path::PathBuf;
+
+#[test]
+fn payloads_from_prelude() {
+    let install = InstallAppPayload {
+        agent_key: None,
+        installed_app_id: Some("test-app".into()),
+        network_seed: None,
+        roles_settings: None,
+        source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+        ignore_genesis_failure: false,
+        allow_throwaway_random_agent_key: false,
+    };
+    assert_eq!(install.installed_app_id.as_deref(), Some("test-app"));
+
+    let clone = CreateCloneCellPayload {
+        role_name: "foo".into(),
+        modifiers: DnaModifiersOpt::none().with_network_seed("seed".into()),
+        membrane_proof: None,
+        name: None,
+    };
+    let clone_id: CloneRef = "foo.0".parse().unwrap();
+    assert_eq!(
+        CloneCellId::from(clone_id),
+        CloneCellId::CloneId(CloneId::new(&clone.role_name, 0))
+    );
+}
@@ -0,0 +1,183 @@
+use holochain::sweettest::SweetConductor;
+use holochain_client::{
+    quickstart, AdminWebsocket, AppWebsocket, AuthorizeSigningCredentialsPayload,
+    ClientAgentSigner, ConnectOptions, ConnectionState, Projection, ProjectionQuery,
+    ReconnectPolicy,
+};
+use holochain_conductor_api::CellInfo;
+use holochain_zome_types::prelude::ExternIO;
+use std::{
+    net::Ipv4Addr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn app_websocket_reconnects_after_conductor_restart() {
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (admin_ws, _) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+    let (current_admin_ws, app_ws) = connect_reconnecting(admin_ws).await;
+    assert_eq!(app_ws.connection_state(), ConnectionState::Connected);
+
+    let received = Arc::new(AtomicUsize::new(0));
+    app_ws
+        .on_signal({
+            let received = received.clone();
+            move |_| {
+                received.fetch_add(1, Ordering::SeqCst);
+            }
+        })
+        .await
+        .unwrap();
+
+    conductor.shutdown().await;
+    wait_for_state(&app_ws, |state| state != ConnectionState::Connected).await;
+
+    conductor.startup().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    *current_admin_ws.lock().unwrap() = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+    wait_for_state(&app_ws, |state| state == ConnectionState::Connected).await;
+
+    // Requests and signal handlers work on the restored connection.
+    assert!(app_ws.app_info().await.unwrap().is_some());
+    app_ws
+        .call_zome(
+            "foo".to_string().into(),
+            "foo".into(),
+            "emitter".into(),
+            ExternIO::encode(()).unwrap(),
+        )
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(received.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn projection_is_rebuilt_after_reconnect() {
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (admin_ws, _) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+    let (current_admin_ws, app_ws) = connect_reconnecting(admin_ws).await;
+
+    let projection = Projection::new(
+        &app_ws,
+        ProjectionQuery {
+            target: "foo".to_string().into(),
+            zome_name: "foo".into(),
+            fn_name: "foo".into(),
+            payload: ExternIO::encode(()).unwrap(),
+        },
+        |initial: String| vec![initial],
+        |view: &mut Vec<String>, signal: String| view.push(signal),
+    )
+    .await
+    .unwrap();
+    app_ws
+        .call_zome(
+            "foo".to_string().into(),
+            "foo".into(),
+            "emitter".into(),
+            ExternIO::encode(()).unwrap(),
+        )
+        .await
+        .unwrap();
+    wait_for_view(&projection, |view| view.len() == 2).await;
+
+    conductor.shutdown().await;
+    wait_for_state(&app_ws, |state| state != ConnectionState::Connected).await;
+    conductor.startup().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    *current_admin_ws.lock().unwrap() = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+    wait_for_state(&app_ws, |state| state == ConnectionState::Connected).await;
+
+    // The view is queried again, which drops the update applied from the signal.
+    wait_for_view(&projection, |view| view == ["foo".to_string()]).await;
+}
+
+async fn wait_for_state(app_ws: &AppWebsocket, condition: impl Fn(ConnectionState) -> bool) {
+    tokio::time::timeout(Duration::from_secs(30), async {
+        while !condition(app_ws.connection_state()) {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .unwrap();
+}
+
+async fn wait_for_view(
+    projection: &Projection<Vec<String>>,
+    condition: impl Fn(&[String]) -> bool,
+) {
+    tokio::time::timeout(Duration::from_secs(30), async {
+        while !projection.read(|view| condition(view)) {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .unwrap();
+}
+
+/// Connect to the app installed by [quickstart] with a reconnect policy. Returns the admin
+/// connection tokens are issued through, to be replaced after the conductor restarts, since the
+/// admin interface gets a new port.
+async fn connect_reconnecting(
+    admin_ws: AdminWebsocket,
+) -> (Arc<Mutex<AdminWebsocket>>, AppWebsocket) {
+    let app_port = admin_ws.list_app_interfaces().await.unwrap()[0].port;
+    let app_info = admin_ws.list_apps(None).await.unwrap().remove(0);
+    let cell_id = match &app_info.cell_info["foo"][0] {
+        CellInfo::Provisioned(cell) => cell.cell_id.clone(),
+        _ => panic!("Expected a provisioned cell"),
+    };
+    let signer = ClientAgentSigner::default();
+    let credentials = admin_ws
+        .authorize_signing_credentials(AuthorizeSigningCredentialsPayload {
+            cell_id: cell_id.clone(),
+            functions: None,
+        })
+        .await
+        .unwrap();
+    signer.add_credentials(cell_id, credentials);
+
+    let current_admin_ws = Arc::new(Mutex::new(admin_ws));
+    let token_source = {
+        let current_admin_ws = current_admin_ws.clone();
+        move || {
+            let admin_ws = current_admin_ws.lock().unwrap().clone();
+            async move {
+                let issued = admin_ws
+                    .issue_app_auth_token("test".to_string().into())
+                    .await?;
+                anyhow::Ok(issued.token)
+            }
+        }
+    };
+    let policy = ReconnectPolicy {
+        initial_delay: Duration::from_millis(100),
+        max_delay: Duration::from_secs(1),
+        ..Default::default()
+    };
+    let app_ws = AppWebsocket::connect_with_token_source(
+        (Ipv4Addr::LOCALHOST, app_port),
+        Arc::new(token_source),
+        signer.into(),
+        ConnectOptions::default().with_reconnect(policy),
+    )
+    .await
+    .unwrap();
+
+    (current_admin_ws, app_ws)
+}
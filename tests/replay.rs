@@ -0,0 +1,72 @@
+use holochain::sweettest::SweetConductor;
+use holochain_client::{
+    quickstart, replay_zome_calls,
+    sync::{LocalChange, QueuedChange, SyncEndpoints},
+    RecordedZomeCall, ReplayErrorPolicy,
+};
+use holochain_zome_types::prelude::ExternIO;
+use serde::{Deserialize, Serialize};
+use std::net::Ipv4Addr;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct TestString(String);
+
+fn recorded_call(fn_name: &str, value: &str) -> RecordedZomeCall {
+    RecordedZomeCall {
+        role_name: "foo".to_string(),
+        zome_name: "foo".into(),
+        fn_name: fn_name.into(),
+        payload: ExternIO::encode(TestString(value.to_string())).unwrap(),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn replay_recorded_zome_calls() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let endpoints = SyncEndpoints {
+        target: "foo".to_string().into(),
+        zome_name: "foo".into(),
+        create_fn: "sync_create".into(),
+        update_fn: "sync_update".into(),
+        list_since_fn: "sync_list_since".into(),
+    };
+    let queued = RecordedZomeCall::from_queued_change(
+        &endpoints,
+        &QueuedChange {
+            seq: 0,
+            change: LocalChange::Create(TestString("queued".to_string())),
+        },
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(queued, recorded_call("sync_create", "queued"));
+
+    // Recorded calls survive a round trip through an audit log.
+    let log = serde_json::to_string(&vec![
+        recorded_call("echo", "first"),
+        recorded_call("does_not_exist", "second"),
+        queued,
+    ])
+    .unwrap();
+    let calls: Vec<RecordedZomeCall> = serde_json::from_str(&log).unwrap();
+
+    let report = replay_zome_calls(&app_ws, calls.clone(), ReplayErrorPolicy::Stop)
+        .await
+        .unwrap();
+    assert_eq!(report.replayed, 1);
+    assert_eq!(report.rejected.len(), 1);
+    assert_eq!(report.rejected[0].0, 1);
+    assert_eq!(report.stopped_at, Some(1));
+
+    let report = replay_zome_calls(&app_ws, calls, ReplayErrorPolicy::Skip)
+        .await
+        .unwrap();
+    assert_eq!(report.replayed, 2);
+    assert_eq!(report.rejected.len(), 1);
+    assert_eq!(report.stopped_at, None);
+}
@@ -0,0 +1,36 @@
+use holochain::sweettest::SweetConductor;
+use holochain_client::{quickstart, SimulatedLatency};
+use std::{
+    net::Ipv4Addr,
+    time::{Duration, Instant},
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn requests_are_delayed_by_simulated_latency() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let latency = SimulatedLatency::new(Duration::from_millis(300), Duration::from_millis(100));
+    admin_ws.set_simulated_latency(Some(latency));
+    app_ws.clone().set_simulated_latency(Some(latency));
+    assert_eq!(admin_ws.simulated_latency(), Some(latency));
+    // The latency is shared between clones.
+    assert_eq!(app_ws.simulated_latency(), Some(latency));
+
+    let started = Instant::now();
+    admin_ws.list_apps(None).await.unwrap();
+    assert!(started.elapsed() >= Duration::from_millis(300));
+
+    let started = Instant::now();
+    app_ws.app_info().await.unwrap();
+    assert!(started.elapsed() >= Duration::from_millis(300));
+
+    app_ws.set_simulated_latency(None);
+    assert_eq!(app_ws.simulated_latency(), None);
+    let started = Instant::now();
+    app_ws.app_info().await.unwrap();
+    assert!(started.elapsed() < Duration::from_millis(300));
+}
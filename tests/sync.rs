@@ -0,0 +1,151 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use holochain::sweettest::SweetConductor;
+use holochain_client::{
+    quickstart,
+    sync::{
+        ConflictHandler, LocalChange, LocalStore, QueuedChange, Resolution, SyncEndpoints,
+        SyncEngine, SyncReport,
+    },
+};
+use holochain_zome_types::prelude::Timestamp;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, net::Ipv4Addr};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct TestString(String);
+
+#[derive(Default)]
+struct MemoryStore {
+    records: Mutex<BTreeSet<String>>,
+    queue: Mutex<Vec<QueuedChange<TestString>>>,
+    last_pulled_at: Mutex<Option<Timestamp>>,
+}
+
+#[async_trait]
+impl LocalStore for MemoryStore {
+    type Id = String;
+    type Record = TestString;
+
+    fn record_id(record: &TestString) -> String {
+        record.0.clone()
+    }
+
+    async fn enqueue(&self, change: LocalChange<TestString>) -> Result<u64> {
+        let mut queue = self.queue.lock();
+        let seq = queue.last().map(|queued| queued.seq + 1).unwrap_or(0);
+        queue.push(QueuedChange { seq, change });
+        Ok(seq)
+    }
+
+    async fn queued_changes(&self) -> Result<Vec<QueuedChange<TestString>>> {
+        Ok(self.queue.lock().clone())
+    }
+
+    async fn dequeue(&self, seq: u64) -> Result<()> {
+        self.queue.lock().retain(|queued| queued.seq != seq);
+        Ok(())
+    }
+
+    async fn save(&self, records: Vec<TestString>) -> Result<()> {
+        self.records
+            .lock()
+            .extend(records.into_iter().map(|record| record.0));
+        Ok(())
+    }
+
+    async fn last_pulled_at(&self) -> Result<Option<Timestamp>> {
+        Ok(*self.last_pulled_at.lock())
+    }
+
+    async fn set_last_pulled_at(&self, pulled_at: Timestamp) -> Result<()> {
+        *self.last_pulled_at.lock() = Some(pulled_at);
+        Ok(())
+    }
+}
+
+fn endpoints() -> SyncEndpoints {
+    SyncEndpoints {
+        target: "foo".to_string().into(),
+        zome_name: "foo".into(),
+        create_fn: "sync_create".into(),
+        update_fn: "sync_update".into(),
+        list_since_fn: "sync_list_since".into(),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sync_pushes_queued_changes_and_pulls_remote_records() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let store = MemoryStore::default();
+    store
+        .enqueue(LocalChange::Create(TestString("local".to_string())))
+        .await
+        .unwrap();
+    // Conflicts with the record the fixture lists as remote.
+    store
+        .enqueue(LocalChange::Update(TestString("remote".to_string())))
+        .await
+        .unwrap();
+
+    let engine = SyncEngine::new(app_ws, store, endpoints());
+    let report = engine.sync_once().await.unwrap();
+    assert_eq!(
+        report,
+        SyncReport {
+            pushed: 2,
+            pulled: 1,
+            conflicts: 1,
+            rejected: 0,
+        }
+    );
+    assert!(engine.store().queue.lock().is_empty());
+    assert_eq!(
+        *engine.store().records.lock(),
+        BTreeSet::from(["local".to_string(), "remote".to_string()])
+    );
+    assert!(engine.store().last_pulled_at.lock().is_some());
+}
+
+struct KeepRemote;
+
+impl ConflictHandler<TestString> for KeepRemote {
+    fn resolve(
+        &self,
+        _local: &LocalChange<TestString>,
+        _remote: &TestString,
+    ) -> Resolution<TestString> {
+        Resolution::KeepRemote
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sync_conflict_handler_can_keep_remote() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (_admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+
+    let store = MemoryStore::default();
+    store
+        .enqueue(LocalChange::Update(TestString("remote".to_string())))
+        .await
+        .unwrap();
+
+    let engine = SyncEngine::new(app_ws, store, endpoints()).with_conflict_handler(KeepRemote);
+    let report = engine.sync_once().await.unwrap();
+    assert_eq!(report.conflicts, 1);
+    assert_eq!(report.pushed, 0);
+    assert!(engine.store().queue.lock().is_empty());
+    assert_eq!(
+        *engine.store().records.lock(),
+        BTreeSet::from(["remote".to_string()])
+    );
+}
@@ -0,0 +1,354 @@
+use holochain::prelude::{DnaModifiersOpt, RoleSettings};
+use holochain::{prelude::AppBundleSource, sweettest::SweetConductor};
+use holochain_client::{
+    quickstart,
+    test_utils::{lose_signing_grants, ConductorSnapshot, TestNetwork, TestScope},
+    AdminWebsocket, AppWebsocket, AuthorizeSigningCredentialsPayload, ClientAgentSigner,
+    ConductorApiResult, ConductorErrorKind, InstallAppPayload, InstalledAppId, SigningCredentials,
+};
+use holochain_conductor_api::{AppInfoStatus, CellInfo};
+use holochain_types::websocket::AllowedOrigins;
+use holochain_zome_types::prelude::{CellId, ExternIO};
+use std::{collections::HashMap, net::Ipv4Addr, path::PathBuf};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn restore_conductor_snapshot() {
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let admin_ws = AdminWebsocket::from_sweet_conductor(&conductor)
+        .await
+        .unwrap();
+
+    let app_id: InstalledAppId = "test-app".into();
+    let app_info = admin_ws
+        .install_app(InstallAppPayload {
+            agent_key: None,
+            installed_app_id: Some(app_id.clone()),
+            network_seed: None,
+            roles_settings: Some(HashMap::from([(
+                "foo".to_string(),
+                RoleSettings::Provisioned {
+                    membrane_proof: Default::default(),
+                    modifiers: Some(
+                        DnaModifiersOpt::default().with_network_seed("snapshot seed".to_string()),
+                    ),
+                },
+            )])),
+            source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+            ignore_genesis_failure: false,
+            allow_throwaway_random_agent_key: false,
+        })
+        .await
+        .unwrap();
+    admin_ws.enable_app(app_id.clone()).await.unwrap();
+    drop(admin_ws);
+
+    let snapshot = ConductorSnapshot::capture(&mut conductor).await.unwrap();
+    assert_eq!(snapshot.apps().len(), 1);
+    assert!(snapshot.apps()[0].enabled);
+
+    // Changes to the original conductor after capturing are not part of the snapshot.
+    AdminWebsocket::from_sweet_conductor(&conductor)
+        .await
+        .unwrap()
+        .uninstall_app(app_id.clone(), false)
+        .await
+        .unwrap();
+
+    // Every restored conductor starts from its own copy, with the app installed and enabled.
+    for _ in 0..2 {
+        let restored = snapshot.restore().await.unwrap();
+        let restored_admin_ws = AdminWebsocket::from_sweet_conductor(&restored)
+            .await
+            .unwrap();
+
+        let apps = restored_admin_ws.list_apps(None).await.unwrap();
+        assert_eq!(apps.len(), 1);
+        assert_eq!(apps[0].installed_app_id, app_id);
+        assert_eq!(apps[0].agent_pub_key, app_info.agent_pub_key);
+        assert_eq!(apps[0].status, AppInfoStatus::Running);
+        let role = apps[0]
+            .manifest
+            .app_roles()
+            .into_iter()
+            .find(|r| r.name == "foo")
+            .unwrap();
+        assert_eq!(
+            role.dna.modifiers.network_seed,
+            Some("snapshot seed".to_string())
+        );
+
+        restored_admin_ws.disable_app(app_id.clone()).await.unwrap();
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_scopes_do_not_collide() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let connect = || AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port));
+
+    let scope_1 = TestScope::new(connect().await.unwrap());
+    let scope_2 = TestScope::new(connect().await.unwrap());
+    let payload = || InstallAppPayload {
+        agent_key: None,
+        installed_app_id: None,
+        network_seed: None,
+        roles_settings: None,
+        source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+        ignore_genesis_failure: false,
+        allow_throwaway_random_agent_key: false,
+    };
+
+    let app_1 = scope_1.install_app(payload()).await.unwrap();
+    let app_2 = scope_2.install_app(payload()).await.unwrap();
+    assert_ne!(app_1.installed_app_id, app_2.installed_app_id);
+
+    let port_1 = scope_1
+        .attach_app_interface(AllowedOrigins::Any, app_1.installed_app_id.clone())
+        .await
+        .unwrap();
+    let port_2 = scope_2
+        .attach_app_interface(AllowedOrigins::Any, app_2.installed_app_id.clone())
+        .await
+        .unwrap();
+    assert_ne!(port_1, port_2);
+    assert_ne!(port_1, 0);
+    // Interfaces can only be bound to apps of the same scope.
+    assert!(scope_1
+        .attach_app_interface(AllowedOrigins::Any, app_2.installed_app_id.clone())
+        .await
+        .is_err());
+
+    let admin_ws = connect().await.unwrap();
+    assert_eq!(admin_ws.list_apps(None).await.unwrap().len(), 2);
+
+    scope_1.cleanup().await.unwrap();
+    let apps = admin_ws.list_apps(None).await.unwrap();
+    assert_eq!(apps.len(), 1);
+    assert_eq!(apps[0].installed_app_id, app_2.installed_app_id);
+
+    scope_2.cleanup().await.unwrap();
+    assert!(admin_ws.list_apps(None).await.unwrap().is_empty());
+
+    // The interfaces stay attached, but are bound to apps which no longer exist.
+    let interfaces = admin_ws.list_app_interfaces().await.unwrap();
+    for port in [port_1, port_2] {
+        let interface = interfaces.iter().find(|i| i.port == port).unwrap();
+        assert!(interface.installed_app_id.is_some());
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_scope_cleanup_attempts_every_app() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_ws = AdminWebsocket::from_sweet_conductor(&conductor)
+        .await
+        .unwrap();
+    let scope = TestScope::new(admin_ws.clone());
+    let payload = || InstallAppPayload {
+        agent_key: None,
+        installed_app_id: None,
+        network_seed: None,
+        roles_settings: None,
+        source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+        ignore_genesis_failure: false,
+        allow_throwaway_random_agent_key: false,
+    };
+    let app_1 = scope.install_app(payload()).await.unwrap();
+    let app_2 = scope.install_app(payload()).await.unwrap();
+
+    // Uninstalling the first app behind the scope's back makes its cleanup fail.
+    admin_ws
+        .uninstall_app(app_1.installed_app_id.clone(), false)
+        .await
+        .unwrap();
+
+    let err = scope.cleanup().await.unwrap_err();
+    assert!(err.to_string().contains(&app_1.installed_app_id));
+    assert!(!err.to_string().contains(&app_2.installed_app_id));
+    assert!(admin_ws.list_apps(None).await.unwrap().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn partition_test_network() {
+    let mut network = TestNetwork::new();
+    let mut conductors = Vec::new();
+    for _ in 0..3 {
+        let conductor = SweetConductor::from_standard_config().await;
+        let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+        let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+            .await
+            .unwrap();
+        let app_info = admin_ws
+            .install_app(InstallAppPayload {
+                agent_key: None,
+                installed_app_id: None,
+                network_seed: Some("partition".to_string()),
+                roles_settings: None,
+                source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+                ignore_genesis_failure: false,
+                allow_throwaway_random_agent_key: false,
+            })
+            .await
+            .unwrap();
+        admin_ws
+            .enable_app(app_info.installed_app_id.clone())
+            .await
+            .unwrap();
+        network.add_node(&conductor, admin_ws, app_info.installed_app_id);
+        conductors.push(conductor);
+    }
+
+    network.exchange_peer_info().await.unwrap();
+    let agent_infos = network.admin_ws(0).agent_info(None).await.unwrap();
+    assert_eq!(agent_infos.len(), 3);
+
+    // A partition keeps both sides online.
+    network.partition(&[0, 1], &[2]).await.unwrap();
+    assert!(network.is_partitioned(0, 2));
+    assert!(network.is_partitioned(2, 1));
+    assert!(!network.is_partitioned(0, 1));
+    for node in 0..3 {
+        assert!(network.is_online(node));
+        let apps = network.admin_ws(node).list_apps(None).await.unwrap();
+        assert_eq!(apps[0].status, AppInfoStatus::Running);
+    }
+    assert!(network.partition(&[0], &[0, 1]).await.is_err());
+
+    network.take_offline(1).await.unwrap();
+    assert!(!network.is_online(1));
+    let apps = network.admin_ws(1).list_apps(None).await.unwrap();
+    assert!(matches!(apps[0].status, AppInfoStatus::Disabled { .. }));
+
+    // Healing removes the partition and brings offline nodes back.
+    network.heal().await.unwrap();
+    assert!(!network.is_partitioned(0, 2));
+    assert!(network.is_online(1));
+    let apps = network.admin_ws(1).list_apps(None).await.unwrap();
+    assert_eq!(apps[0].status, AppInfoStatus::Running);
+    let agent_infos = network.admin_ws(2).agent_info(None).await.unwrap();
+    assert_eq!(agent_infos.len(), 3);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn connect_from_sweet_conductor() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_ws = AdminWebsocket::from_sweet_conductor(&conductor)
+        .await
+        .unwrap();
+
+    let app_id: InstalledAppId = "test-app".into();
+    admin_ws
+        .install_app(InstallAppPayload {
+            agent_key: None,
+            installed_app_id: Some(app_id.clone()),
+            network_seed: None,
+            roles_settings: None,
+            source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+            ignore_genesis_failure: false,
+            allow_throwaway_random_agent_key: false,
+        })
+        .await
+        .unwrap();
+    admin_ws.enable_app(app_id.clone()).await.unwrap();
+
+    let app_ws = AppWebsocket::from_sweet_conductor(
+        &conductor,
+        app_id.clone(),
+        ClientAgentSigner::default().into(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        app_ws.app_info().await.unwrap().unwrap().installed_app_id,
+        app_id
+    );
+}
+
+async fn authorize(admin_ws: &AdminWebsocket, cell_id: &CellId) -> SigningCredentials {
+    admin_ws
+        .authorize_signing_credentials(AuthorizeSigningCredentialsPayload {
+            cell_id: cell_id.clone(),
+            functions: None,
+        })
+        .await
+        .unwrap()
+}
+
+async fn call_foo(app_ws: &AppWebsocket, cell_id: &CellId) -> ConductorApiResult<ExternIO> {
+    app_ws
+        .call_zome(
+            cell_id.clone().into(),
+            "foo".into(),
+            "foo".into(),
+            ExternIO::encode(()).unwrap(),
+        )
+        .await
+}
+
+fn assert_unauthorized(result: ConductorApiResult<ExternIO>) {
+    let kind = result.unwrap_err().conductor_error().unwrap().kind;
+    assert_eq!(kind, ConductorErrorKind::ZomeCallUnauthorized);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reauthorize_after_signing_grant_loss() {
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (admin_ws, app_ws) = quickstart((Ipv4Addr::LOCALHOST, admin_port), "./fixture/test.happ")
+        .await
+        .unwrap();
+    let cell_id = match &app_ws
+        .cached_app_info()
+        .app_info
+        .cell_info
+        .get("foo")
+        .unwrap()[0]
+    {
+        CellInfo::Provisioned(cell) => cell.cell_id.clone(),
+        _ => panic!("wrong cell type"),
+    };
+
+    let signer = ClientAgentSigner::default();
+    signer.add_credentials(cell_id.clone(), authorize(&admin_ws, &cell_id).await);
+    let app_ws = app_ws.with_signer(signer.clone().into());
+    call_foo(&app_ws, &cell_id).await.unwrap();
+    drop(app_ws);
+    drop(admin_ws);
+
+    lose_signing_grants(
+        &mut conductor,
+        [(
+            "test".to_string(),
+            AppBundleSource::Path("./fixture/test.happ".into()),
+        )],
+    )
+    .await
+    .unwrap();
+
+    // The reset conductor has the same cell, but no grant for the credentials of the signer.
+    let admin_ws = AdminWebsocket::from_sweet_conductor(&conductor)
+        .await
+        .unwrap();
+    let app_port = admin_ws
+        .attach_app_interface(0, AllowedOrigins::Any, None)
+        .await
+        .unwrap();
+    let token = admin_ws
+        .issue_app_auth_token("test".to_string().into())
+        .await
+        .unwrap()
+        .token;
+    let app_ws = AppWebsocket::connect(
+        (Ipv4Addr::LOCALHOST, app_port),
+        token,
+        signer.clone().into(),
+    )
+    .await
+    .unwrap();
+    assert_unauthorized(call_foo(&app_ws, &cell_id).await);
+
+    // Authorizing new credentials restores access.
+    signer.add_credentials(cell_id.clone(), authorize(&admin_ws, &cell_id).await);
+    call_foo(&app_ws, &cell_id).await.unwrap();
+}
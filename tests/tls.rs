@@ -0,0 +1,163 @@
+//! Connects to a conductor through a reverse proxy which terminates TLS with a certificate issued
+//! by a certificate authority of the test.
+
+use holochain::sweettest::SweetConductor;
+use holochain_client::{AdminWebsocket, ConnectOptions, ConnectRetry, TlsOptions};
+use rcgen::{BasicConstraints, CertificateParams, IsCa, KeyPair};
+use std::{net::Ipv4Addr, sync::Arc};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+use tokio_rustls::{
+    rustls::{
+        pki_types::{PrivateKeyDer, PrivatePkcs8KeyDer},
+        ClientConfig, RootCertStore, ServerConfig,
+    },
+    TlsAcceptor,
+};
+
+/// The path the proxy serves the admin websocket of the conductor at.
+const ADMIN_PATH: &str = "/conductor/admin";
+
+/// Issue a certificate for the proxy from a new certificate authority, returning the server
+/// configuration of the proxy and a client configuration trusting the certificate authority.
+fn issue_certificate() -> (ServerConfig, ClientConfig) {
+    let ca_key = KeyPair::generate().unwrap();
+    let mut ca_params = CertificateParams::new(Vec::<String>::new()).unwrap();
+    ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    let ca_cert = ca_params.self_signed(&ca_key).unwrap();
+
+    let server_key = KeyPair::generate().unwrap();
+    let server_cert =
+        CertificateParams::new(vec!["localhost".to_string(), "127.0.0.1".to_string()])
+            .unwrap()
+            .signed_by(&server_key, &ca_cert, &ca_key)
+            .unwrap();
+    let server_config = ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(
+            vec![server_cert.der().clone()],
+            PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(server_key.serialize_der())),
+        )
+        .unwrap();
+
+    let mut roots = RootCertStore::empty();
+    roots.add(ca_cert.der().clone()).unwrap();
+    let client_config = ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+
+    (server_config, client_config)
+}
+
+/// Start a proxy which terminates TLS and forwards websocket handshakes for [ADMIN_PATH] to the
+/// admin port of the conductor, with the path rewritten to `/`. Handshakes for another path or
+/// host are dropped. Returns the port of the proxy.
+async fn start_proxy(server_config: ServerConfig, admin_port: u16) -> u16 {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let acceptor = TlsAcceptor::from(Arc::new(server_config));
+    tokio::spawn(async move {
+        while let Ok((tcp, _)) = listener.accept().await {
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                let _ = forward(acceptor, tcp, port, admin_port).await;
+            });
+        }
+    });
+    port
+}
+
+async fn forward(
+    acceptor: TlsAcceptor,
+    tcp: TcpStream,
+    port: u16,
+    admin_port: u16,
+) -> std::io::Result<()> {
+    let mut tls = acceptor.accept(tcp).await?;
+    // The websocket waits for the response to its handshake before it sends anything else.
+    let mut head = Vec::new();
+    while !head.ends_with(b"\r\n\r\n") {
+        head.push(tls.read_u8().await?);
+    }
+    let head = String::from_utf8(head).map_err(std::io::Error::other)?;
+
+    let request_line = format!("GET {ADMIN_PATH} HTTP/1.1\r\n");
+    let host = format!("\r\nhost: 127.0.0.1:{port}\r\n");
+    if !head.starts_with(&request_line)
+        || !head.to_ascii_lowercase().contains(&host)
+        || head
+            .to_ascii_lowercase()
+            .contains("x-holochain-client-tunnel")
+    {
+        return Ok(());
+    }
+
+    let mut conductor = TcpStream::connect((Ipv4Addr::LOCALHOST, admin_port)).await?;
+    conductor
+        .write_all(
+            head.replacen(&request_line, "GET / HTTP/1.1\r\n", 1)
+                .as_bytes(),
+        )
+        .await?;
+    tokio::io::copy_bidirectional(&mut tls, &mut conductor).await?;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn connect_through_tls_proxy() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (server_config, client_config) = issue_certificate();
+    let proxy_port = start_proxy(server_config, admin_port).await;
+
+    let options = ConnectOptions::default()
+        .with_tls(TlsOptions::new("127.0.0.1").with_client_config(Arc::new(client_config)));
+    let admin_ws = AdminWebsocket::connect_url(
+        &format!("wss://127.0.0.1:{proxy_port}{ADMIN_PATH}"),
+        options,
+    )
+    .await
+    .unwrap();
+
+    let apps = admin_ws.list_apps(None).await.unwrap();
+    assert!(apps.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn untrusted_certificate_is_rejected() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (server_config, _) = issue_certificate();
+    let proxy_port = start_proxy(server_config, admin_port).await;
+
+    // The certificate authority of the test isn't one of the Mozilla root certificates.
+    let options = ConnectOptions::default().with_retry(ConnectRetry::Disabled);
+    let result = AdminWebsocket::connect_url(
+        &format!("wss://127.0.0.1:{proxy_port}{ADMIN_PATH}"),
+        options,
+    )
+    .await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn wrong_path_is_not_forwarded() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let (server_config, client_config) = issue_certificate();
+    let proxy_port = start_proxy(server_config, admin_port).await;
+
+    let options = ConnectOptions::default()
+        .with_retry(ConnectRetry::Disabled)
+        .with_tls(TlsOptions::new("127.0.0.1").with_client_config(Arc::new(client_config)));
+    let result = AdminWebsocket::connect_url(
+        &format!("wss://127.0.0.1:{proxy_port}/conductor/app"),
+        options,
+    )
+    .await;
+
+    assert!(result.is_err());
+}
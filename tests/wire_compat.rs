@@ -0,0 +1,236 @@
+//! Guards the wire encoding of conductor API messages against silent changes.
+//!
+//! A set of requests is sent to a conductor and the encoding of each request and of the response
+//! the conductor sent back is recorded in a transcript per conductor API release line, such as
+//! `tests/transcripts/0.5.json`. Bumping the conductor API to another point release of the same
+//! line must not change the encoding, so any difference fails this test. A new release line
+//! starts a new transcript.
+//!
+//! Only requests whose responses are the same on every run are recorded, so no response contains
+//! a generated key, a port or a timestamp. Responses are received decoded, and are recorded
+//! encoded again, which also catches a change in how they are decoded.
+//!
+//! A missing transcript fails the test. To record a transcript for a new release line, or to
+//! deliberately re-record one, run the test with `RECORD_TRANSCRIPTS=1` and commit the result.
+
+use holochain::{prelude::AppBundleSource, sweettest::SweetConductor};
+use holochain_client::{AdminWebsocket, InstallAppPayload, InstalledAppId, WebsocketConfig};
+use holochain_conductor_api::{
+    AdminRequest, AdminResponse, AppAuthenticationRequest, AppRequest, AppResponse,
+};
+use holochain_types::{prelude::encode, websocket::AllowedOrigins};
+use holochain_websocket::WebsocketSender;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    net::{Ipv4Addr, SocketAddr},
+    path::PathBuf,
+    sync::Arc,
+};
+
+/// The version of the conductor API this crate is built with, as resolved by Cargo.
+fn conductor_api_version() -> String {
+    let output = std::process::Command::new(env!("CARGO"))
+        .args(["metadata", "--format-version", "1", "--manifest-path"])
+        .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"))
+        .output()
+        .expect("cargo metadata runs");
+    assert!(
+        output.status.success(),
+        "cargo metadata failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let metadata: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("cargo metadata prints JSON");
+
+    let root = &metadata["resolve"]["root"];
+    let conductor_api = metadata["resolve"]["nodes"]
+        .as_array()
+        .and_then(|nodes| nodes.iter().find(|node| node["id"] == *root))
+        .and_then(|node| node["deps"].as_array())
+        .and_then(|deps| {
+            deps.iter()
+                .find(|dep| dep["name"] == "holochain_conductor_api")
+        })
+        .map(|dep| &dep["pkg"])
+        .expect("holochain_conductor_api is a dependency of this crate");
+    metadata["packages"]
+        .as_array()
+        .and_then(|packages| {
+            packages
+                .iter()
+                .find(|package| package["id"] == *conductor_api)
+        })
+        .and_then(|package| package["version"].as_str())
+        .expect("holochain_conductor_api package in cargo metadata")
+        .to_string()
+}
+
+/// The release line of a version, within which the wire encoding must not change.
+///
+/// Before 1.0, a minor version bump is a breaking release, so `0.5.0-dev.7` and `0.5.2` belong to
+/// the line `0.5`.
+fn release_line(version: &str) -> String {
+    let mut parts = version.split(['.', '-']);
+    let major = parts.next().unwrap_or_default();
+    let minor = parts.next().unwrap_or_default();
+    if major == "0" {
+        format!("{major}.{minor}")
+    } else {
+        major.to_string()
+    }
+}
+
+/// A request and the response the conductor sent back, both encoded as hex.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Exchange {
+    request: String,
+    response: String,
+}
+
+async fn connect(port: u16) -> WebsocketSender {
+    let (tx, mut rx) = holochain_websocket::connect(
+        Arc::new(WebsocketConfig::CLIENT_DEFAULT),
+        SocketAddr::from((Ipv4Addr::LOCALHOST, port)),
+    )
+    .await
+    .unwrap();
+    // The receiver needs to be polled for responses to arrive.
+    tokio::spawn(async move { while rx.recv::<AdminResponse>().await.is_ok() {} });
+    tx
+}
+
+async fn admin_exchange(tx: &WebsocketSender, request: AdminRequest) -> Exchange {
+    let encoded = encode(&request).unwrap();
+    let response: AdminResponse = tx.request(request).await.unwrap();
+    Exchange {
+        request: to_hex(&encoded),
+        response: to_hex(&encode(&response).unwrap()),
+    }
+}
+
+async fn app_exchange(tx: &WebsocketSender, request: AppRequest) -> Exchange {
+    let encoded = encode(&request).unwrap();
+    let response: AppResponse = tx.request(request).await.unwrap();
+    Exchange {
+        request: to_hex(&encoded),
+        response: to_hex(&encode(&response).unwrap()),
+    }
+}
+
+/// Sends the requests of the transcript to a fresh conductor.
+async fn exchanges() -> BTreeMap<String, Exchange> {
+    let conductor = SweetConductor::from_standard_config().await;
+    let admin_port = conductor.get_arbitrary_admin_websocket_port().unwrap();
+    let admin_tx = connect(admin_port).await;
+
+    let mut exchanges = BTreeMap::new();
+    // Sent before any app is installed, so the responses are empty lists and errors.
+    let admin_requests = [
+        (
+            "admin/list_apps",
+            AdminRequest::ListApps {
+                status_filter: None,
+            },
+        ),
+        ("admin/list_dnas", AdminRequest::ListDnas),
+        ("admin/list_cell_ids", AdminRequest::ListCellIds),
+        (
+            "admin/enable_missing_app",
+            AdminRequest::EnableApp {
+                installed_app_id: "missing-app".into(),
+            },
+        ),
+    ];
+    for (name, request) in admin_requests {
+        let exchange = admin_exchange(&admin_tx, request).await;
+        exchanges.insert(name.to_string(), exchange);
+    }
+
+    // Set up an app to send app requests to.
+    let admin_ws = AdminWebsocket::connect((Ipv4Addr::LOCALHOST, admin_port))
+        .await
+        .unwrap();
+    let app_id: InstalledAppId = "test-app".into();
+    admin_ws
+        .install_app(InstallAppPayload {
+            agent_key: None,
+            installed_app_id: Some(app_id.clone()),
+            network_seed: None,
+            roles_settings: None,
+            source: AppBundleSource::Path(PathBuf::from("./fixture/test.happ")),
+            ignore_genesis_failure: false,
+            allow_throwaway_random_agent_key: false,
+        })
+        .await
+        .unwrap();
+    admin_ws.enable_app(app_id.clone()).await.unwrap();
+    let app_port = admin_ws
+        .attach_app_interface(0, AllowedOrigins::Any, None)
+        .await
+        .unwrap();
+    let token = admin_ws
+        .issue_app_auth_token(app_id.into())
+        .await
+        .unwrap()
+        .token;
+    let app_tx = connect(app_port).await;
+    app_tx
+        .authenticate(AppAuthenticationRequest { token })
+        .await
+        .unwrap();
+
+    let exchange = app_exchange(&app_tx, AppRequest::ListWasmHostFunctions).await;
+    exchanges.insert("app/list_wasm_host_functions".to_string(), exchange);
+
+    exchanges
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn wire_encoding_matches_transcript() {
+    let version = conductor_api_version();
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/transcripts")
+        .join(format!("{}.json", release_line(&version)));
+    let current = exchanges().await;
+
+    if std::env::var_os("RECORD_TRANSCRIPTS").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &path,
+            serde_json::to_string_pretty(&current).unwrap() + "\n",
+        )
+        .unwrap();
+        return;
+    }
+
+    let recorded = std::fs::read_to_string(&path).unwrap_or_else(|err| {
+        panic!(
+            "No transcript {} for conductor API {version} ({err}). Record it by running this test \
+             with RECORD_TRANSCRIPTS=1 and commit it.",
+            path.display()
+        )
+    });
+    let recorded: BTreeMap<String, Exchange> = serde_json::from_str(&recorded).unwrap();
+    let changed: Vec<&String> = current
+        .iter()
+        .filter(|(name, exchange)| recorded.get(*name) != Some(*exchange))
+        .map(|(name, _)| name)
+        .collect();
+    assert!(
+        changed.is_empty(),
+        "The wire encoding of {changed:?} differs from {} with conductor API {version}",
+        path.display()
+    );
+}
+
+#[test]
+fn release_lines() {
+    assert_eq!(release_line("0.5.0-dev.7"), "0.5");
+    assert_eq!(release_line("0.5.2"), "0.5");
+    assert_eq!(release_line("1.2.3"), "1");
+}